@@ -0,0 +1,263 @@
+use crate::client::MarketData;
+use crate::fees::{self, FeeSchedule};
+use crate::market::api_structs::Event;
+use crate::market::{Market, Orderbook};
+use crate::money::{Cents, Price};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+// Exactly one market of a mutually exclusive event settles yes, so a full set of yes
+// contracts always pays 100 cents and a full set of no contracts pays 100 * (legs - 1). If
+// the asks add up to less than that after fees, buying every leg locks in the difference.
+//
+// This assumes the event's markets cover every outcome. An event that can resolve with no
+// market settling yes is not safe to buy as a yes set.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArbitrageSide {
+    // Buy yes on every leg: the yes asks sum to less than 100.
+    BuyYes,
+    // Buy no on every leg, the same as selling yes at the bids: the yes bids sum to more
+    // than 100.
+    BuyNo,
+}
+
+impl ArbitrageSide {
+    pub fn side(self) -> &'static str {
+        match self {
+            ArbitrageSide::BuyYes => "yes",
+            ArbitrageSide::BuyNo => "no",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ArbitrageConfig {
+    // Every leg is bought as a taker.
+    pub fees: FeeSchedule,
+    // Only report opportunities that make more than this after fees.
+    pub min_profit: Cents,
+    pub max_sets: Option<i64>,
+}
+
+impl Default for ArbitrageConfig {
+    fn default() -> Self {
+        ArbitrageConfig {
+            fees: FeeSchedule::default(),
+            min_profit: Cents::ZERO,
+            max_sets: None,
+        }
+    }
+}
+
+// Top of book totals across an event's markets, as reported on each Market.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BestPriceSums {
+    pub yes_asks: Cents,
+    pub yes_bids: Cents,
+}
+
+pub fn best_price_sums(markets: &[Market]) -> BestPriceSums {
+    BestPriceSums {
+        yes_asks: markets.iter().map(|market| market.yes_ask).sum(),
+        yes_bids: markets.iter().map(|market| market.yes_bid).sum(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ArbitrageLeg {
+    pub ticker: String,
+    // yes or no
+    pub side: &'static str,
+    pub count: i64,
+    // Worst price taken, usable as the limit price of the order.
+    pub limit_price: Price,
+    pub cost: Cents,
+    pub fees: Cents,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ArbitrageOpportunity {
+    pub event_ticker: String,
+    pub side: ArbitrageSide,
+    // Number of complete sets, one contract on every leg each.
+    pub sets: i64,
+    pub legs: Vec<ArbitrageLeg>,
+    pub cost: Cents,
+    pub fees: Cents,
+    pub payout: Cents,
+    pub profit: Cents,
+}
+
+// Looks for arbitrage in both directions, sized by the depth of every leg's orderbook.
+// Returns nothing for events that aren't mutually exclusive or are missing an orderbook.
+pub fn find_arbitrage(
+    event: &Event,
+    markets: &[Market],
+    orderbooks: &HashMap<String, Orderbook>,
+    config: &ArbitrageConfig,
+) -> Vec<ArbitrageOpportunity> {
+    if !event.mutually_exclusive || markets.len() < 2 {
+        return Vec::new();
+    }
+    [ArbitrageSide::BuyYes, ArbitrageSide::BuyNo]
+        .into_iter()
+        .filter_map(|side| {
+            let ladders = markets
+                .iter()
+                .map(|market| {
+                    orderbooks
+                        .get(&market.ticker)
+                        .map(|orderbook| (market.ticker.as_str(), ask_ladder(orderbook, side)))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            find_side(&event.event_ticker, side, ladders, config)
+        })
+        .collect()
+}
+
+// Fetches the event and every market's orderbook, then looks for arbitrage.
+pub async fn find_event_arbitrage_async<B: MarketData + ?Sized>(
+    backend: &B,
+    event_ticker: &str,
+    depth: i32,
+    config: &ArbitrageConfig,
+) -> Result<Vec<ArbitrageOpportunity>, Box<dyn std::error::Error + Send + Sync>> {
+    let response = backend.get_event_async(event_ticker).await?;
+    if !response.event.mutually_exclusive {
+        info!(
+            "Event '{}' is not mutually exclusive, skipping",
+            event_ticker
+        );
+        return Ok(Vec::new());
+    }
+    let mut orderbooks = HashMap::new();
+    for market in &response.markets {
+        let orderbook = backend
+            .get_market_orderbook_async(&market.ticker, depth)
+            .await?
+            .orderbook;
+        orderbooks.insert(market.ticker.clone(), orderbook);
+    }
+    Ok(find_arbitrage(
+        &response.event,
+        &response.markets,
+        &orderbooks,
+        config,
+    ))
+}
+
+// The orderbook only has bids, so asks on one side are the other side's bids at the
+// complement price. Returned cheapest first.
+fn ask_ladder(orderbook: &Orderbook, side: ArbitrageSide) -> Vec<(Price, i64)> {
+    let bids = match side {
+        ArbitrageSide::BuyYes => &orderbook.no,
+        ArbitrageSide::BuyNo => &orderbook.yes,
+    };
+    let mut asks: Vec<(Price, i64)> = bids
+        .iter()
+        .filter(|level| level.len() == 2 && level[1] > 0)
+        .filter_map(|level| {
+            let bid = Price::try_from(level[0]).ok()?;
+            Some((bid.complement(), level[1] as i64))
+        })
+        .collect();
+    asks.sort();
+    asks
+}
+
+fn find_side(
+    event_ticker: &str,
+    side: ArbitrageSide,
+    mut ladders: Vec<(&str, Vec<(Price, i64)>)>,
+    config: &ArbitrageConfig,
+) -> Option<ArbitrageOpportunity> {
+    let set_payout = match side {
+        ArbitrageSide::BuyYes => 100.0,
+        ArbitrageSide::BuyNo => 100.0 * (ladders.len() - 1) as f64,
+    };
+    // Contracts taken from each leg at each price.
+    let mut taken: Vec<BTreeMap<Price, i64>> = vec![BTreeMap::new(); ladders.len()];
+    let mut positions = vec![0; ladders.len()];
+    let mut sets = 0;
+
+    loop {
+        let levels: Option<Vec<(Price, i64)>> = ladders
+            .iter()
+            .zip(&positions)
+            .map(|((_, ladder), position)| ladder.get(*position).copied())
+            .collect();
+        let Some(levels) = levels else { break };
+        // Stop once another set would lose money, using unrounded fees at the margin.
+        let set_cost: f64 = ladders
+            .iter()
+            .zip(&levels)
+            .map(|((ticker, _), (price, _))| {
+                price.value() as f64
+                    + fees::fee_per_contract(config.fees.rate(ticker, true), *price)
+            })
+            .sum();
+        if set_cost >= set_payout {
+            break;
+        }
+        let mut count = levels.iter().map(|(_, count)| *count).min().unwrap_or(0);
+        if let Some(max_sets) = config.max_sets {
+            count = count.min(max_sets - sets);
+        }
+        if count <= 0 {
+            break;
+        }
+        for (leg, (price, _)) in levels.iter().enumerate() {
+            *taken[leg].entry(*price).or_insert(0) += count;
+            let level = &mut ladders[leg].1[positions[leg]];
+            level.1 -= count;
+            if level.1 == 0 {
+                positions[leg] += 1;
+            }
+        }
+        sets += count;
+    }
+    if sets == 0 {
+        return None;
+    }
+
+    let legs: Vec<ArbitrageLeg> = ladders
+        .iter()
+        .zip(&taken)
+        .map(|((ticker, _), taken)| {
+            let worst_price = *taken.keys().next_back().expect("every leg takes a level");
+            ArbitrageLeg {
+                ticker: ticker.to_string(),
+                side: side.side(),
+                count: sets,
+                limit_price: worst_price,
+                cost: taken
+                    .iter()
+                    .map(|(price, count)| price.cents() * *count)
+                    .sum(),
+                fees: taken
+                    .iter()
+                    .map(|(price, count)| config.fees.taker_fee(ticker, *count, *price))
+                    .sum(),
+            }
+        })
+        .collect();
+    let cost: Cents = legs.iter().map(|leg| leg.cost).sum();
+    let fees: Cents = legs.iter().map(|leg| leg.fees).sum();
+    let payout = Cents(set_payout as i64 * sets);
+    let profit = payout - cost - fees;
+    if profit <= config.min_profit {
+        return None;
+    }
+    Some(ArbitrageOpportunity {
+        event_ticker: event_ticker.to_string(),
+        side,
+        sets,
+        legs,
+        cost,
+        fees,
+        payout,
+        profit,
+    })
+}
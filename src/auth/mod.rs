@@ -1,6 +1,9 @@
+use crate::environment;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::env;
+use std::fmt;
+use std::path::PathBuf;
 
 #[derive(Deserialize)]
 struct LoginResponse {
@@ -11,9 +14,102 @@ struct LoginResponse {
 const EMAIL_ENV_VAR: &str = "EMAIL";
 const PASS_ENV_VAR: &str = "PASS";
 
-const LOGIN_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/login";
-const LOGOUT_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/logout";
+const LOGIN_PATH: &str = "login";
+const LOGOUT_PATH: &str = "logout";
 
+#[derive(Clone, Deserialize)]
+pub struct Credentials {
+    pub email: String,
+    pub password: String,
+}
+
+// The password is left out so credentials can be logged safely.
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("email", &self.email)
+            .field("password", &"(hidden)")
+            .finish()
+    }
+}
+
+// Somewhere to get the email and password to log in with.
+pub trait CredentialProvider {
+    fn credentials(&self) -> Result<Credentials, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl CredentialProvider for Credentials {
+    fn credentials(&self) -> Result<Credentials, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.clone())
+    }
+}
+
+// Reads the credentials from environment variables, EMAIL and PASS by default.
+#[derive(Clone, Debug)]
+pub struct EnvCredentials {
+    pub email_var: String,
+    pub password_var: String,
+}
+
+impl Default for EnvCredentials {
+    fn default() -> Self {
+        EnvCredentials {
+            email_var: EMAIL_ENV_VAR.to_string(),
+            password_var: PASS_ENV_VAR.to_string(),
+        }
+    }
+}
+
+impl CredentialProvider for EnvCredentials {
+    fn credentials(&self) -> Result<Credentials, Box<dyn std::error::Error + Send + Sync>> {
+        let var = |name: &str| {
+            env::var(name).map_err(|_| {
+                format!(
+                    "Environment variable '{}' must be set to authenticate with kalshi.",
+                    name
+                )
+            })
+        };
+        Ok(Credentials {
+            email: var(&self.email_var)?,
+            password: var(&self.password_var)?,
+        })
+    }
+}
+
+// Reads the credentials from a JSON file like {"email": "...", "password": "..."}.
+#[derive(Clone, Debug)]
+pub struct FileCredentials {
+    pub path: PathBuf,
+}
+
+impl FileCredentials {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileCredentials { path: path.into() }
+    }
+}
+
+impl CredentialProvider for FileCredentials {
+    fn credentials(&self) -> Result<Credentials, Box<dyn std::error::Error + Send + Sync>> {
+        let contents = std::fs::read_to_string(&self.path).map_err(|e| {
+            format!(
+                "Unable to read credentials from '{}' - {}",
+                self.path.display(),
+                e
+            )
+        })?;
+        serde_json::from_str(&contents).map_err(|e| {
+            format!(
+                "Unable to parse credentials in '{}' - {}",
+                self.path.display(),
+                e
+            )
+            .into()
+        })
+    }
+}
+
+#[allow(clippy::expect_fun_call)]
 pub async fn login(
     client: &reqwest::Client,
 ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
@@ -25,12 +121,26 @@ pub async fn login(
         "Environment variable '{}' must be set to authenticate with kalshi.",
         PASS_ENV_VAR
     ));
+    login_with(
+        client,
+        &Credentials {
+            email,
+            password: pass,
+        },
+    )
+    .await
+}
 
+// Logs in with credentials from any provider, e.g. a FileCredentials.
+pub async fn login_with(
+    client: &reqwest::Client,
+    credentials: &Credentials,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
     let mut map = HashMap::new();
-    map.insert("email", email);
-    map.insert("password", pass);
+    map.insert("email", credentials.email.clone());
+    map.insert("password", credentials.password.clone());
 
-    let url = reqwest::Url::parse(LOGIN_URL)?;
+    let url = environment::api_url(LOGIN_PATH)?;
     match client
         .post(url)
         // Send the request with headers. The website guards against scraping, so these
@@ -45,7 +155,7 @@ pub async fn login(
             if result.status().is_success() {
                 match result.json::<LoginResponse>().await {
                     Ok(body) => {
-                        println!(
+                        info!(
                             "Logged in successfully. Member id - {}, token - (hidden)",
                             body.member_id
                         );
@@ -59,7 +169,7 @@ pub async fn login(
             } else {
                 let status_code = result.status();
                 let text = result.text().await?;
-                println!(
+                info!(
                     "Login unsuccessful with status {} and message {}",
                     status_code.as_str(),
                     text
@@ -82,7 +192,7 @@ pub async fn logout(
     client: reqwest::Client,
     token: &str,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let url = reqwest::Url::parse(LOGOUT_URL)?;
+    let url = environment::api_url(LOGOUT_PATH)?;
     match client
         .post(url)
         .bearer_auth(token)
@@ -103,7 +213,7 @@ pub async fn logout(
                 eprintln!("{}", error_message);
                 Err(Box::from(error_message))
             } else {
-                println!("Logout successful!");
+                info!("Logout successful!");
                 Ok(())
             }
         }
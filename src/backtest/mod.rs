@@ -0,0 +1,622 @@
+use crate::fees::FeeSchedule;
+use crate::ledger::Ledger;
+use crate::market::{Candlestick, Market, Orderbook, Trade};
+use crate::money::{Cents, Price};
+use crate::portfolio::{CreateOrderRequest, Fill, Order, Settlement};
+use crate::strategy::{OrderIntent, Strategy, StrategyContext};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const BACKTEST_USER_ID: &str = "backtest";
+
+// One piece of recorded market data, in the same types the live client returns.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MarketDataEvent {
+    Market {
+        time: DateTime<Utc>,
+        market: Market,
+    },
+    Orderbook {
+        time: DateTime<Utc>,
+        ticker: String,
+        orderbook: Orderbook,
+    },
+    Trade(Trade),
+    Candlestick {
+        ticker: String,
+        candlestick: Candlestick,
+    },
+}
+
+impl MarketDataEvent {
+    pub fn time(&self) -> DateTime<Utc> {
+        match self {
+            MarketDataEvent::Market { time, .. } => *time,
+            MarketDataEvent::Orderbook { time, .. } => *time,
+            MarketDataEvent::Trade(trade) => trade.created_time,
+            MarketDataEvent::Candlestick { candlestick, .. } => candlestick.end_period_ts,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BacktestConfig {
+    pub starting_balance: Cents,
+    pub fees: FeeSchedule,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        BacktestConfig {
+            starting_balance: Cents(100_000),
+            fees: FeeSchedule::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PnlPoint {
+    pub time: DateTime<Utc>,
+    pub balance: Cents,
+    // Cash plus open positions valued at the top of book.
+    pub equity: Cents,
+    pub realized_pnl: Cents,
+    pub unrealized_pnl: Cents,
+    pub fees: Cents,
+}
+
+#[derive(Debug)]
+pub struct BacktestReport {
+    pub starting_balance: Cents,
+    pub final_balance: Cents,
+    pub pnl_curve: Vec<PnlPoint>,
+    // Every simulated fill, in order. This is the trade log.
+    pub fills: Vec<Fill>,
+    pub orders: Vec<Order>,
+    pub settlements: Vec<Settlement>,
+    pub ledger: Ledger,
+}
+
+impl BacktestReport {
+    pub fn total_pnl(&self) -> Cents {
+        let final_equity = self
+            .pnl_curve
+            .last()
+            .map(|point| point.equity)
+            .unwrap_or(self.final_balance);
+        final_equity - self.starting_balance
+    }
+}
+
+struct SimulatedOrder {
+    order: Order,
+    // Contracts resting ahead of this order at its price level.
+    queue_ahead: i64,
+}
+
+// Replays recorded market data through a strategy. Orders are matched against the recorded
+// orderbooks; resting orders join the back of the queue at their price and are filled by
+// recorded trades once the contracts ahead of them have traded. Markets settle when a
+// Market update with a yes or no result is replayed.
+pub struct Backtester<S: Strategy> {
+    config: BacktestConfig,
+    strategy: S,
+    now: DateTime<Utc>,
+    balance: Cents,
+    ledger: Ledger,
+    orderbooks: HashMap<String, Orderbook>,
+    orders: Vec<SimulatedOrder>,
+    fills: Vec<Fill>,
+    pending_fills: VecDeque<Fill>,
+    settlements: Vec<Settlement>,
+    settled_tickers: HashSet<String>,
+    pnl_curve: Vec<PnlPoint>,
+    next_id: u64,
+}
+
+impl<S: Strategy> Backtester<S> {
+    pub fn new(strategy: S, config: BacktestConfig) -> Self {
+        Backtester {
+            balance: config.starting_balance,
+            config,
+            strategy,
+            now: DateTime::<Utc>::MIN_UTC,
+            ledger: Ledger::new(),
+            orderbooks: HashMap::new(),
+            orders: Vec::new(),
+            fills: Vec::new(),
+            pending_fills: VecDeque::new(),
+            settlements: Vec::new(),
+            settled_tickers: HashSet::new(),
+            pnl_curve: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    // Events are replayed in time order; events with the same time keep their given order.
+    pub fn run(mut self, events: impl IntoIterator<Item = MarketDataEvent>) -> (S, BacktestReport) {
+        let mut events: Vec<MarketDataEvent> = events.into_iter().collect();
+        events.sort_by_key(|event| event.time());
+        info!("Starting backtest over {} events", events.len());
+
+        for event in events {
+            self.now = event.time();
+            match event {
+                MarketDataEvent::Market { market, .. } => {
+                    self.ledger.mark(&market);
+                    if market.result == "yes" || market.result == "no" {
+                        self.settle(&market.ticker, &market.result);
+                    }
+                    self.dispatch(|strategy, context| strategy.on_market(context, &market));
+                }
+                MarketDataEvent::Orderbook {
+                    ticker, orderbook, ..
+                } => {
+                    self.orderbooks.insert(ticker.clone(), orderbook.clone());
+                    self.match_resting_orders_against_book(&ticker);
+                    self.dispatch(|strategy, context| {
+                        strategy.on_book_update(context, &ticker, &orderbook)
+                    });
+                }
+                MarketDataEvent::Trade(trade) => {
+                    self.match_resting_orders_against_trade(&trade);
+                    self.dispatch(|strategy, context| strategy.on_trade(context, &trade));
+                }
+                MarketDataEvent::Candlestick {
+                    ticker,
+                    candlestick,
+                } => {
+                    self.dispatch(|strategy, context| {
+                        strategy.on_candlestick(context, &ticker, &candlestick)
+                    });
+                }
+            }
+            self.record_pnl();
+        }
+
+        let report = BacktestReport {
+            starting_balance: self.config.starting_balance,
+            final_balance: self.balance,
+            pnl_curve: self.pnl_curve,
+            fills: self.fills,
+            orders: self.orders.into_iter().map(|order| order.order).collect(),
+            settlements: self.settlements,
+            ledger: self.ledger,
+        };
+        info!(
+            "Backtest finished with {} fills and total P&L {}",
+            report.fills.len(),
+            report.total_pnl()
+        );
+        (self.strategy, report)
+    }
+
+    // Runs a strategy handler, applies the orders it queued and then delivers any resulting
+    // fills, which may queue more orders in turn.
+    fn dispatch(&mut self, handler: impl FnOnce(&mut S, &mut StrategyContext)) {
+        let mut context = self.context();
+        handler(&mut self.strategy, &mut context);
+        self.apply_intents(context.take_intents());
+
+        while let Some(fill) = self.pending_fills.pop_front() {
+            let mut context = self.context();
+            self.strategy.on_fill(&mut context, &fill);
+            self.apply_intents(context.take_intents());
+        }
+    }
+
+    fn context(&self) -> StrategyContext {
+        let positions = self
+            .ledger
+            .markets()
+            .map(|market| (market.ticker.clone(), market.position))
+            .collect();
+        let open_orders = self
+            .orders
+            .iter()
+            .filter(|order| order.order.status == "resting")
+            .map(|order| order.order.clone())
+            .collect();
+        StrategyContext::new(self.now, self.balance, positions, open_orders)
+    }
+
+    fn apply_intents(&mut self, intents: Vec<OrderIntent>) {
+        for intent in intents {
+            match intent {
+                OrderIntent::Create(request) => {
+                    if let Err(e) = self.create_order(&request) {
+                        info!("Backtest rejected order {:?}: {}", request, e);
+                    }
+                }
+                OrderIntent::Cancel(order_id) => self.cancel_order(&order_id),
+            }
+        }
+    }
+
+    fn create_order(&mut self, request: &CreateOrderRequest) -> Result<(), String> {
+        if request.count <= 0 {
+            return Err("count must be positive".to_string());
+        }
+        if self.settled_tickers.contains(&request.ticker) {
+            return Err(format!("market '{}' has settled", request.ticker));
+        }
+        let is_market_order = request.type_ == "market";
+        let yes_price = match (request.yes_price, request.no_price) {
+            (Some(yes_price), _) => yes_price,
+            (None, Some(no_price)) => no_price.complement(),
+            // Market orders without a price are treated as limit orders at the most
+            // aggressive price: buys pay up to 99 cents and sells accept down to 1 cent.
+            (None, None) if is_market_order => {
+                let limit = if request.action == "buy" {
+                    Price::MAX
+                } else {
+                    Price::MIN
+                };
+                if request.side == "yes" {
+                    limit
+                } else {
+                    limit.complement()
+                }
+            }
+            (None, None) => return Err("limit orders need a price".to_string()),
+        };
+
+        let mut order = Order {
+            action: request.action.clone(),
+            client_order_id: request.client_order_id.clone(),
+            close_cancel_count: 0,
+            created_time: self.now,
+            decrease_count: 0,
+            expiration_time: request.expiration_ts,
+            fcc_cancel_count: 0,
+            last_update_time: self.now,
+            maker_fill_count: 0,
+            maker_fill_cost: Cents::ZERO,
+            maker_fees: Cents::ZERO,
+            no_price: yes_price.complement(),
+            order_id: format!("backtest-order-{}", self.next_id),
+            place_count: request.count,
+            queue_position: 0,
+            remaining_count: request.count,
+            side: request.side.clone(),
+            status: "resting".to_string(),
+            taker_fees: Cents::ZERO,
+            taker_fill_cost: Cents::ZERO,
+            taker_fill_count: 0,
+            ticker: request.ticker.clone(),
+            type_: request.type_.clone(),
+            user_id: BACKTEST_USER_ID.to_string(),
+            yes_price,
+        };
+        let (book_side, price) = book_position(&order);
+        let cost = Cents(price as i64) * self.opening_count(&order);
+        if cost > self.balance {
+            return Err(format!(
+                "insufficient balance {} for cost {}",
+                self.balance, cost
+            ));
+        }
+        self.next_id += 1;
+
+        // Take whatever the book offers, best price first.
+        let mut executions = Vec::new();
+        if let Some(orderbook) = self.orderbooks.get_mut(&order.ticker) {
+            let mut remaining = order.remaining_count;
+            let levels = side_levels_mut(orderbook, opposite(book_side));
+            levels.sort_by_key(|level| -level_price(level));
+            for level in levels.iter_mut().filter(|level| level.len() == 2) {
+                if remaining == 0 || level[0] < 100 - price {
+                    break;
+                }
+                let count = remaining.min(level[1]);
+                if count > 0 {
+                    executions.push((100 - level[0], count));
+                    level[1] -= count;
+                    remaining -= count;
+                }
+            }
+        }
+        let queue_ahead = self
+            .orderbooks
+            .get(&order.ticker)
+            .map(|orderbook| level_size(side_levels(orderbook, book_side), price))
+            .unwrap_or(0);
+        order.queue_position = queue_ahead as i32;
+        self.orders.push(SimulatedOrder { order, queue_ahead });
+        let index = self.orders.len() - 1;
+        for (fill_price, count) in executions {
+            self.execute(index, fill_price, count, true);
+        }
+
+        let order = &mut self.orders[index].order;
+        if is_market_order && order.remaining_count > 0 {
+            order.close_cancel_count += order.remaining_count;
+            order.remaining_count = 0;
+            order.status = "canceled".to_string();
+        }
+        Ok(())
+    }
+
+    // Contracts of the order that open or add to a position rather than closing one.
+    fn opening_count(&self, order: &Order) -> i64 {
+        let position = self
+            .ledger
+            .market(&order.ticker)
+            .map(|market| market.position)
+            .unwrap_or(0);
+        let buys_yes = (order.side == "yes") == (order.action == "buy");
+        let closing = if buys_yes {
+            (-position).max(0)
+        } else {
+            position.max(0)
+        };
+        (order.remaining_count as i64 - closing).max(0)
+    }
+
+    fn cancel_order(&mut self, order_id: &str) {
+        if let Some(order) = self
+            .orders
+            .iter_mut()
+            .map(|order| &mut order.order)
+            .find(|order| order.order_id == order_id && order.status == "resting")
+        {
+            order.remaining_count = 0;
+            order.status = "canceled".to_string();
+            order.last_update_time = self.now;
+        }
+    }
+
+    // Resting orders are filled when a new snapshot crosses them, and lose queue position
+    // when the size ahead of them shrinks.
+    fn match_resting_orders_against_book(&mut self, ticker: &str) {
+        let Some(mut orderbook) = self.orderbooks.remove(ticker) else {
+            return;
+        };
+        let mut executions = Vec::new();
+        for index in self.resting_order_indices(ticker) {
+            let simulated = &mut self.orders[index];
+            let (book_side, price) = book_position(&simulated.order);
+            simulated.queue_ahead = simulated
+                .queue_ahead
+                .min(level_size(side_levels(&orderbook, book_side), price));
+
+            let mut remaining = simulated.order.remaining_count;
+            let levels = side_levels_mut(&mut orderbook, opposite(book_side));
+            levels.sort_by_key(|level| -level_price(level));
+            for level in levels.iter_mut().filter(|level| level.len() == 2) {
+                if remaining == 0 || level[0] < 100 - price {
+                    break;
+                }
+                let count = remaining.min(level[1]);
+                if count > 0 {
+                    executions.push((index, price, count));
+                    level[1] -= count;
+                    remaining -= count;
+                }
+            }
+        }
+        self.orderbooks.insert(ticker.to_string(), orderbook);
+        for (index, price, count) in executions {
+            self.execute(index, price, count, false);
+        }
+    }
+
+    // A trade where the taker bought yes traded against resting no bids and vice versa.
+    // Orders priced better than the trade would have filled first; orders at the trade
+    // price fill once the contracts queued ahead of them are used up.
+    fn match_resting_orders_against_trade(&mut self, trade: &Trade) {
+        let resting_side = opposite(&trade.taker_side);
+        let trade_price = if resting_side == "yes" {
+            trade.yes_price.value() as i32
+        } else {
+            trade.no_price.value() as i32
+        };
+        let mut remaining = trade.count as i64;
+        let mut executions = Vec::new();
+        for index in self.resting_order_indices(&trade.ticker) {
+            let simulated = &mut self.orders[index];
+            let (book_side, price) = book_position(&simulated.order);
+            if book_side != resting_side || price < trade_price || remaining == 0 {
+                continue;
+            }
+            if price == trade_price {
+                let traded_ahead = simulated.queue_ahead.min(remaining);
+                simulated.queue_ahead -= traded_ahead;
+                remaining -= traded_ahead;
+            }
+            let count = remaining.min(simulated.order.remaining_count as i64);
+            if count > 0 {
+                executions.push((index, price, count as i32));
+                remaining -= count;
+            }
+        }
+        for (index, price, count) in executions {
+            self.execute(index, price, count, false);
+        }
+    }
+
+    // Resting orders for the ticker in priority order: best price first, then oldest.
+    fn resting_order_indices(&self, ticker: &str) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .orders
+            .iter()
+            .enumerate()
+            .filter(|(_, order)| order.order.status == "resting" && order.order.ticker == ticker)
+            .map(|(index, _)| index)
+            .collect();
+        indices.sort_by_key(|index| -book_position(&self.orders[*index].order).1);
+        indices
+    }
+
+    // Fills count contracts of the order at price, given on the order's book side.
+    fn execute(&mut self, index: usize, price: i32, count: i32, is_taker: bool) {
+        let order = &mut self.orders[index].order;
+        let (book_side, _) = book_position(order);
+        let yes_price = if book_side == "yes" {
+            price
+        } else {
+            100 - price
+        };
+        let Ok(yes_price) = Price::try_from(yes_price as i64) else {
+            return;
+        };
+        let fee = self
+            .config
+            .fees
+            .fee(&order.ticker, count as i64, yes_price, is_taker);
+
+        order.remaining_count -= count;
+        if is_taker {
+            order.taker_fill_count += count as i64;
+            order.taker_fill_cost += Cents(price as i64) * count as i64;
+            order.taker_fees += fee;
+        } else {
+            order.maker_fill_count += count;
+            order.maker_fill_cost += Cents(price as i64) * count as i64;
+            order.maker_fees += fee;
+        }
+        if order.remaining_count == 0 {
+            order.status = "executed".to_string();
+        }
+        order.last_update_time = self.now;
+
+        let fill = Fill {
+            action: order.action.clone(),
+            count,
+            created_time: self.now,
+            is_taker,
+            no_price: yes_price.complement(),
+            order_id: order.order_id.clone(),
+            side: order.side.clone(),
+            ticker: order.ticker.clone(),
+            trade_id: format!("backtest-trade-{}", self.next_id),
+            yes_price,
+        };
+        self.next_id += 1;
+
+        // Same cash accounting as paper trading: the change in cost of open positions plus
+        // realized P&L, less fees.
+        let before = self
+            .ledger
+            .market(&fill.ticker)
+            .cloned()
+            .unwrap_or_default();
+        self.ledger.apply_fill(&fill);
+        self.ledger.apply_fee(&fill.ticker, fee);
+        let after = self
+            .ledger
+            .market(&fill.ticker)
+            .cloned()
+            .unwrap_or_default();
+        self.balance += (before.total_cost() - after.total_cost())
+            + (after.realized_pnl - before.realized_pnl)
+            - fee;
+        self.fills.push(fill.clone());
+        self.pending_fills.push_back(fill);
+    }
+
+    fn settle(&mut self, ticker: &str, result: &str) {
+        if !self.settled_tickers.insert(ticker.to_string()) {
+            return;
+        }
+        for index in self.resting_order_indices(ticker) {
+            let order = &mut self.orders[index].order;
+            order.remaining_count = 0;
+            order.status = "canceled".to_string();
+            order.last_update_time = self.now;
+        }
+        let Some(market) = self.ledger.market(ticker) else {
+            return;
+        };
+        if market.position == 0 {
+            return;
+        }
+        let yes_count = market.position.max(0);
+        let no_count = (-market.position).max(0);
+        let (yes_total_cost, no_total_cost) = if market.position > 0 {
+            (market.total_cost(), Cents::ZERO)
+        } else {
+            (Cents::ZERO, market.total_cost())
+        };
+        let winning_count = if result == "yes" { yes_count } else { no_count };
+        let settlement = Settlement {
+            market_result: result.to_string(),
+            no_count,
+            no_total_cost,
+            revenue: Cents(100) * winning_count,
+            settled_time: self.now,
+            ticker: ticker.to_string(),
+            yes_count,
+            yes_total_cost,
+        };
+        self.ledger.apply_settlement(&settlement);
+        self.balance += settlement.revenue;
+        self.settlements.push(settlement);
+    }
+
+    fn record_pnl(&mut self) {
+        let open_cost: Cents = self
+            .ledger
+            .markets()
+            .filter(|market| !market.settled)
+            .map(|market| market.total_cost())
+            .sum();
+        let unrealized_pnl = self.ledger.total_unrealized_pnl();
+        self.pnl_curve.push(PnlPoint {
+            time: self.now,
+            balance: self.balance,
+            equity: self.balance + open_cost + unrealized_pnl,
+            realized_pnl: self.ledger.total_realized_pnl(),
+            unrealized_pnl,
+            fees: self.ledger.total_fees(),
+        });
+    }
+}
+
+// Where the order rests in a bids-only orderbook: buying yes (or selling no) is a yes bid
+// at the yes price, and buying no (or selling yes) is a no bid at the no price.
+fn book_position(order: &Order) -> (&'static str, i32) {
+    if (order.side == "yes") == (order.action == "buy") {
+        ("yes", order.yes_price.value() as i32)
+    } else {
+        ("no", order.no_price.value() as i32)
+    }
+}
+
+fn opposite(side: &str) -> &'static str {
+    if side == "yes" {
+        "no"
+    } else {
+        "yes"
+    }
+}
+
+fn side_levels<'a>(orderbook: &'a Orderbook, side: &str) -> &'a Vec<Vec<i32>> {
+    if side == "yes" {
+        &orderbook.yes
+    } else {
+        &orderbook.no
+    }
+}
+
+fn side_levels_mut<'a>(orderbook: &'a mut Orderbook, side: &str) -> &'a mut Vec<Vec<i32>> {
+    if side == "yes" {
+        &mut orderbook.yes
+    } else {
+        &mut orderbook.no
+    }
+}
+
+fn level_price(level: &[i32]) -> i32 {
+    level.first().copied().unwrap_or(0)
+}
+
+fn level_size(levels: &[Vec<i32>], price: i32) -> i64 {
+    levels
+        .iter()
+        .filter(|level| level.len() == 2 && level[0] == price)
+        .map(|level| level[1] as i64)
+        .sum()
+}
@@ -1,9 +1,26 @@
-use crate::market::Market;
+use crate::fees;
+use crate::market::{Market, Orderbook};
+use crate::money::{Cents, Price};
 
 #[derive(Debug)]
 pub struct Spread {
-    pub ask: i32,
-    pub bid: i32,
+    pub ask: Cents,
+    pub bid: Cents,
+}
+
+impl Spread {
+    pub fn width(&self) -> Cents {
+        self.ask - self.bid
+    }
+
+    pub fn mid(&self) -> f64 {
+        (self.ask + self.bid).value() as f64 / 2.0
+    }
+
+    // Chance of this side settling yes that the market is pricing in, from the mid.
+    pub fn implied_probability(&self) -> f64 {
+        self.mid() / 100.0
+    }
 }
 
 #[derive(Debug)]
@@ -12,7 +29,72 @@ pub struct Bids {
     pub yes: Spread,
 }
 
-// TODO: Add some market stats here about what values would be worthwhile to buy, etc.
+impl Bids {
+    // How much buying both sides at the asks costs over the 100 cents it is sure to pay,
+    // as a fraction of the payout. Zero for a book with no spread.
+    pub fn overround(&self) -> f64 {
+        (self.yes.ask + self.no.ask).value() as f64 / 100.0 - 1.0
+    }
+}
+
+// scanner::scan ranks markets by these and other stats to find ones worth buying.
+
+// Mid weighted towards the side with less size, which is where the price is more likely to
+// move next, as a probability. Falls back to the mid when neither side has any size.
+pub fn microprice(bid: Cents, bid_size: i64, ask: Cents, ask_size: i64) -> f64 {
+    let (bid, ask) = (bid.value() as f64, ask.value() as f64);
+    let total = bid_size + ask_size;
+    let price = if total > 0 {
+        (bid * ask_size as f64 + ask * bid_size as f64) / total as f64
+    } else {
+        (bid + ask) / 2.0
+    };
+    price / 100.0
+}
+
+// Microprice of yes from the top of the orderbook. The yes ask is the complement of the
+// best no bid. Returns None when either side of the book is empty.
+pub fn orderbook_microprice(orderbook: &Orderbook) -> Option<f64> {
+    let best = |levels: &[Vec<i32>]| {
+        levels
+            .iter()
+            .filter(|level| level.len() == 2 && level[1] > 0)
+            .map(|level| (level[0], level[1]))
+            .max()
+    };
+    let (yes_bid, yes_size) = best(&orderbook.yes)?;
+    let (no_bid, no_size) = best(&orderbook.no)?;
+    Some(microprice(
+        Cents(yes_bid as i64),
+        yes_size as i64,
+        Cents(100 - no_bid as i64),
+        no_size as i64,
+    ))
+}
+
+// The functions below price buying one contract at price as a taker, given fee_rate (see
+// fees::FeeSchedule::rate) and the probability the buyer believes the contract settles in
+// their favour. For a no contract that is 1 - the probability of yes.
+
+// Profit in cents the buyer expects per contract, after fees.
+pub fn expected_value(probability: f64, price: Price, fee_rate: f64) -> f64 {
+    probability * 100.0 - price.value() as f64 - fees::fee_per_contract(fee_rate, price)
+}
+
+// Probability above which buying at price has a positive expected value after fees.
+pub fn break_even_probability(price: Price, fee_rate: f64) -> f64 {
+    (price.value() as f64 + fees::fee_per_contract(fee_rate, price)) / 100.0
+}
+
+// Fraction of the bankroll the Kelly criterion stakes on buying at price. A contract costing
+// c (fees included) that pays 1 gives f = (p - c) / (1 - c). Zero when there is no edge.
+pub fn kelly_fraction(probability: f64, price: Price, fee_rate: f64) -> f64 {
+    let cost = break_even_probability(price, fee_rate);
+    if cost >= 1.0 || probability <= cost {
+        return 0.0;
+    }
+    (probability - cost) / (1.0 - cost)
+}
 
 impl From<Market> for Bids {
     fn from(item: Market) -> Self {
@@ -0,0 +1,314 @@
+use chrono::Utc;
+use kalshi_api::market::{self, Market, Orderbook};
+use kalshi_api::money::{Cents, Price};
+use kalshi_api::portfolio::{self, MarketPosition, Order};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Watchlist,
+    Orders,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FormField {
+    Action,
+    Side,
+    Count,
+    Price,
+}
+
+impl FormField {
+    fn next(self) -> Self {
+        match self {
+            FormField::Action => FormField::Side,
+            FormField::Side => FormField::Count,
+            FormField::Count => FormField::Price,
+            FormField::Price => FormField::Action,
+        }
+    }
+}
+
+// A limit order being entered for the selected market.
+pub struct OrderForm {
+    pub ticker: String,
+    pub action: &'static str,
+    pub side: &'static str,
+    pub count: String,
+    pub price: String,
+    pub field: FormField,
+}
+
+impl OrderForm {
+    fn new(ticker: &str, price: Option<Cents>) -> Self {
+        OrderForm {
+            ticker: ticker.to_string(),
+            action: "buy",
+            side: "yes",
+            count: "1".to_string(),
+            price: price
+                .map(|price| price.value().to_string())
+                .unwrap_or_default(),
+            field: FormField::Action,
+        }
+    }
+
+    pub fn next_field(&mut self) {
+        self.field = self.field.next();
+    }
+
+    // Flips buy/sell or yes/no, depending on the field.
+    pub fn toggle(&mut self) {
+        match self.field {
+            FormField::Action => {
+                self.action = if self.action == "buy" { "sell" } else { "buy" };
+            }
+            FormField::Side => self.side = if self.side == "yes" { "no" } else { "yes" },
+            FormField::Count | FormField::Price => {}
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        if !c.is_ascii_digit() {
+            return;
+        }
+        match self.field {
+            FormField::Count => self.count.push(c),
+            FormField::Price => self.price.push(c),
+            FormField::Action | FormField::Side => {}
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        match self.field {
+            FormField::Count => {
+                self.count.pop();
+            }
+            FormField::Price => {
+                self.price.pop();
+            }
+            FormField::Action | FormField::Side => {}
+        }
+    }
+
+    fn parse(&self) -> Result<(i32, Price), String> {
+        let count: i32 = self
+            .count
+            .parse()
+            .map_err(|_| format!("Invalid count '{}'", self.count))?;
+        if count <= 0 {
+            return Err("Count must be positive".to_string());
+        }
+        let price: i64 = self
+            .price
+            .parse()
+            .map_err(|_| format!("Invalid price '{}'", self.price))?;
+        let price = Price::try_from(price).map_err(|e| e.to_string())?;
+        Ok((count, price))
+    }
+}
+
+pub struct App {
+    client: reqwest::Client,
+    token: String,
+    pub watchlist: Vec<String>,
+    pub markets: HashMap<String, Market>,
+    pub orderbook: Option<Orderbook>,
+    pub orderbook_depth: i32,
+    pub positions: Vec<MarketPosition>,
+    pub open_orders: Vec<Order>,
+    pub focus: Focus,
+    pub selected_market: usize,
+    pub selected_order: usize,
+    pub form: Option<OrderForm>,
+    // Last thing that happened, shown in the status bar.
+    pub message: String,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn new(
+        client: reqwest::Client,
+        token: &str,
+        watchlist: Vec<String>,
+        orderbook_depth: i32,
+    ) -> Self {
+        App {
+            client,
+            token: token.to_string(),
+            watchlist,
+            markets: HashMap::new(),
+            orderbook: None,
+            orderbook_depth,
+            positions: Vec::new(),
+            open_orders: Vec::new(),
+            focus: Focus::Watchlist,
+            selected_market: 0,
+            selected_order: 0,
+            form: None,
+            message: String::new(),
+            should_quit: false,
+        }
+    }
+
+    pub fn selected_ticker(&self) -> Option<&str> {
+        self.watchlist.get(self.selected_market).map(String::as_str)
+    }
+
+    // Reloads everything on screen. A failure is shown in the status bar and the previous
+    // data is kept.
+    pub async fn refresh(&mut self) {
+        if let Err(e) = self.refresh_async().await {
+            self.message = format!("Refresh failed - {}", e);
+        }
+    }
+
+    async fn refresh_async(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        for ticker in &self.watchlist {
+            let market = market::get_market_async(&self.client, ticker, &self.token)
+                .await?
+                .market;
+            self.markets.insert(ticker.clone(), market);
+        }
+        self.orderbook = match self.selected_ticker() {
+            Some(ticker) => Some(
+                market::get_market_orderbook_async(
+                    &self.client,
+                    ticker,
+                    self.orderbook_depth,
+                    &self.token,
+                )
+                .await?
+                .orderbook,
+            ),
+            None => None,
+        };
+        self.positions =
+            portfolio::get_positions_async(&self.client, &self.token, None, None, None, None, None)
+                .await?
+                .market_positions
+                .into_iter()
+                .filter(|position| position.position != 0 || position.resting_order_count > 0)
+                .collect();
+        self.open_orders = portfolio::get_orders_async(
+            &self.client,
+            &self.token,
+            None,
+            None,
+            None,
+            None,
+            Some("resting"),
+            None,
+            None,
+        )
+        .await?
+        .orders;
+        self.selected_order = self
+            .selected_order
+            .min(self.open_orders.len().saturating_sub(1));
+        Ok(())
+    }
+
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Watchlist => Focus::Orders,
+            Focus::Orders => Focus::Watchlist,
+        };
+    }
+
+    // Moves the selection in the focused pane. Selecting another market fetches its
+    // orderbook straight away.
+    pub async fn move_selection(&mut self, down: bool) {
+        let (selected, len) = match self.focus {
+            Focus::Watchlist => (&mut self.selected_market, self.watchlist.len()),
+            Focus::Orders => (&mut self.selected_order, self.open_orders.len()),
+        };
+        let previous = *selected;
+        if down {
+            *selected = (*selected + 1).min(len.saturating_sub(1));
+        } else {
+            *selected = selected.saturating_sub(1);
+        }
+        if self.focus == Focus::Watchlist && *selected != previous {
+            self.refresh().await;
+        }
+    }
+
+    // Opens the order form for the selected market, priced at the yes bid.
+    pub fn open_form(&mut self) {
+        if let Some(ticker) = self.selected_ticker() {
+            let price = self.markets.get(ticker).map(|market| market.yes_bid);
+            self.form = Some(OrderForm::new(ticker, price));
+        }
+    }
+
+    pub async fn submit_form(&mut self) {
+        let form = match self.form.take() {
+            Some(form) => form,
+            None => return,
+        };
+        let (count, price) = match form.parse() {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.message = e;
+                self.form = Some(form);
+                return;
+            }
+        };
+        let (yes_price, no_price) = if form.side == "yes" {
+            (Some(price), None)
+        } else {
+            (None, Some(price))
+        };
+        let client_order_id = format!("kalshi-tui-{}", Utc::now().timestamp_millis());
+        self.message = match portfolio::create_order_async(
+            &self.client,
+            &self.token,
+            form.action,
+            None,
+            &client_order_id,
+            count,
+            None,
+            no_price,
+            None,
+            form.side,
+            &form.ticker,
+            "limit",
+            yes_price,
+        )
+        .await
+        {
+            Ok(response) => format!(
+                "Placed {} {} {} x{} at {} - {}",
+                form.action, form.side, form.ticker, count, price, response.order.status
+            ),
+            Err(e) => format!("Order failed - {}", e),
+        };
+        self.refresh().await;
+    }
+
+    pub async fn cancel_selected_order(&mut self) {
+        let order_id = match self.open_orders.get(self.selected_order) {
+            Some(order) => order.order_id.clone(),
+            None => return,
+        };
+        self.message =
+            match portfolio::cancel_order_async(&self.client, &self.token, &order_id).await {
+                Ok(_) => format!("Cancelled order {}", order_id),
+                Err(e) => format!("Cancel failed - {}", e),
+            };
+        self.refresh().await;
+    }
+
+    // Value of the position if it were sold at the current bid, when the market is on the
+    // watchlist.
+    pub fn mark_value(&self, position: &MarketPosition) -> Option<Cents> {
+        let market = self.markets.get(&position.ticker)?;
+        let bid = if position.position >= 0 {
+            market.yes_bid
+        } else {
+            market.no_bid
+        };
+        Some(bid * position.position.abs())
+    }
+}
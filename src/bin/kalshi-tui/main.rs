@@ -0,0 +1,110 @@
+mod app;
+mod ui;
+
+use app::{App, Focus};
+use clap::Parser;
+use kalshi_api::{auth, environment};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use std::time::{Duration, Instant};
+
+// Terminal dashboard for discretionary trading: a watchlist with bids, a depth ladder for
+// the selected market, positions with P&L, and open orders that can be cancelled. Logs in
+// with the EMAIL and PASS environment variables. Request errors are written to stderr, so
+// redirect it, e.g. `kalshi-tui TICKER 2>kalshi-tui.log`.
+#[derive(Parser)]
+#[command(name = "kalshi-tui", about = "Terminal dashboard for Kalshi markets")]
+struct Args {
+    /// Market tickers to watch
+    #[arg(required = true)]
+    tickers: Vec<String>,
+
+    /// Use the demo exchange
+    #[arg(long)]
+    demo: bool,
+
+    /// Send requests to this base URL instead
+    #[arg(long)]
+    base_url: Option<String>,
+
+    /// Seconds between refreshes
+    #[arg(long, default_value_t = 2)]
+    refresh: u64,
+
+    #[arg(long, default_value_t = 10)]
+    depth: i32,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let args = Args::parse();
+    environment::set_logging_enabled(false);
+    match (&args.base_url, args.demo) {
+        (Some(base_url), _) => environment::set_default_api_base_url(base_url),
+        (None, true) => environment::set_default_api_base_url(environment::DEMO_API_URL),
+        (None, false) => {}
+    }
+
+    let client = reqwest::Client::new();
+    let token = auth::login(&client).await?;
+    let mut app = App::new(client.clone(), &token, args.tickers, args.depth);
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app, Duration::from_secs(args.refresh)).await;
+    ratatui::restore();
+
+    if let Err(e) = auth::logout(client, &token).await {
+        eprintln!("Error while logging out - {}", e);
+    }
+    result
+}
+
+async fn run(
+    terminal: &mut ratatui::DefaultTerminal,
+    app: &mut App,
+    refresh_interval: Duration,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    app.refresh().await;
+    let mut last_refresh = Instant::now();
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        // Only blocks this task briefly, so refreshes stay on schedule.
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    handle_key(app, key).await;
+                }
+            }
+        }
+        if last_refresh.elapsed() >= refresh_interval {
+            app.refresh().await;
+            last_refresh = Instant::now();
+        }
+    }
+    Ok(())
+}
+
+async fn handle_key(app: &mut App, key: KeyEvent) {
+    if let Some(form) = app.form.as_mut() {
+        match key.code {
+            KeyCode::Esc => app.form = None,
+            KeyCode::Enter => app.submit_form().await,
+            KeyCode::Tab => form.next_field(),
+            KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right => form.toggle(),
+            KeyCode::Backspace => form.pop_char(),
+            KeyCode::Char(c) => form.push_char(c),
+            _ => {}
+        }
+        return;
+    }
+
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
+        KeyCode::Tab => app.toggle_focus(),
+        KeyCode::Up | KeyCode::Char('k') => app.move_selection(false).await,
+        KeyCode::Down | KeyCode::Char('j') => app.move_selection(true).await,
+        KeyCode::Char('n') => app.open_form(),
+        KeyCode::Char('c') if app.focus == Focus::Orders => app.cancel_selected_order().await,
+        KeyCode::Char('r') => app.refresh().await,
+        _ => {}
+    }
+}
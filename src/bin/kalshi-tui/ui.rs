@@ -0,0 +1,243 @@
+use crate::app::{App, Focus, FormField, OrderForm};
+use kalshi_api::bids::Bids;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use std::cmp::Reverse;
+
+const HELP: &str =
+    "q quit  tab switch pane  up/down select  n new order  c cancel order  r refresh";
+const FORM_HELP: &str = "tab next field  space toggle  enter submit  esc close";
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [main, status, help] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [left, right] =
+        Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(main);
+    let [watchlist, positions] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(left);
+    let [ladder, orders] =
+        Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(right);
+
+    draw_watchlist(frame, app, watchlist);
+    draw_positions(frame, app, positions);
+    draw_ladder(frame, app, ladder);
+    draw_orders(frame, app, orders);
+    frame.render_widget(Paragraph::new(app.message.as_str()), status);
+    let help_text = if app.form.is_some() { FORM_HELP } else { HELP };
+    frame.render_widget(
+        Paragraph::new(help_text).style(Style::default().fg(Color::DarkGray)),
+        help,
+    );
+
+    if let Some(form) = &app.form {
+        draw_form(frame, form);
+    }
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let style = if focused {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+}
+
+fn header(cells: &[&'static str]) -> Row<'static> {
+    Row::new(cells.to_vec()).style(Style::default().add_modifier(Modifier::BOLD))
+}
+
+fn highlight() -> Style {
+    Style::default().add_modifier(Modifier::REVERSED)
+}
+
+fn draw_watchlist(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app
+        .watchlist
+        .iter()
+        .map(|ticker| match app.markets.get(ticker) {
+            Some(market) => {
+                let bids: Bids = market.clone().into();
+                Row::new(vec![
+                    ticker.clone(),
+                    bids.yes.bid.value().to_string(),
+                    bids.yes.ask.value().to_string(),
+                    bids.no.bid.value().to_string(),
+                    bids.no.ask.value().to_string(),
+                    market.last_price.value().to_string(),
+                    market.volume.to_string(),
+                ])
+            }
+            None => Row::new(vec![ticker.clone(), "-".to_string()]),
+        });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(16),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Length(5),
+            Constraint::Length(8),
+        ],
+    )
+    .header(header(&[
+        "ticker", "yes bid", "yes ask", "no bid", "no ask", "last", "volume",
+    ]))
+    .block(pane("Watchlist", app.focus == Focus::Watchlist))
+    .row_highlight_style(highlight());
+    let mut state = TableState::default().with_selected(Some(app.selected_market));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_positions(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.positions.iter().map(|position| {
+        let mark = app.mark_value(position);
+        Row::new(vec![
+            position.ticker.clone(),
+            position.position.to_string(),
+            position.market_exposure.to_string(),
+            mark.map(|mark| (mark - position.market_exposure).to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            position.realized_pnl.to_string(),
+            position.fees_paid.to_string(),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(16),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(8),
+        ],
+    )
+    .header(header(&[
+        "ticker",
+        "position",
+        "exposure",
+        "unrealized",
+        "realized",
+        "fees",
+    ]))
+    .block(pane("Positions and P&L", false));
+    frame.render_widget(table, area);
+}
+
+// Yes asks are the complement of the no bids, so both sides fit on one price ladder.
+fn draw_ladder(frame: &mut Frame, app: &App, area: Rect) {
+    let title = format!("Depth {}", app.selected_ticker().unwrap_or(""));
+    let mut asks: Vec<(i32, i32)> = Vec::new();
+    let mut bids: Vec<(i32, i32)> = Vec::new();
+    if let Some(orderbook) = &app.orderbook {
+        for level in orderbook.no.iter().filter(|level| level.len() == 2) {
+            asks.push((100 - level[0], level[1]));
+        }
+        for level in orderbook.yes.iter().filter(|level| level.len() == 2) {
+            bids.push((level[0], level[1]));
+        }
+    }
+    // Highest price first, so the best ask sits just above the best bid.
+    asks.sort_by_key(|(price, _)| Reverse(*price));
+    bids.sort_by_key(|(price, _)| Reverse(*price));
+
+    let ask_rows = asks.into_iter().map(|(price, count)| {
+        Row::new(vec![String::new(), price.to_string(), count.to_string()])
+            .style(Style::default().fg(Color::Red))
+    });
+    let bid_rows = bids.into_iter().map(|(price, count)| {
+        Row::new(vec![count.to_string(), price.to_string(), String::new()])
+            .style(Style::default().fg(Color::Green))
+    });
+    let table = Table::new(
+        ask_rows.chain(bid_rows),
+        [
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header(&["yes bids", "price", "yes asks"]))
+    .block(pane(&title, false));
+    frame.render_widget(table, area);
+}
+
+fn draw_orders(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.open_orders.iter().map(|order| {
+        let price = if order.side == "yes" {
+            order.yes_price
+        } else {
+            order.no_price
+        };
+        Row::new(vec![
+            order.ticker.clone(),
+            order.action.clone(),
+            order.side.clone(),
+            price.value().to_string(),
+            order.remaining_count.to_string(),
+        ])
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(16),
+            Constraint::Length(6),
+            Constraint::Length(4),
+            Constraint::Length(6),
+            Constraint::Length(9),
+        ],
+    )
+    .header(header(&["ticker", "action", "side", "price", "remaining"]))
+    .block(pane("Open orders", app.focus == Focus::Orders))
+    .row_highlight_style(highlight());
+    let selected =
+        (app.focus == Focus::Orders && !app.open_orders.is_empty()).then_some(app.selected_order);
+    let mut state = TableState::default().with_selected(selected);
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_form(frame: &mut Frame, form: &OrderForm) {
+    let area = centered(frame.area(), 40, 8);
+    let field = |name: &str, value: &str, this: FormField| {
+        let style = if form.field == this {
+            highlight()
+        } else {
+            Style::default()
+        };
+        Line::styled(format!("{:<8}{}", name, value), style)
+    };
+    let lines = vec![
+        field("action", form.action, FormField::Action),
+        field("side", form.side, FormField::Side),
+        field("count", &form.count, FormField::Count),
+        field("price", &form.price, FormField::Price),
+    ];
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(pane(&format!("New order {}", form.ticker), true)),
+        area,
+    );
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
@@ -0,0 +1,316 @@
+use crate::environment;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+const CASSETTE_MODE_ENV_VAR: &str = "KALSHI_CASSETTE_MODE";
+const REDACTED: &str = "REDACTED";
+// Fields that are replaced with REDACTED before an interaction is written to disk.
+const SCRUBBED_FIELDS: [&str; 4] = ["email", "member_id", "password", "token"];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<serde_json::Value>,
+    pub status: u16,
+    // Bodies that aren't JSON are stored as a JSON string.
+    pub response_body: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum CassetteMode {
+    // Forward every request to the upstream API and record the exchange.
+    Record { upstream_base_url: String },
+    // Serve previously recorded responses without touching the network.
+    Replay,
+}
+
+impl CassetteMode {
+    // Reads KALSHI_CASSETTE_MODE. "record" records against the production API and anything
+    // else replays, so CI never hits the network by accident.
+    pub fn from_env() -> Self {
+        match std::env::var(CASSETTE_MODE_ENV_VAR).as_deref() {
+            Ok("record") => CassetteMode::Record {
+                upstream_base_url: environment::PRODUCTION_API_URL.to_string(),
+            },
+            _ => CassetteMode::Replay,
+        }
+    }
+}
+
+struct CassetteState {
+    mode: CassetteMode,
+    cassette: Mutex<Cassette>,
+    // Replayed interactions are consumed in order so repeated requests get successive responses.
+    used: Mutex<Vec<bool>>,
+    client: reqwest::Client,
+}
+
+// A local HTTP server that either records or replays API traffic. Requests made inside
+// CassetteServer::run are sent to it instead of the real API.
+pub struct CassetteServer {
+    path: PathBuf,
+    addr: SocketAddr,
+    state: Arc<CassetteState>,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<Result<(), hyper::Error>>,
+}
+
+impl CassetteServer {
+    pub async fn start(
+        path: impl AsRef<Path>,
+        mode: CassetteMode,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let cassette = match mode {
+            CassetteMode::Record { .. } => Cassette::default(),
+            CassetteMode::Replay => Cassette::load(&path)?,
+        };
+        info!(
+            "Starting cassette server in {:?} mode for '{}'",
+            mode,
+            path.display()
+        );
+        let state = Arc::new(CassetteState {
+            mode,
+            used: Mutex::new(vec![false; cassette.interactions.len()]),
+            cassette: Mutex::new(cassette),
+            client: reqwest::Client::new(),
+        });
+
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle_request(state.clone(), request)
+                }))
+            }
+        });
+        let server = Server::try_bind(&SocketAddr::from(([127, 0, 0, 1], 0)))?.serve(make_service);
+        let addr = server.local_addr();
+        let (shutdown, shutdown_signal) = oneshot::channel::<()>();
+        let handle = tokio::spawn(server.with_graceful_shutdown(async {
+            shutdown_signal.await.ok();
+        }));
+
+        Ok(CassetteServer {
+            path,
+            addr,
+            state,
+            shutdown,
+            handle,
+        })
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    // Runs the future with all API requests sent through this server.
+    pub async fn run<F: Future>(&self, future: F) -> F::Output {
+        environment::with_api_base_url(&self.base_url(), future).await
+    }
+
+    // Stops the server and, when recording, writes the cassette to disk.
+    pub async fn stop(self) -> Result<Cassette, Box<dyn std::error::Error + Send + Sync>> {
+        self.shutdown.send(()).ok();
+        self.handle.await??;
+        let cassette = self.state.cassette.lock().unwrap().clone();
+        if let CassetteMode::Record { .. } = self.state.mode {
+            info!(
+                "Saving {} recorded interactions to '{}'",
+                cassette.interactions.len(),
+                self.path.display()
+            );
+            cassette.save(&self.path)?;
+        }
+        Ok(cassette)
+    }
+}
+
+async fn handle_request(
+    state: Arc<CassetteState>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let response = match &state.mode {
+        CassetteMode::Record { upstream_base_url } => {
+            let upstream_base_url = upstream_base_url.clone();
+            record(&state, &upstream_base_url, request).await
+        }
+        CassetteMode::Replay => replay(&state, request).await,
+    };
+    Ok(response.unwrap_or_else(|e| {
+        eprintln!("Cassette server error - {}", e);
+        text_response(500, &e.to_string())
+    }))
+}
+
+async fn record(
+    state: &CassetteState,
+    upstream_base_url: &str,
+    request: Request<Body>,
+) -> Result<Response<Body>, Box<dyn std::error::Error + Send + Sync>> {
+    let method = request.method().clone();
+    let path = request.uri().path().trim_start_matches('/').to_string();
+    let query = request.uri().query().map(str::to_string);
+    let authorization = request.headers().get(hyper::header::AUTHORIZATION).cloned();
+    let body = hyper::body::to_bytes(request.into_body()).await?;
+
+    let mut url = reqwest::Url::parse(upstream_base_url)?.join(&path)?;
+    url.set_query(query.as_deref());
+    let method = reqwest::Method::from_bytes(method.as_str().as_bytes())?;
+    let mut upstream_request = state
+        .client
+        .request(method.clone(), url)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .body(body.to_vec());
+    // The authorization header is forwarded but never recorded.
+    if let Some(authorization) = authorization {
+        upstream_request =
+            upstream_request.header(reqwest::header::AUTHORIZATION, authorization.as_bytes());
+    }
+    let upstream_response = upstream_request.send().await?;
+    let status = upstream_response.status().as_u16();
+    let text = upstream_response.text().await?;
+
+    let request_body = if body.is_empty() {
+        None
+    } else {
+        Some(scrub(parse_body(&String::from_utf8_lossy(&body))))
+    };
+    state
+        .cassette
+        .lock()
+        .unwrap()
+        .interactions
+        .push(Interaction {
+            method: method.to_string(),
+            path,
+            query: query.as_deref().map(normalize_query),
+            request_body,
+            status,
+            response_body: scrub(parse_body(&text)),
+        });
+    Ok(text_response(status, &text))
+}
+
+async fn replay(
+    state: &CassetteState,
+    request: Request<Body>,
+) -> Result<Response<Body>, Box<dyn std::error::Error + Send + Sync>> {
+    let method = request.method().to_string();
+    let path = request.uri().path().trim_start_matches('/').to_string();
+    let query = request.uri().query().map(normalize_query);
+
+    let cassette = state.cassette.lock().unwrap();
+    let mut used = state.used.lock().unwrap();
+    let matching: Vec<usize> = cassette
+        .interactions
+        .iter()
+        .enumerate()
+        .filter(|(_, interaction)| {
+            interaction.method == method && interaction.path == path && interaction.query == query
+        })
+        .map(|(index, _)| index)
+        .collect();
+    // Once every matching interaction has been used, the last one keeps being served.
+    let Some(index) = matching
+        .iter()
+        .copied()
+        .find(|index| !used[*index])
+        .or(matching.last().copied())
+    else {
+        let message = format!(
+            "No recorded interaction for {} /{}{}",
+            method,
+            path,
+            query.map(|query| format!("?{}", query)).unwrap_or_default()
+        );
+        eprintln!("{}", message);
+        return Ok(text_response(404, &message));
+    };
+    used[index] = true;
+    let interaction = &cassette.interactions[index];
+    let body = match &interaction.response_body {
+        serde_json::Value::String(text) => text.clone(),
+        body => body.to_string(),
+    };
+    Ok(text_response(interaction.status, &body))
+}
+
+fn text_response(status: u16, body: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+fn parse_body(text: &str) -> serde_json::Value {
+    serde_json::from_str(text).unwrap_or_else(|_| serde_json::Value::String(text.to_string()))
+}
+
+// Query parameters are sent in arbitrary order, so they are sorted before comparing.
+fn normalize_query(query: &str) -> String {
+    let mut pairs: Vec<&str> = query.split('&').filter(|pair| !pair.is_empty()).collect();
+    pairs.sort_unstable();
+    pairs.join("&")
+}
+
+fn scrub(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    if SCRUBBED_FIELDS.contains(&key.as_str()) {
+                        (key, serde_json::Value::String(REDACTED.to_string()))
+                    } else {
+                        (key, scrub(value))
+                    }
+                })
+                .collect(),
+        ),
+        serde_json::Value::Array(values) => {
+            serde_json::Value::Array(values.into_iter().map(scrub).collect())
+        }
+        value => value,
+    }
+}
@@ -0,0 +1,520 @@
+use crate::auth;
+use crate::exchange::{self, ExchangeSchedule, ExchangeStatus};
+use crate::market::{
+    self,
+    api_structs::{GetEventsResponse, GetMarketsResponse},
+    GetEventResponse, GetMarketOrderbookResponse, GetMarketResponse, GetTradesResponse,
+};
+use crate::money::Price;
+use crate::portfolio::{
+    self, AmendOrderResponse, Balance, BatchCancelOrderResult, BatchCancelOrdersResponse,
+    BatchCreateOrderResult, BatchCreateOrdersResponse, BatchOrderError, CancelOrderResponse,
+    CreateOrderRequest, CreateOrderResponse, DecreaseOrderResponse, GetFillsResponse,
+    GetOrderResponse, GetOrdersResponse, GetPositionsResponse, GetSettlementsResponse,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+// Public market data. None of these require an account.
+#[async_trait]
+pub trait MarketData: Send + Sync {
+    async fn get_exchange_status_async(
+        &self,
+    ) -> Result<ExchangeStatus, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_exchange_schedule_async(
+        &self,
+    ) -> Result<ExchangeSchedule, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_event_async(
+        &self,
+        event_ticker: &str,
+    ) -> Result<GetEventResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_events_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        status: Option<&str>,
+        series_ticker: Option<&str>,
+    ) -> Result<GetEventsResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_market_async(
+        &self,
+        market_ticker: &str,
+    ) -> Result<GetMarketResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn get_markets_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        event_ticker: Option<&str>,
+        series_ticker: Option<&str>,
+        max_close_ts: Option<DateTime<Utc>>,
+        min_close_ts: Option<DateTime<Utc>>,
+        status: Option<&str>,
+        tickers: Option<&str>,
+    ) -> Result<GetMarketsResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_market_orderbook_async(
+        &self,
+        market_ticker: &str,
+        depth: i32,
+    ) -> Result<GetMarketOrderbookResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_trades_async(
+        &self,
+        ticker: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        limit: Option<i32>,
+        cursor: Option<&str>,
+    ) -> Result<GetTradesResponse, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+// Order entry.
+#[async_trait]
+pub trait Trading: Send + Sync {
+    async fn create_order_async(
+        &self,
+        order: &CreateOrderRequest,
+    ) -> Result<CreateOrderResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_order_async(
+        &self,
+        order_id: &str,
+    ) -> Result<GetOrderResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn cancel_order_async(
+        &self,
+        order_id: &str,
+    ) -> Result<CancelOrderResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn amend_order_async(
+        &self,
+        order_id: &str,
+        action: &str,
+        client_order_id: &str,
+        count: i32,
+        no_price: Option<Price>,
+        side: &str,
+        ticker: &str,
+        updated_client_order_id: &str,
+        yes_price: Option<Price>,
+    ) -> Result<AmendOrderResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn decrease_order_async(
+        &self,
+        order_id: &str,
+        reduce_by: Option<i32>,
+        reduce_to: Option<i32>,
+    ) -> Result<DecreaseOrderResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    // Backends without a native batch endpoint create the orders one at a time.
+    async fn batch_create_orders_async(
+        &self,
+        orders: &[CreateOrderRequest],
+        _max_concurrency: usize,
+    ) -> Result<BatchCreateOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut results = Vec::with_capacity(orders.len());
+        for order in orders {
+            results.push(match self.create_order_async(order).await {
+                Ok(response) => BatchCreateOrderResult {
+                    client_order_id: Some(order.client_order_id.clone()),
+                    order: Some(response.order),
+                    error: None,
+                },
+                Err(e) => BatchCreateOrderResult {
+                    client_order_id: Some(order.client_order_id.clone()),
+                    order: None,
+                    error: Some(BatchOrderError {
+                        code: "request_failed".to_string(),
+                        message: e.to_string(),
+                    }),
+                },
+            });
+        }
+        Ok(BatchCreateOrdersResponse { orders: results })
+    }
+
+    // Backends without a native batch endpoint cancel the orders one at a time.
+    async fn batch_cancel_orders_async(
+        &self,
+        order_ids: &[String],
+        _max_concurrency: usize,
+    ) -> Result<BatchCancelOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut results = Vec::with_capacity(order_ids.len());
+        for order_id in order_ids {
+            results.push(match self.cancel_order_async(order_id).await {
+                Ok(response) => BatchCancelOrderResult {
+                    order_id: order_id.clone(),
+                    order: Some(response.order),
+                    reduced_by: response.reduced_by,
+                    error: None,
+                },
+                Err(e) => BatchCancelOrderResult {
+                    order_id: order_id.clone(),
+                    order: None,
+                    reduced_by: 0,
+                    error: Some(BatchOrderError {
+                        code: "request_failed".to_string(),
+                        message: e.to_string(),
+                    }),
+                },
+            });
+        }
+        Ok(BatchCancelOrdersResponse { orders: results })
+    }
+}
+
+// Account state.
+#[async_trait]
+pub trait Portfolio: Send + Sync {
+    async fn get_balance_async(&self) -> Result<Balance, Box<dyn std::error::Error + Send + Sync>>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn get_fills_async(
+        &self,
+        ticker: Option<&str>,
+        order_id: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        limit: Option<i32>,
+        cursor: Option<&str>,
+    ) -> Result<GetFillsResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn get_orders_async(
+        &self,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        status: Option<&str>,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+    ) -> Result<GetOrdersResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_positions_async(
+        &self,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+        settlement_status: Option<&str>,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+    ) -> Result<GetPositionsResponse, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn get_settlements_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<String>,
+    ) -> Result<GetSettlementsResponse, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+// Everything the strategy runtime needs, e.g. the real client or the paper-trading engine.
+pub trait Backend: MarketData + Trading + Portfolio {}
+
+impl<T: MarketData + Trading + Portfolio + ?Sized> Backend for T {}
+
+// The real HTTP client. It forwards to the free functions in each module.
+#[derive(Clone, Debug)]
+pub struct KalshiClient {
+    pub client: reqwest::Client,
+    pub token: String,
+}
+
+impl KalshiClient {
+    pub fn new(client: reqwest::Client, token: String) -> Self {
+        KalshiClient { client, token }
+    }
+
+    pub async fn login(
+        client: reqwest::Client,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let token = auth::login(&client).await?;
+        Ok(KalshiClient { client, token })
+    }
+
+    pub async fn logout(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        auth::logout(self.client, &self.token).await
+    }
+}
+
+#[async_trait]
+impl MarketData for KalshiClient {
+    async fn get_exchange_status_async(
+        &self,
+    ) -> Result<ExchangeStatus, Box<dyn std::error::Error + Send + Sync>> {
+        exchange::get_exchange_status_async(&self.client).await
+    }
+
+    async fn get_exchange_schedule_async(
+        &self,
+    ) -> Result<ExchangeSchedule, Box<dyn std::error::Error + Send + Sync>> {
+        exchange::get_exchange_schedule_async(&self.client).await
+    }
+
+    async fn get_event_async(
+        &self,
+        event_ticker: &str,
+    ) -> Result<GetEventResponse, Box<dyn std::error::Error + Send + Sync>> {
+        market::get_event_async(&self.client, event_ticker, &self.token).await
+    }
+
+    async fn get_events_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        status: Option<&str>,
+        series_ticker: Option<&str>,
+    ) -> Result<GetEventsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        market::get_events_async(
+            &self.client,
+            &self.token,
+            limit,
+            cursor,
+            status,
+            series_ticker,
+        )
+        .await
+    }
+
+    async fn get_market_async(
+        &self,
+        market_ticker: &str,
+    ) -> Result<GetMarketResponse, Box<dyn std::error::Error + Send + Sync>> {
+        market::get_market_async(&self.client, market_ticker, &self.token).await
+    }
+
+    async fn get_markets_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        event_ticker: Option<&str>,
+        series_ticker: Option<&str>,
+        max_close_ts: Option<DateTime<Utc>>,
+        min_close_ts: Option<DateTime<Utc>>,
+        status: Option<&str>,
+        tickers: Option<&str>,
+    ) -> Result<GetMarketsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        market::get_markets_async(
+            &self.client,
+            &self.token,
+            limit,
+            cursor,
+            event_ticker,
+            series_ticker,
+            max_close_ts,
+            min_close_ts,
+            status,
+            tickers,
+        )
+        .await
+    }
+
+    async fn get_market_orderbook_async(
+        &self,
+        market_ticker: &str,
+        depth: i32,
+    ) -> Result<GetMarketOrderbookResponse, Box<dyn std::error::Error + Send + Sync>> {
+        market::get_market_orderbook_async(&self.client, market_ticker, depth, &self.token).await
+    }
+
+    async fn get_trades_async(
+        &self,
+        ticker: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        limit: Option<i32>,
+        cursor: Option<&str>,
+    ) -> Result<GetTradesResponse, Box<dyn std::error::Error + Send + Sync>> {
+        market::get_trades_async(
+            &self.client,
+            &self.token,
+            ticker,
+            min_ts,
+            max_ts,
+            limit,
+            cursor,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl Trading for KalshiClient {
+    async fn create_order_async(
+        &self,
+        order: &CreateOrderRequest,
+    ) -> Result<CreateOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::create_order_async(
+            &self.client,
+            &self.token,
+            &order.action,
+            order.buy_max_cost,
+            &order.client_order_id,
+            order.count,
+            order.expiration_ts,
+            order.no_price,
+            order.sell_position_floor,
+            &order.side,
+            &order.ticker,
+            &order.type_,
+            order.yes_price,
+        )
+        .await
+    }
+
+    async fn get_order_async(
+        &self,
+        order_id: &str,
+    ) -> Result<GetOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::get_order(&self.client, &self.token, order_id).await
+    }
+
+    async fn cancel_order_async(
+        &self,
+        order_id: &str,
+    ) -> Result<CancelOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::cancel_order_async(&self.client, &self.token, order_id).await
+    }
+
+    async fn amend_order_async(
+        &self,
+        order_id: &str,
+        action: &str,
+        client_order_id: &str,
+        count: i32,
+        no_price: Option<Price>,
+        side: &str,
+        ticker: &str,
+        updated_client_order_id: &str,
+        yes_price: Option<Price>,
+    ) -> Result<AmendOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::amend_order_async(
+            &self.client,
+            &self.token,
+            order_id,
+            action,
+            client_order_id,
+            count,
+            no_price,
+            side,
+            ticker,
+            updated_client_order_id,
+            yes_price,
+        )
+        .await
+    }
+
+    async fn decrease_order_async(
+        &self,
+        order_id: &str,
+        reduce_by: Option<i32>,
+        reduce_to: Option<i32>,
+    ) -> Result<DecreaseOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::decrease_order_async(&self.client, &self.token, order_id, reduce_by, reduce_to)
+            .await
+    }
+
+    async fn batch_create_orders_async(
+        &self,
+        orders: &[CreateOrderRequest],
+        max_concurrency: usize,
+    ) -> Result<BatchCreateOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::batch_create_orders_async(&self.client, &self.token, orders, max_concurrency)
+            .await
+    }
+
+    async fn batch_cancel_orders_async(
+        &self,
+        order_ids: &[String],
+        max_concurrency: usize,
+    ) -> Result<BatchCancelOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::batch_cancel_orders_async(&self.client, &self.token, order_ids, max_concurrency)
+            .await
+    }
+}
+
+#[async_trait]
+impl Portfolio for KalshiClient {
+    async fn get_balance_async(&self) -> Result<Balance, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::get_balance_async(&self.client, &self.token).await
+    }
+
+    async fn get_fills_async(
+        &self,
+        ticker: Option<&str>,
+        order_id: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        limit: Option<i32>,
+        cursor: Option<&str>,
+    ) -> Result<GetFillsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::get_fills_async(
+            &self.client,
+            &self.token,
+            ticker,
+            order_id,
+            min_ts,
+            max_ts,
+            limit,
+            cursor,
+        )
+        .await
+    }
+
+    async fn get_orders_async(
+        &self,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        status: Option<&str>,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+    ) -> Result<GetOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::get_orders_async(
+            &self.client,
+            &self.token,
+            ticker,
+            event_ticker,
+            min_ts,
+            max_ts,
+            status,
+            cursor,
+            limit,
+        )
+        .await
+    }
+
+    async fn get_positions_async(
+        &self,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+        settlement_status: Option<&str>,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+    ) -> Result<GetPositionsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::get_positions_async(
+            &self.client,
+            &self.token,
+            cursor,
+            limit,
+            settlement_status,
+            ticker,
+            event_ticker,
+        )
+        .await
+    }
+
+    async fn get_settlements_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<String>,
+    ) -> Result<GetSettlementsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        portfolio::get_settlements_async(&self.client, &self.token, limit, cursor).await
+    }
+}
@@ -0,0 +1,59 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+pub const PRODUCTION_API_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/";
+pub const DEMO_API_URL: &str = "https://demo-api.kalshi.co/trade-api/v2/";
+
+static DEFAULT_API_BASE_URL: RwLock<Option<String>> = RwLock::new(None);
+static LOGGING_ENABLED: AtomicBool = AtomicBool::new(true);
+
+tokio::task_local! {
+    static API_BASE_URL: String;
+}
+
+// Changes the base URL used by every request in the process, e.g. to point at a different
+// Kalshi environment. Requests made inside with_api_base_url are unaffected.
+pub fn set_default_api_base_url(base_url: &str) {
+    *DEFAULT_API_BASE_URL.write().unwrap() = Some(with_trailing_slash(base_url));
+}
+
+// Runs the future with every request it makes sent to base_url instead of the default.
+// This is scoped to the current task, so tasks spawned from inside the future need to be
+// wrapped again with api_base_url().
+pub async fn with_api_base_url<F: Future>(base_url: &str, future: F) -> F::Output {
+    API_BASE_URL
+        .scope(with_trailing_slash(base_url), future)
+        .await
+}
+
+pub fn api_base_url() -> String {
+    API_BASE_URL
+        .try_with(|base_url| base_url.clone())
+        .ok()
+        .or_else(|| DEFAULT_API_BASE_URL.read().unwrap().clone())
+        .unwrap_or_else(|| PRODUCTION_API_URL.to_string())
+}
+
+// Resolves an endpoint path such as "portfolio/orders/" against the current base URL.
+pub fn api_url(path: &str) -> Result<reqwest::Url, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(reqwest::Url::parse(&api_base_url())?.join(path)?)
+}
+
+// Turns the crate's informational logging on stdout on or off. Errors are still written to
+// stderr. Useful when stdout carries machine readable output.
+pub fn set_logging_enabled(enabled: bool) {
+    LOGGING_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn logging_enabled() -> bool {
+    LOGGING_ENABLED.load(Ordering::Relaxed)
+}
+
+fn with_trailing_slash(base_url: &str) -> String {
+    if base_url.ends_with('/') {
+        base_url.to_string()
+    } else {
+        format!("{}/", base_url)
+    }
+}
@@ -1,7 +1,96 @@
-use serde::Deserialize;
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExchangeStatus {
     pub exchange_active: bool,
     pub trading_active: bool,
 }
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Announcement {
+    pub delivery_time: DateTime<Utc>,
+    pub message: String,
+    // active or inactive
+    pub status: String,
+    // info, warning or error
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetExchangeAnnouncementsResponse {
+    pub announcements: Vec<Announcement>,
+}
+
+// Trading hours for one weekday, in exchange (US Eastern) time. A close_time before the
+// open_time means the session runs past midnight into the next day.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyHours {
+    #[serde(
+        deserialize_with = "deserialize_hours_minutes",
+        serialize_with = "serialize_hours_minutes"
+    )]
+    pub open_time: NaiveTime,
+    #[serde(
+        deserialize_with = "deserialize_hours_minutes",
+        serialize_with = "serialize_hours_minutes"
+    )]
+    pub close_time: NaiveTime,
+}
+
+// Days without an entry have no trading session.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandardHours {
+    #[serde(default)]
+    pub monday: Option<DailyHours>,
+    #[serde(default)]
+    pub tuesday: Option<DailyHours>,
+    #[serde(default)]
+    pub wednesday: Option<DailyHours>,
+    #[serde(default)]
+    pub thursday: Option<DailyHours>,
+    #[serde(default)]
+    pub friday: Option<DailyHours>,
+    #[serde(default)]
+    pub saturday: Option<DailyHours>,
+    #[serde(default)]
+    pub sunday: Option<DailyHours>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub start_datetime: DateTime<Utc>,
+    pub end_datetime: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeSchedule {
+    pub standard_hours: StandardHours,
+    #[serde(default)]
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetExchangeScheduleResponse {
+    pub schedule: ExchangeSchedule,
+}
+
+// The schedule endpoint reports times as "HH:MM".
+const HOURS_MINUTES_FORMAT: &str = "%H:%M";
+
+fn deserialize_hours_minutes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<NaiveTime, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&text, HOURS_MINUTES_FORMAT)
+        .or_else(|_| NaiveTime::parse_from_str(&text, "%H:%M:%S"))
+        .map_err(serde::de::Error::custom)
+}
+
+fn serialize_hours_minutes<S: Serializer>(
+    time: &NaiveTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&time.format(HOURS_MINUTES_FORMAT).to_string())
+}
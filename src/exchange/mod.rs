@@ -1,18 +1,31 @@
-pub use crate::exchange::api_structs::ExchangeStatus;
+pub use crate::exchange::api_structs::{
+    Announcement, DailyHours, ExchangeSchedule, ExchangeStatus, GetExchangeAnnouncementsResponse,
+    GetExchangeScheduleResponse, MaintenanceWindow, StandardHours,
+};
 
 pub mod api_structs;
 
-const GET_EXCHANGE_STATUS_URL: &str =
-    "https://trading-api.kalshi.com/trade-api/v2/exchange/status/";
+use crate::environment;
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc, Weekday};
+use chrono_tz::America::New_York;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+const GET_EXCHANGE_STATUS_PATH: &str = "exchange/status/";
+const GET_EXCHANGE_SCHEDULE_PATH: &str = "exchange/schedule";
+const GET_EXCHANGE_ANNOUNCEMENTS_PATH: &str = "exchange/announcements";
+const STATUS_CHANGE_CHANNEL_SIZE: usize = 16;
+// Far enough ahead to cover a full week of standard hours.
+const SCHEDULE_LOOKAHEAD_DAYS: i64 = 8;
 
 pub async fn get_exchange_status_async(
     client: &reqwest::Client,
 ) -> Result<ExchangeStatus, Box<dyn std::error::Error + Send + Sync>> {
-    println!(
+    info!(
         "Fetching exchange status at endpoint '{}'",
-        GET_EXCHANGE_STATUS_URL
+        GET_EXCHANGE_STATUS_PATH
     );
-    let url = reqwest::Url::parse(GET_EXCHANGE_STATUS_URL)?;
+    let url = environment::api_url(GET_EXCHANGE_STATUS_PATH)?;
     match client
         .get(url)
         .header(
@@ -26,14 +39,14 @@ pub async fn get_exchange_status_async(
             if result.status().is_success() {
                 match result.json::<ExchangeStatus>().await {
                     Ok(body) => {
-                        println!(
+                        info!(
                             "Successfully parsed exchange status response body: {:?}",
                             body
                         );
                         Ok(body)
                     }
                     Err(e) => {
-                        println!(
+                        info!(
                             "Unable to parse exchange status response body. Error - {}",
                             e
                         );
@@ -57,3 +70,299 @@ pub async fn get_exchange_status_async(
         }
     }
 }
+
+pub async fn get_exchange_schedule_async(
+    client: &reqwest::Client,
+) -> Result<ExchangeSchedule, Box<dyn std::error::Error + Send + Sync>> {
+    info!(
+        "Fetching exchange schedule at endpoint '{}'",
+        GET_EXCHANGE_SCHEDULE_PATH
+    );
+    let url = environment::api_url(GET_EXCHANGE_SCHEDULE_PATH)?;
+    match client
+        .get(url)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .send()
+        .await
+    {
+        Ok(result) => {
+            if result.status().is_success() {
+                match result.json::<GetExchangeScheduleResponse>().await {
+                    Ok(body) => {
+                        info!(
+                            "Successfully parsed exchange schedule response body: {:?}",
+                            body
+                        );
+                        Ok(body.schedule)
+                    }
+                    Err(e) => {
+                        info!(
+                            "Unable to parse exchange schedule response body. Error - {}",
+                            e
+                        );
+                        Err(Box::new(e))
+                    }
+                }
+            } else {
+                let error_message = format!(
+                    "get_exchange_schedule unsuccessful with status {} and message {}",
+                    result.status().as_str(),
+                    result.text().await?
+                );
+                eprintln!("{}", error_message);
+                Err(Box::from(error_message))
+            }
+        }
+        Err(e) => {
+            let error_message = format!("Error from get_exchange_schedule: {}", e);
+            eprintln!("{}", error_message);
+            Err(Box::from(error_message))
+        }
+    }
+}
+
+// Fetches the schedule and sleeps until the exchange is next open, so callers can wait out
+// nights, weekends and maintenance instead of having their requests rejected.
+pub async fn sleep_until_open_async(
+    client: &reqwest::Client,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let schedule = get_exchange_schedule_async(client).await?;
+    let now = Utc::now();
+    let Some(next_open) = schedule.next_open_after(now) else {
+        return Err(Box::from(
+            "Exchange schedule has no upcoming trading session",
+        ));
+    };
+    if next_open > now {
+        info!("Exchange is closed. Sleeping until {}", next_open);
+        tokio::time::sleep((next_open - now).to_std()?).await;
+    }
+    Ok(())
+}
+
+impl StandardHours {
+    pub fn hours_for(&self, weekday: Weekday) -> Option<&DailyHours> {
+        match weekday {
+            Weekday::Mon => self.monday.as_ref(),
+            Weekday::Tue => self.tuesday.as_ref(),
+            Weekday::Wed => self.wednesday.as_ref(),
+            Weekday::Thu => self.thursday.as_ref(),
+            Weekday::Fri => self.friday.as_ref(),
+            Weekday::Sat => self.saturday.as_ref(),
+            Weekday::Sun => self.sunday.as_ref(),
+        }
+    }
+}
+
+impl ExchangeSchedule {
+    pub fn maintenance_window_at(&self, time: DateTime<Utc>) -> Option<&MaintenanceWindow> {
+        self.maintenance_windows
+            .iter()
+            .find(|window| window.start_datetime <= time && time < window.end_datetime)
+    }
+
+    // Whether time falls inside standard hours and outside every maintenance window.
+    pub fn is_trading_open_at(&self, time: DateTime<Utc>) -> bool {
+        if self.maintenance_window_at(time).is_some() {
+            return false;
+        }
+        let local = time.with_timezone(&New_York);
+        let local_time = local.time();
+        if let Some(hours) = self.standard_hours.hours_for(local.weekday()) {
+            let is_open = if hours.open_time == hours.close_time {
+                true
+            } else if hours.open_time < hours.close_time {
+                hours.open_time <= local_time && local_time < hours.close_time
+            } else {
+                hours.open_time <= local_time
+            };
+            if is_open {
+                return true;
+            }
+        }
+        // The previous day's session may run past midnight.
+        self.standard_hours
+            .hours_for(local.weekday().pred())
+            .is_some_and(|hours| {
+                hours.close_time < hours.open_time && local_time < hours.close_time
+            })
+    }
+
+    // The first time at or after the given time when trading is open, or None if the
+    // schedule has no session in the coming week.
+    pub fn next_open_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.is_trading_open_at(time) {
+            return Some(time);
+        }
+        let today = time.with_timezone(&New_York).date_naive();
+        let mut candidates: Vec<DateTime<Utc>> = (0..=SCHEDULE_LOOKAHEAD_DAYS)
+            .filter_map(|offset| {
+                let date = today + Duration::days(offset);
+                let hours = self.standard_hours.hours_for(date.weekday())?;
+                New_York
+                    .from_local_datetime(&date.and_time(hours.open_time))
+                    .earliest()
+                    .map(|open| open.with_timezone(&Utc))
+            })
+            .chain(
+                self.maintenance_windows
+                    .iter()
+                    .map(|window| window.end_datetime),
+            )
+            .filter(|candidate| *candidate > time)
+            .collect();
+        candidates.sort();
+        candidates
+            .into_iter()
+            .find(|candidate| self.is_trading_open_at(*candidate))
+    }
+
+    pub fn next_open(&self) -> Option<DateTime<Utc>> {
+        self.next_open_after(Utc::now())
+    }
+}
+
+pub async fn get_exchange_announcements_async(
+    client: &reqwest::Client,
+) -> Result<GetExchangeAnnouncementsResponse, Box<dyn std::error::Error + Send + Sync>> {
+    info!(
+        "Fetching exchange announcements at endpoint '{}'",
+        GET_EXCHANGE_ANNOUNCEMENTS_PATH
+    );
+    let url = environment::api_url(GET_EXCHANGE_ANNOUNCEMENTS_PATH)?;
+    match client
+        .get(url)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .send()
+        .await
+    {
+        Ok(result) => {
+            if result.status().is_success() {
+                match result.json::<GetExchangeAnnouncementsResponse>().await {
+                    Ok(body) => {
+                        info!(
+                            "Successfully parsed exchange announcements response body: {:?}",
+                            body
+                        );
+                        Ok(body)
+                    }
+                    Err(e) => {
+                        info!(
+                            "Unable to parse exchange announcements response body. Error - {}",
+                            e
+                        );
+                        Err(Box::new(e))
+                    }
+                }
+            } else {
+                let error_message = format!(
+                    "get_exchange_announcements unsuccessful with status {} and message {}",
+                    result.status().as_str(),
+                    result.text().await?
+                );
+                eprintln!("{}", error_message);
+                Err(Box::from(error_message))
+            }
+        }
+        Err(e) => {
+            let error_message = format!("Error from get_exchange_announcements: {}", e);
+            eprintln!("{}", error_message);
+            Err(Box::from(error_message))
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExchangeStatusChange {
+    // None for the first status the watcher sees.
+    pub previous: Option<ExchangeStatus>,
+    pub current: ExchangeStatus,
+    pub time: DateTime<Utc>,
+}
+
+impl ExchangeStatusChange {
+    pub fn trading_halted(&self) -> bool {
+        !self.current.trading_active
+            && self
+                .previous
+                .as_ref()
+                .is_none_or(|previous| previous.trading_active)
+    }
+
+    pub fn trading_resumed(&self) -> bool {
+        self.current.trading_active
+            && self
+                .previous
+                .as_ref()
+                .is_none_or(|previous| !previous.trading_active)
+    }
+}
+
+// Polls get_exchange_status_async in the background and reports every change, starting
+// with the first status it sees. Polling errors are logged and retried on the next tick.
+pub struct ExchangeStatusWatcher {
+    changes: mpsc::Receiver<ExchangeStatusChange>,
+    handle: JoinHandle<()>,
+}
+
+impl ExchangeStatusWatcher {
+    pub fn spawn(client: reqwest::Client, poll_interval: std::time::Duration) -> Self {
+        let (sender, changes) = mpsc::channel(STATUS_CHANGE_CHANNEL_SIZE);
+        // The spawned task doesn't inherit a base URL set with with_api_base_url.
+        let base_url = environment::api_base_url();
+        let watch = async move {
+            let mut interval = tokio::time::interval(poll_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut previous: Option<ExchangeStatus> = None;
+            loop {
+                interval.tick().await;
+                let status = match get_exchange_status_async(&client).await {
+                    Ok(status) => status,
+                    Err(e) => {
+                        eprintln!("Exchange status watcher failed to poll - {}", e);
+                        continue;
+                    }
+                };
+                if previous.as_ref() == Some(&status) {
+                    continue;
+                }
+                let change = ExchangeStatusChange {
+                    previous: previous.replace(status.clone()),
+                    current: status,
+                    time: Utc::now(),
+                };
+                info!("Exchange status changed: {:?}", change);
+                if sender.send(change).await.is_err() {
+                    // Nobody is listening any more.
+                    return;
+                }
+            }
+        };
+        let handle = tokio::spawn(async move {
+            environment::with_api_base_url(&base_url, watch).await;
+        });
+        ExchangeStatusWatcher { changes, handle }
+    }
+
+    // Waits for the next change. Returns None once the watcher has stopped.
+    pub async fn next_change(&mut self) -> Option<ExchangeStatusChange> {
+        self.changes.recv().await
+    }
+
+    // Stops polling. Dropping the watcher does the same.
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl Drop for ExchangeStatusWatcher {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
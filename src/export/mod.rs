@@ -0,0 +1,367 @@
+pub use crate::export::tax_lots::{ClosedLot, MarketLotSummary, OpenLot, TaxLots};
+
+use crate::client::Portfolio;
+use crate::money::Cents;
+use crate::portfolio::{Fill, Order, Settlement};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub mod tax_lots;
+
+const PAGE_SIZE: i32 = 100;
+
+// Account history for accounting. Amounts are written as plain dollar decimals, e.g.
+// "12.34", and times as UTC RFC 3339 with milliseconds.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    // One JSON object per line.
+    JsonLines,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = InvalidExportFormat;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "csv" => Ok(ExportFormat::Csv),
+            "jsonl" => Ok(ExportFormat::JsonLines),
+            _ => Err(InvalidExportFormat(format.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidExportFormat(pub String);
+
+impl fmt::Display for InvalidExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Export format must be csv or jsonl, got '{}'", self.0)
+    }
+}
+
+impl std::error::Error for InvalidExportFormat {}
+
+pub(crate) fn serialize_dollars<S: Serializer>(
+    cents: &Cents,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&cents.to_decimal_string())
+}
+
+pub(crate) fn serialize_time<S: Serializer>(
+    time: &DateTime<Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&time.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FillRecord {
+    #[serde(serialize_with = "serialize_time")]
+    pub time: DateTime<Utc>,
+    pub trade_id: String,
+    pub order_id: String,
+    pub ticker: String,
+    pub action: String,
+    pub side: String,
+    pub count: i32,
+    // Price per contract on the side traded.
+    #[serde(serialize_with = "serialize_dollars")]
+    pub price: Cents,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub notional: Cents,
+    pub is_taker: bool,
+}
+
+impl From<&Fill> for FillRecord {
+    fn from(fill: &Fill) -> Self {
+        let price = if fill.side == "yes" {
+            fill.yes_price
+        } else {
+            fill.no_price
+        };
+        FillRecord {
+            time: fill.created_time,
+            trade_id: fill.trade_id.clone(),
+            order_id: fill.order_id.clone(),
+            ticker: fill.ticker.clone(),
+            action: fill.action.clone(),
+            side: fill.side.clone(),
+            count: fill.count,
+            price: price.cents(),
+            notional: price.cents() * fill.count as i64,
+            is_taker: fill.is_taker,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OrderRecord {
+    #[serde(serialize_with = "serialize_time")]
+    pub created_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_time")]
+    pub last_update_time: DateTime<Utc>,
+    pub order_id: String,
+    pub client_order_id: String,
+    pub ticker: String,
+    pub action: String,
+    pub side: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub status: String,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub price: Cents,
+    pub place_count: i32,
+    pub filled_count: i64,
+    pub remaining_count: i32,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub taker_fill_cost: Cents,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub taker_fees: Cents,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub maker_fill_cost: Cents,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub maker_fees: Cents,
+}
+
+impl From<&Order> for OrderRecord {
+    fn from(order: &Order) -> Self {
+        let price = if order.side == "yes" {
+            order.yes_price
+        } else {
+            order.no_price
+        };
+        OrderRecord {
+            created_time: order.created_time,
+            last_update_time: order.last_update_time,
+            order_id: order.order_id.clone(),
+            client_order_id: order.client_order_id.clone(),
+            ticker: order.ticker.clone(),
+            action: order.action.clone(),
+            side: order.side.clone(),
+            type_: order.type_.clone(),
+            status: order.status.clone(),
+            price: price.cents(),
+            place_count: order.place_count,
+            filled_count: order.maker_fill_count as i64 + order.taker_fill_count,
+            remaining_count: order.remaining_count,
+            taker_fill_cost: order.taker_fill_cost,
+            taker_fees: order.taker_fees,
+            maker_fill_cost: order.maker_fill_cost,
+            maker_fees: order.maker_fees,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SettlementRecord {
+    #[serde(serialize_with = "serialize_time")]
+    pub settled_time: DateTime<Utc>,
+    pub ticker: String,
+    pub market_result: String,
+    pub yes_count: i64,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub yes_total_cost: Cents,
+    pub no_count: i64,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub no_total_cost: Cents,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub revenue: Cents,
+    // revenue - yes_total_cost - no_total_cost
+    #[serde(serialize_with = "serialize_dollars")]
+    pub profit: Cents,
+}
+
+impl From<&Settlement> for SettlementRecord {
+    fn from(settlement: &Settlement) -> Self {
+        SettlementRecord {
+            settled_time: settlement.settled_time,
+            ticker: settlement.ticker.clone(),
+            market_result: settlement.market_result.clone(),
+            yes_count: settlement.yes_count,
+            yes_total_cost: settlement.yes_total_cost,
+            no_count: settlement.no_count,
+            no_total_cost: settlement.no_total_cost,
+            revenue: settlement.revenue,
+            profit: settlement.revenue - settlement.yes_total_cost - settlement.no_total_cost,
+        }
+    }
+}
+
+// Writes records as CSV with a header row, or as JSON Lines.
+pub fn write_records<T: Serialize>(
+    writer: impl Write,
+    format: ExportFormat,
+    records: &[T],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(writer);
+            for record in records {
+                writer.serialize(record)?;
+            }
+            writer.flush()?;
+        }
+        ExportFormat::JsonLines => {
+            let mut writer = std::io::BufWriter::new(writer);
+            for record in records {
+                serde_json::to_writer(&mut writer, record)?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+// Fills, orders and settlements over a date range, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct AccountHistory {
+    pub fills: Vec<Fill>,
+    pub orders: Vec<Order>,
+    pub settlements: Vec<Settlement>,
+}
+
+impl AccountHistory {
+    // Pages through everything between min_ts and max_ts. Orders are included by creation
+    // time. The settlements endpoint can't filter by time, so every settlement is fetched
+    // and filtered here.
+    pub async fn fetch_async<B: Portfolio + ?Sized>(
+        backend: &B,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut history = AccountHistory::default();
+
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = backend
+                .get_fills_async(
+                    None,
+                    None,
+                    min_ts,
+                    max_ts,
+                    Some(PAGE_SIZE),
+                    cursor.as_deref(),
+                )
+                .await?;
+            history.fills.extend(page.fills);
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = backend
+                .get_orders_async(
+                    None,
+                    None,
+                    min_ts,
+                    max_ts,
+                    None,
+                    cursor.as_deref(),
+                    Some(PAGE_SIZE),
+                )
+                .await?;
+            history.orders.extend(page.orders);
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = backend
+                .get_settlements_async(Some(PAGE_SIZE as i64), cursor)
+                .await?;
+            history
+                .settlements
+                .extend(page.settlements.into_iter().filter(|settlement| {
+                    min_ts.is_none_or(|min_ts| settlement.settled_time >= min_ts)
+                        && max_ts.is_none_or(|max_ts| settlement.settled_time <= max_ts)
+                }));
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+
+        history.fills.sort_by_key(|fill| fill.created_time);
+        history.orders.sort_by_key(|order| order.created_time);
+        history
+            .settlements
+            .sort_by_key(|settlement| settlement.settled_time);
+        info!(
+            "Fetched {} fills, {} orders and {} settlements",
+            history.fills.len(),
+            history.orders.len(),
+            history.settlements.len()
+        );
+        Ok(history)
+    }
+
+    pub fn tax_lots(&self) -> TaxLots {
+        tax_lots::tax_lots(&self.fills, &self.settlements)
+    }
+
+    pub fn lot_summary(&self) -> Vec<MarketLotSummary> {
+        tax_lots::summarize(&self.tax_lots(), &self.orders)
+    }
+
+    // Writes fills, orders, settlements, closed_lots and lot_summary files into dir and
+    // returns their paths.
+    pub fn write_dir(
+        &self,
+        dir: impl AsRef<Path>,
+        format: ExportFormat,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error + Send + Sync>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let lots = self.tax_lots();
+        let fills: Vec<FillRecord> = self.fills.iter().map(FillRecord::from).collect();
+        let orders: Vec<OrderRecord> = self.orders.iter().map(OrderRecord::from).collect();
+        let settlements: Vec<SettlementRecord> = self
+            .settlements
+            .iter()
+            .map(SettlementRecord::from)
+            .collect();
+        let summary = tax_lots::summarize(&lots, &self.orders);
+
+        Ok(vec![
+            write_file(dir, "fills", format, &fills)?,
+            write_file(dir, "orders", format, &orders)?,
+            write_file(dir, "settlements", format, &settlements)?,
+            write_file(dir, "closed_lots", format, &lots.closed)?,
+            write_file(dir, "lot_summary", format, &summary)?,
+        ])
+    }
+}
+
+fn write_file<T: Serialize>(
+    dir: &Path,
+    name: &str,
+    format: ExportFormat,
+    records: &[T],
+) -> Result<PathBuf, Box<dyn std::error::Error + Send + Sync>> {
+    let path = dir.join(format!("{}.{}", name, format.extension()));
+    write_records(std::fs::File::create(&path)?, format, records)?;
+    Ok(path)
+}
@@ -0,0 +1,237 @@
+use crate::export::{serialize_dollars, serialize_time};
+use crate::money::Cents;
+use crate::portfolio::{Fill, Order, Settlement};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+
+// Lots are matched first in, first out. Every lot is a long position in yes or no
+// contracts: buying no while holding yes closes yes lots, the same way the exchange nets
+// the position. Only the fills passed in are matched, so lots opened before an export's
+// date range are missing and closing them opens a lot on the other side instead.
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OpenLot {
+    pub ticker: String,
+    // The side held, yes or no.
+    pub side: &'static str,
+    pub count: i64,
+    #[serde(serialize_with = "serialize_time")]
+    pub opened_time: DateTime<Utc>,
+    // Price paid per contract.
+    #[serde(serialize_with = "serialize_dollars")]
+    pub price: Cents,
+}
+
+impl OpenLot {
+    pub fn cost_basis(&self) -> Cents {
+        self.price * self.count
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ClosedLot {
+    pub ticker: String,
+    pub side: &'static str,
+    pub count: i64,
+    #[serde(serialize_with = "serialize_time")]
+    pub opened_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_time")]
+    pub closed_time: DateTime<Utc>,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub cost_basis: Cents,
+    // What the contracts were sold for, or paid out at settlement.
+    #[serde(serialize_with = "serialize_dollars")]
+    pub proceeds: Cents,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub gain: Cents,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaxLots {
+    // In the order they were closed.
+    pub closed: Vec<ClosedLot>,
+    pub open: Vec<OpenLot>,
+}
+
+// Totals for one market, the tax-lot summary accountants work from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MarketLotSummary {
+    pub ticker: String,
+    pub closed_count: i64,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub cost_basis: Cents,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub proceeds: Cents,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub realized_gain: Cents,
+    // Taker fees from the market's orders. Fills don't report fees.
+    #[serde(serialize_with = "serialize_dollars")]
+    pub fees: Cents,
+    // Side of the open lots, empty when nothing is open.
+    pub open_side: &'static str,
+    pub open_count: i64,
+    #[serde(serialize_with = "serialize_dollars")]
+    pub open_cost_basis: Cents,
+}
+
+enum LotEntry<'a> {
+    Fill(&'a Fill),
+    Settlement(&'a Settlement),
+}
+
+// Matches fills and settlements into lots in chronological order.
+pub fn tax_lots(fills: &[Fill], settlements: &[Settlement]) -> TaxLots {
+    let mut entries: Vec<(DateTime<Utc>, LotEntry)> = fills
+        .iter()
+        .map(|fill| (fill.created_time, LotEntry::Fill(fill)))
+        .chain(
+            settlements
+                .iter()
+                .map(|settlement| (settlement.settled_time, LotEntry::Settlement(settlement))),
+        )
+        .collect();
+    entries.sort_by_key(|(time, _)| *time);
+
+    let mut open: BTreeMap<String, VecDeque<OpenLot>> = BTreeMap::new();
+    let mut closed = Vec::new();
+    for (time, entry) in entries {
+        match entry {
+            LotEntry::Fill(fill) => {
+                let buys_yes = (fill.side == "yes") == (fill.action == "buy");
+                let (side, price) = if buys_yes {
+                    ("yes", fill.yes_price)
+                } else {
+                    ("no", fill.no_price)
+                };
+                let lots = open.entry(fill.ticker.clone()).or_default();
+                // Contracts on the other side are sold at the complement of the price.
+                let remaining = close_lots(
+                    lots,
+                    &mut closed,
+                    |lot| lot.side != side,
+                    fill.count as i64,
+                    price.complement().cents(),
+                    time,
+                );
+                if remaining > 0 {
+                    lots.push_back(OpenLot {
+                        ticker: fill.ticker.clone(),
+                        side,
+                        count: remaining,
+                        opened_time: time,
+                        price: price.cents(),
+                    });
+                }
+            }
+            LotEntry::Settlement(settlement) => {
+                let result = settlement.market_result.as_str();
+                // Voided and other results leave the lots open.
+                if result != "yes" && result != "no" {
+                    continue;
+                }
+                if let Some(lots) = open.get_mut(&settlement.ticker) {
+                    for side in ["yes", "no"] {
+                        let payout = if side == result {
+                            Cents(100)
+                        } else {
+                            Cents::ZERO
+                        };
+                        close_lots(
+                            lots,
+                            &mut closed,
+                            |lot| lot.side == side,
+                            i64::MAX,
+                            payout,
+                            time,
+                        );
+                    }
+                }
+            }
+        }
+    }
+    TaxLots {
+        closed,
+        open: open.into_values().flatten().collect(),
+    }
+}
+
+// Closes up to count contracts from the oldest lots that match, at price per contract.
+// Returns the count left over.
+fn close_lots(
+    lots: &mut VecDeque<OpenLot>,
+    closed: &mut Vec<ClosedLot>,
+    matches: impl Fn(&OpenLot) -> bool,
+    mut count: i64,
+    price: Cents,
+    time: DateTime<Utc>,
+) -> i64 {
+    while count > 0 {
+        let Some(lot) = lots.front_mut().filter(|lot| matches(lot)) else {
+            break;
+        };
+        let closed_count = count.min(lot.count);
+        let cost_basis = lot.price * closed_count;
+        let proceeds = price * closed_count;
+        closed.push(ClosedLot {
+            ticker: lot.ticker.clone(),
+            side: lot.side,
+            count: closed_count,
+            opened_time: lot.opened_time,
+            closed_time: time,
+            cost_basis,
+            proceeds,
+            gain: proceeds - cost_basis,
+        });
+        lot.count -= closed_count;
+        count -= closed_count;
+        if lot.count == 0 {
+            lots.pop_front();
+        }
+    }
+    count
+}
+
+// One summary per market with lots or fees, sorted by ticker.
+pub fn summarize(lots: &TaxLots, orders: &[Order]) -> Vec<MarketLotSummary> {
+    let mut summaries: BTreeMap<String, MarketLotSummary> = BTreeMap::new();
+    for lot in &lots.closed {
+        let summary = summary_for(&mut summaries, &lot.ticker);
+        summary.closed_count += lot.count;
+        summary.cost_basis += lot.cost_basis;
+        summary.proceeds += lot.proceeds;
+        summary.realized_gain += lot.gain;
+    }
+    for lot in &lots.open {
+        let summary = summary_for(&mut summaries, &lot.ticker);
+        summary.open_side = lot.side;
+        summary.open_count += lot.count;
+        summary.open_cost_basis += lot.cost_basis();
+    }
+    for order in orders {
+        let fees = order.taker_fees + order.maker_fees;
+        if fees != Cents::ZERO {
+            summary_for(&mut summaries, &order.ticker).fees += fees;
+        }
+    }
+    summaries.into_values().collect()
+}
+
+fn summary_for<'a>(
+    summaries: &'a mut BTreeMap<String, MarketLotSummary>,
+    ticker: &str,
+) -> &'a mut MarketLotSummary {
+    summaries
+        .entry(ticker.to_string())
+        .or_insert_with(|| MarketLotSummary {
+            ticker: ticker.to_string(),
+            closed_count: 0,
+            cost_basis: Cents::ZERO,
+            proceeds: Cents::ZERO,
+            realized_gain: Cents::ZERO,
+            fees: Cents::ZERO,
+            open_side: "",
+            open_count: 0,
+            open_cost_basis: Cents::ZERO,
+        })
+}
@@ -0,0 +1,133 @@
+use crate::money::{Cents, Price};
+use crate::portfolio::CreateOrderRequest;
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub const DEFAULT_TAKER_FEE_RATE: f64 = 0.07;
+
+// Kalshi's trading fee: ceil(rate * count * P * (1 - P)) dollars, where P is the contract
+// price in dollars, rounded up to the next cent. P * (1 - P) is the same for either side of
+// a trade, so the price may be given as the yes or the no price.
+pub fn fee(rate: f64, count: i64, price: Price) -> Cents {
+    let cents = count as f64 * fee_per_contract(rate, price);
+    // Allow for floating point error so exact cent amounts don't round up another cent.
+    Cents((cents - 1e-9).ceil().max(0.0) as i64)
+}
+
+// Fee of a single contract in cents, before rounding. Rounding applies to a whole fill, so
+// this is the marginal cost of one more contract.
+pub fn fee_per_contract(rate: f64, price: Price) -> f64 {
+    let price = price.value() as f64;
+    rate * price * (100.0 - price) / 100.0
+}
+
+// Series a market belongs to, taken from the market ticker, e.g. "INXD" for
+// "INXD-23APR03-B4100".
+pub fn series_ticker(market_ticker: &str) -> &str {
+    market_ticker.split('-').next().unwrap_or(market_ticker)
+}
+
+// The rates charged to takers and makers. Most markets charge makers nothing, and some
+// series charge a multiple of the standard rates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeSchedule {
+    pub taker_rate: f64,
+    pub maker_rate: f64,
+    // Multiplies both rates for every market in a series, keyed by series ticker.
+    pub series_multipliers: HashMap<String, f64>,
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        FeeSchedule {
+            taker_rate: DEFAULT_TAKER_FEE_RATE,
+            maker_rate: 0.0,
+            series_multipliers: HashMap::new(),
+        }
+    }
+}
+
+impl FeeSchedule {
+    // A schedule that charges nothing, e.g. to see results before fees.
+    pub fn free() -> Self {
+        FeeSchedule {
+            taker_rate: 0.0,
+            maker_rate: 0.0,
+            series_multipliers: HashMap::new(),
+        }
+    }
+
+    pub fn with_series_multiplier(mut self, series_ticker: &str, multiplier: f64) -> Self {
+        self.series_multipliers
+            .insert(series_ticker.to_string(), multiplier);
+        self
+    }
+
+    // Rate charged on fills in the market, after the series multiplier.
+    pub fn rate(&self, market_ticker: &str, is_taker: bool) -> f64 {
+        let rate = if is_taker {
+            self.taker_rate
+        } else {
+            self.maker_rate
+        };
+        let multiplier = self
+            .series_multipliers
+            .get(series_ticker(market_ticker))
+            .copied()
+            .unwrap_or(1.0);
+        rate * multiplier
+    }
+
+    pub fn fee(&self, market_ticker: &str, count: i64, price: Price, is_taker: bool) -> Cents {
+        fee(self.rate(market_ticker, is_taker), count, price)
+    }
+
+    pub fn taker_fee(&self, market_ticker: &str, count: i64, price: Price) -> Cents {
+        self.fee(market_ticker, count, price, true)
+    }
+
+    pub fn maker_fee(&self, market_ticker: &str, count: i64, price: Price) -> Cents {
+        self.fee(market_ticker, count, price, false)
+    }
+
+    // Prices an order as if it all fills at its limit price as a taker, which is the most
+    // it can cost. Returns None for orders without a price.
+    pub fn estimate_order(&self, request: &CreateOrderRequest) -> Option<OrderCostEstimate> {
+        let price = if request.side == "yes" {
+            request
+                .yes_price
+                .or_else(|| request.no_price.map(Price::complement))
+        } else {
+            request
+                .no_price
+                .or_else(|| request.yes_price.map(Price::complement))
+        }?;
+        let count = request.count as i64;
+        let notional = price.cents() * count;
+        let fees = self.taker_fee(&request.ticker, count, price);
+        let all_in = if request.action == "sell" {
+            notional - fees
+        } else {
+            notional + fees
+        };
+        Some(OrderCostEstimate {
+            price,
+            count,
+            notional,
+            fees,
+            all_in,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct OrderCostEstimate {
+    // Limit price on the order's side.
+    pub price: Price,
+    pub count: i64,
+    // price * count, before fees.
+    pub notional: Cents,
+    pub fees: Cents,
+    // What a buy costs including fees, or what a sell takes in after fees.
+    pub all_in: Cents,
+}
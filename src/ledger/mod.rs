@@ -0,0 +1,374 @@
+use crate::client::Portfolio;
+use crate::market::Market;
+use crate::money::Cents;
+use crate::portfolio::{Fill, MarketPosition, Settlement};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// Positions are tracked the same way the exchange reports them: a positive position is
+// long yes contracts and a negative position is long no contracts. Internally every trade
+// is converted to a yes-equivalent trade, since buying no at 30 is selling yes at 70.
+#[derive(Clone, Debug, Default)]
+pub struct MarketLedger {
+    pub ticker: String,
+    pub event_ticker: Option<String>,
+    pub position: i64,
+    // Yes-equivalent cost of the open position, i.e. the sum of yes prices paid (for a long
+    // position) or received (for a short position) for every open contract.
+    pub open_cost: Cents,
+    pub realized_pnl: Cents,
+    pub fees: Cents,
+    pub settled: bool,
+    pub last_update_time: Option<DateTime<Utc>>,
+}
+
+impl MarketLedger {
+    fn new(ticker: &str) -> Self {
+        MarketLedger {
+            ticker: ticker.to_string(),
+            ..MarketLedger::default()
+        }
+    }
+
+    // Average price paid per contract on the side actually held.
+    pub fn average_cost(&self) -> Option<f64> {
+        if self.position == 0 {
+            return None;
+        }
+        let average_yes_price = self.open_cost.value() as f64 / self.position.abs() as f64;
+        if self.position > 0 {
+            Some(average_yes_price)
+        } else {
+            Some(100.0 - average_yes_price)
+        }
+    }
+
+    // Cash paid for the open position on the side actually held.
+    pub fn total_cost(&self) -> Cents {
+        if self.position >= 0 {
+            self.open_cost
+        } else {
+            Cents(self.position.abs() * 100) - self.open_cost
+        }
+    }
+
+    // P&L from closing the position at the current top of book: longs sell into the yes
+    // bid and shorts buy back at the yes ask.
+    pub fn unrealized_pnl(&self, market: &Market) -> Cents {
+        if self.position > 0 {
+            market.yes_bid * self.position - self.open_cost
+        } else if self.position < 0 {
+            self.open_cost - market.yes_ask * self.position.abs()
+        } else {
+            Cents::ZERO
+        }
+    }
+
+    fn apply_trade(&mut self, delta: i64, yes_price: Cents) {
+        if delta == 0 {
+            return;
+        }
+        if self.position == 0 || self.position.signum() == delta.signum() {
+            self.position += delta;
+            self.open_cost += yes_price * delta.abs();
+            return;
+        }
+        let closed = delta.abs().min(self.position.abs());
+        let closed_cost = Cents(self.open_cost.value() * closed / self.position.abs());
+        if self.position > 0 {
+            self.realized_pnl += yes_price * closed - closed_cost;
+        } else {
+            self.realized_pnl += closed_cost - yes_price * closed;
+        }
+        self.open_cost -= closed_cost;
+        self.position += closed * delta.signum();
+
+        let remaining = delta.abs() - closed;
+        if remaining > 0 {
+            self.position = remaining * delta.signum();
+            self.open_cost = yes_price * remaining;
+        }
+    }
+
+    fn apply_settlement(&mut self, settlement: &Settlement) {
+        self.realized_pnl += settlement.revenue - self.total_cost();
+        self.position = 0;
+        self.open_cost = Cents::ZERO;
+        self.settled = true;
+        self.last_update_time = Some(settlement.settled_time);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EventLedger {
+    pub event_ticker: String,
+    pub tickers: Vec<String>,
+    // Net contracts across all markets in the event.
+    pub position: i64,
+    pub total_cost: Cents,
+    pub realized_pnl: Cents,
+    pub unrealized_pnl: Cents,
+    pub fees: Cents,
+}
+
+#[derive(Clone, Debug)]
+pub struct PositionMismatch {
+    pub ticker: String,
+    pub local_position: i64,
+    pub exchange_position: i64,
+    pub local_realized_pnl: Cents,
+    pub exchange_realized_pnl: Cents,
+}
+
+#[derive(Debug, Default)]
+pub struct Ledger {
+    markets: BTreeMap<String, MarketLedger>,
+    marks: HashMap<String, Market>,
+    seen_trade_ids: HashSet<String>,
+}
+
+enum LedgerEntry<'a> {
+    Fill(&'a Fill),
+    Settlement(&'a Settlement),
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Ledger::default()
+    }
+
+    // Builds a ledger by replaying fills and settlements in chronological order.
+    pub fn replay(fills: &[Fill], settlements: &[Settlement]) -> Self {
+        let mut entries: Vec<(DateTime<Utc>, LedgerEntry)> =
+            fills
+                .iter()
+                .map(|fill| (fill.created_time, LedgerEntry::Fill(fill)))
+                .chain(settlements.iter().map(|settlement| {
+                    (settlement.settled_time, LedgerEntry::Settlement(settlement))
+                }))
+                .collect();
+        entries.sort_by_key(|(time, _)| *time);
+
+        let mut ledger = Ledger::new();
+        for (_, entry) in entries {
+            match entry {
+                LedgerEntry::Fill(fill) => ledger.apply_fill(fill),
+                LedgerEntry::Settlement(settlement) => ledger.apply_settlement(settlement),
+            }
+        }
+        ledger
+    }
+
+    // Pages through the full fill and settlement history and replays it. Fills don't carry
+    // fees, so the taker and maker fees on every order are applied afterwards.
+    pub async fn load_async<B: Portfolio + ?Sized>(
+        backend: &B,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut fills = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = backend
+                .get_fills_async(None, None, None, None, Some(100), cursor.as_deref())
+                .await?;
+            fills.extend(page.fills);
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+
+        let mut settlements = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = backend.get_settlements_async(Some(100), cursor).await?;
+            settlements.extend(page.settlements);
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+
+        let mut orders = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = backend
+                .get_orders_async(None, None, None, None, None, cursor.as_deref(), Some(100))
+                .await?;
+            orders.extend(page.orders);
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+
+        let mut ledger = Ledger::replay(&fills, &settlements);
+        for order in &orders {
+            let fees = order.taker_fees + order.maker_fees;
+            if fees != Cents::ZERO {
+                ledger.apply_fee(&order.ticker, fees);
+            }
+        }
+        Ok(ledger)
+    }
+
+    // Duplicate fills (by trade id) are ignored.
+    pub fn apply_fill(&mut self, fill: &Fill) {
+        if !self.seen_trade_ids.insert(fill.trade_id.clone()) {
+            return;
+        }
+        let buys_yes = (fill.side == "yes") == (fill.action == "buy");
+        let delta = if buys_yes {
+            fill.count as i64
+        } else {
+            -(fill.count as i64)
+        };
+        let market = self.market_mut(&fill.ticker);
+        market.apply_trade(delta, fill.yes_price.cents());
+        market.last_update_time = Some(fill.created_time);
+    }
+
+    pub fn apply_settlement(&mut self, settlement: &Settlement) {
+        self.market_mut(&settlement.ticker)
+            .apply_settlement(settlement);
+    }
+
+    pub fn apply_fee(&mut self, ticker: &str, fee: Cents) {
+        self.market_mut(ticker).fees += fee;
+    }
+
+    // Records the latest market data used for unrealized P&L. This also associates the
+    // market with its event.
+    pub fn mark(&mut self, market: &Market) {
+        self.market_mut(&market.ticker).event_ticker = Some(market.event_ticker.clone());
+        self.marks.insert(market.ticker.clone(), market.clone());
+    }
+
+    pub fn market(&self, ticker: &str) -> Option<&MarketLedger> {
+        self.markets.get(ticker)
+    }
+
+    pub fn markets(&self) -> impl Iterator<Item = &MarketLedger> {
+        self.markets.values()
+    }
+
+    // Unrealized P&L for a market, if it has been marked.
+    pub fn unrealized_pnl(&self, ticker: &str) -> Option<Cents> {
+        let market = self.markets.get(ticker)?;
+        let mark = self.marks.get(ticker)?;
+        Some(market.unrealized_pnl(mark))
+    }
+
+    // Markets are grouped by the event they were marked with, or by the event in their
+    // ticker if they were never marked.
+    pub fn events(&self) -> Vec<EventLedger> {
+        let mut events: BTreeMap<String, EventLedger> = BTreeMap::new();
+        for market in self.markets.values() {
+            let event_ticker = market
+                .event_ticker
+                .as_deref()
+                .unwrap_or_else(|| event_ticker(&market.ticker));
+            let event = events
+                .entry(event_ticker.to_string())
+                .or_insert_with(|| EventLedger {
+                    event_ticker: event_ticker.to_string(),
+                    ..EventLedger::default()
+                });
+            event.tickers.push(market.ticker.clone());
+            event.position += market.position;
+            event.total_cost += market.total_cost();
+            event.realized_pnl += market.realized_pnl;
+            event.unrealized_pnl += self.unrealized_pnl(&market.ticker).unwrap_or_default();
+            event.fees += market.fees;
+        }
+        events.into_values().collect()
+    }
+
+    pub fn total_realized_pnl(&self) -> Cents {
+        self.markets
+            .values()
+            .map(|market| market.realized_pnl)
+            .sum()
+    }
+
+    pub fn total_unrealized_pnl(&self) -> Cents {
+        self.markets
+            .keys()
+            .filter_map(|ticker| self.unrealized_pnl(ticker))
+            .sum()
+    }
+
+    pub fn total_fees(&self) -> Cents {
+        self.markets.values().map(|market| market.fees).sum()
+    }
+
+    // Compares local positions against the exchange's. Only position differences are
+    // reported; realized P&L is included for context since the exchange nets fees into it.
+    pub fn reconcile(&self, positions: &[MarketPosition]) -> Vec<PositionMismatch> {
+        let mut mismatches = Vec::new();
+        let mut seen = HashSet::new();
+        for position in positions {
+            seen.insert(position.ticker.as_str());
+            let local = self.markets.get(&position.ticker);
+            let local_position = local.map(|market| market.position).unwrap_or(0);
+            if local_position != position.position {
+                mismatches.push(PositionMismatch {
+                    ticker: position.ticker.clone(),
+                    local_position,
+                    exchange_position: position.position,
+                    local_realized_pnl: local.map(|market| market.realized_pnl).unwrap_or_default(),
+                    exchange_realized_pnl: position.realized_pnl,
+                });
+            }
+        }
+        for market in self.markets.values() {
+            if market.position != 0 && !seen.contains(market.ticker.as_str()) {
+                mismatches.push(PositionMismatch {
+                    ticker: market.ticker.clone(),
+                    local_position: market.position,
+                    exchange_position: 0,
+                    local_realized_pnl: market.realized_pnl,
+                    exchange_realized_pnl: Cents::ZERO,
+                });
+            }
+        }
+        mismatches
+    }
+
+    // Cross-checks the ledger against get_positions_async, paging through every position.
+    pub async fn reconcile_async<B: Portfolio + ?Sized>(
+        &self,
+        backend: &B,
+    ) -> Result<Vec<PositionMismatch>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut positions = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = backend
+                .get_positions_async(cursor.as_deref(), Some(100), None, None, None)
+                .await?;
+            positions.extend(page.market_positions);
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+        let mismatches = self.reconcile(&positions);
+        for mismatch in &mismatches {
+            info!("Position mismatch: {:?}", mismatch);
+        }
+        Ok(mismatches)
+    }
+
+    fn market_mut(&mut self, ticker: &str) -> &mut MarketLedger {
+        self.markets
+            .entry(ticker.to_string())
+            .or_insert_with(|| MarketLedger::new(ticker))
+    }
+}
+
+// Event a market belongs to, taken from the market ticker, e.g. "INXD-23APR03" for
+// "INXD-23APR03-B4100".
+fn event_ticker(market_ticker: &str) -> &str {
+    market_ticker
+        .rsplit_once('-')
+        .map_or(market_ticker, |(event_ticker, _)| event_ticker)
+}
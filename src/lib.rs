@@ -1,5 +1,33 @@
+// Informational logging used throughout the crate. It prints to stdout like before, unless
+// turned off with environment::set_logging_enabled(false).
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::environment::logging_enabled() {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub mod arbitrage;
 pub mod auth;
+pub mod backtest;
 pub mod bids;
+pub mod cassette;
+pub mod client;
+pub mod environment;
 pub mod exchange;
+pub mod export;
+pub mod fees;
+pub mod ledger;
+pub mod lifecycle;
 pub mod market;
+pub mod mock;
+pub mod money;
+pub mod order_manager;
+pub mod paper;
 pub mod portfolio;
+pub mod recorder;
+pub mod risk;
+pub mod runtime;
+pub mod scanner;
+pub mod strategy;
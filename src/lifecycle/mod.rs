@@ -0,0 +1,349 @@
+use crate::client::{MarketData, Portfolio};
+use crate::environment;
+use crate::market::Market;
+use crate::portfolio::Settlement;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+const LIFECYCLE_EVENT_CHANNEL_SIZE: usize = 64;
+const SETTLEMENTS_PAGE_SIZE: i64 = 100;
+
+#[derive(Clone, Debug)]
+pub enum LifecycleEvent {
+    Opened {
+        ticker: String,
+        open_time: DateTime<Utc>,
+    },
+    Closed {
+        ticker: String,
+        close_time: DateTime<Utc>,
+    },
+    // The market's result is known, e.g. "yes" or "no".
+    Determined {
+        ticker: String,
+        result: String,
+        time: DateTime<Utc>,
+    },
+    // The account was paid out for a position in the market.
+    Settled(Settlement),
+}
+
+impl LifecycleEvent {
+    pub fn ticker(&self) -> &str {
+        match self {
+            LifecycleEvent::Opened { ticker, .. } => ticker,
+            LifecycleEvent::Closed { ticker, .. } => ticker,
+            LifecycleEvent::Determined { ticker, .. } => ticker,
+            LifecycleEvent::Settled(settlement) => &settlement.ticker,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LifecycleConfig {
+    pub tickers: Vec<String>,
+    // How often markets are polled when nothing is about to happen, and settlements while
+    // a determined market with a position hasn't settled.
+    pub poll_interval: Duration,
+    // How often markets are polled within transition_window before an open or close, and
+    // for transition_window after a close while the result is awaited.
+    pub fast_poll_interval: Duration,
+    pub transition_window: Duration,
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        LifecycleConfig {
+            tickers: Vec::new(),
+            poll_interval: Duration::from_secs(60),
+            fast_poll_interval: Duration::from_secs(5),
+            transition_window: Duration::from_secs(300),
+        }
+    }
+}
+
+// What has been reported for one market so far.
+#[derive(Clone, Debug)]
+struct MarketProgress {
+    open_time: DateTime<Utc>,
+    close_time: DateTime<Utc>,
+    opened: bool,
+    closed: bool,
+    // When the result was first seen.
+    determined_time: Option<DateTime<Utc>>,
+    // The account's position once the result is known. None until it has been checked.
+    position: Option<i64>,
+}
+
+// Turns polled markets and settlements into lifecycle events, reporting each transition
+// once. Transitions that had already happened when a market is first seen are reported
+// too, in order. Opening and closing go by the market's open_time and close_time, which
+// are taken from the latest poll since markets can close early.
+#[derive(Clone, Debug, Default)]
+pub struct LifecycleTracker {
+    markets: HashMap<String, MarketProgress>,
+    settled: HashSet<String>,
+    // Settlements at or before this time have already been seen, so paging can stop there.
+    settlements_seen_until: Option<DateTime<Utc>>,
+}
+
+impl LifecycleTracker {
+    pub fn new() -> Self {
+        LifecycleTracker::default()
+    }
+
+    pub fn observe_market(&mut self, market: &Market, now: DateTime<Utc>) -> Vec<LifecycleEvent> {
+        let progress = self
+            .markets
+            .entry(market.ticker.clone())
+            .or_insert_with(|| MarketProgress {
+                open_time: market.open_time,
+                close_time: market.close_time,
+                opened: false,
+                closed: false,
+                determined_time: None,
+                position: None,
+            });
+        progress.open_time = market.open_time;
+        progress.close_time = market.close_time;
+
+        let mut events = Vec::new();
+        let determined = !market.result.is_empty();
+        if !progress.opened && (now >= market.open_time || determined) {
+            progress.opened = true;
+            events.push(LifecycleEvent::Opened {
+                ticker: market.ticker.clone(),
+                open_time: market.open_time,
+            });
+        }
+        if !progress.closed && (now >= market.close_time || determined) {
+            progress.closed = true;
+            events.push(LifecycleEvent::Closed {
+                ticker: market.ticker.clone(),
+                close_time: market.close_time,
+            });
+        }
+        if progress.determined_time.is_none() && determined {
+            progress.determined_time = Some(now);
+            events.push(LifecycleEvent::Determined {
+                ticker: market.ticker.clone(),
+                result: market.result.clone(),
+                time: now,
+            });
+        }
+        events
+    }
+
+    // Settlements for markets that aren't being tracked are ignored.
+    pub fn observe_settlement(&mut self, settlement: &Settlement) -> Option<LifecycleEvent> {
+        if !self.markets.contains_key(&settlement.ticker)
+            || !self.settled.insert(settlement.ticker.clone())
+        {
+            return None;
+        }
+        Some(LifecycleEvent::Settled(settlement.clone()))
+    }
+
+    // Records the account's position in a determined market. Markets without a position
+    // never settle, so settlements stop being polled for them.
+    pub fn observe_position(&mut self, ticker: &str, position: i64) {
+        if let Some(progress) = self.markets.get_mut(ticker) {
+            progress.position = Some(position);
+        }
+    }
+
+    // Determined markets that haven't settled and whose position hasn't been checked yet.
+    pub fn unchecked_positions(&self) -> Vec<String> {
+        self.markets
+            .iter()
+            .filter(|(ticker, progress)| {
+                progress.determined_time.is_some()
+                    && progress.position.is_none()
+                    && !self.settled.contains(*ticker)
+            })
+            .map(|(ticker, _)| ticker.clone())
+            .collect()
+    }
+
+    // Settlements only appear once a market has a result, so they are only worth polling
+    // while a determined market that may hold a position hasn't been settled.
+    pub fn awaiting_settlement(&self) -> bool {
+        self.markets.iter().any(|(ticker, progress)| {
+            progress.determined_time.is_some()
+                && progress.position != Some(0)
+                && !self.settled.contains(ticker)
+        })
+    }
+
+    // How long to wait before polling again. Polling speeds up within transition_window
+    // before the next open or close, and for transition_window after a close while the
+    // result is awaited. Otherwise the regular interval is cut short so a window starts
+    // on time.
+    pub fn next_poll_delay(&self, now: DateTime<Utc>, config: &LifecycleConfig) -> Duration {
+        let fast = config.fast_poll_interval.min(config.poll_interval);
+        let mut delay = config.poll_interval;
+        for progress in self.markets.values() {
+            let next_transition = if !progress.opened {
+                progress.open_time
+            } else if !progress.closed {
+                progress.close_time
+            } else if progress.determined_time.is_none() {
+                let since_close = (now - progress.close_time).to_std().unwrap_or_default();
+                if since_close <= config.transition_window {
+                    return fast;
+                }
+                continue;
+            } else {
+                continue;
+            };
+            // A transition already passed but not yet seen counts as inside the window.
+            let until = (next_transition - now).to_std().unwrap_or_default();
+            let until_window = until.saturating_sub(config.transition_window);
+            if until_window.is_zero() {
+                return fast;
+            }
+            delay = delay.min(until_window);
+        }
+        delay
+    }
+}
+
+// Polls the configured markets with get_market_async, and get_settlements_async once a
+// market with a position has a result, until it settles, reporting lifecycle events as
+// they happen. Polling errors are logged and retried on the next poll, and don't hold up
+// events for other markets.
+pub struct LifecycleWatcher {
+    events: mpsc::Receiver<LifecycleEvent>,
+    handle: JoinHandle<()>,
+}
+
+impl LifecycleWatcher {
+    pub fn spawn<B: MarketData + Portfolio + 'static>(backend: B, config: LifecycleConfig) -> Self {
+        let (sender, events) = mpsc::channel(LIFECYCLE_EVENT_CHANNEL_SIZE);
+        // The spawned task doesn't inherit a base URL set with with_api_base_url.
+        let base_url = environment::api_base_url();
+        let watch = async move {
+            let mut tracker = LifecycleTracker::new();
+            loop {
+                for event in poll_async(&backend, &config, &mut tracker).await {
+                    info!("Market lifecycle event: {:?}", event);
+                    if sender.send(event).await.is_err() {
+                        // Nobody is listening any more.
+                        return;
+                    }
+                }
+                tokio::time::sleep(tracker.next_poll_delay(Utc::now(), &config)).await;
+            }
+        };
+        let handle = tokio::spawn(async move {
+            environment::with_api_base_url(&base_url, watch).await;
+        });
+        LifecycleWatcher { events, handle }
+    }
+
+    // Waits for the next event. Returns None once the watcher has stopped.
+    pub async fn next_event(&mut self) -> Option<LifecycleEvent> {
+        self.events.recv().await
+    }
+
+    // Stops polling. Dropping the watcher does the same.
+    pub fn stop(self) {
+        drop(self);
+    }
+}
+
+impl Drop for LifecycleWatcher {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+// The tracker is updated as each response arrives, so everything it reports is returned
+// even if a later request fails.
+async fn poll_async<B: MarketData + Portfolio + ?Sized>(
+    backend: &B,
+    config: &LifecycleConfig,
+    tracker: &mut LifecycleTracker,
+) -> Vec<LifecycleEvent> {
+    let mut events = Vec::new();
+    for ticker in &config.tickers {
+        match backend.get_market_async(ticker).await {
+            Ok(response) => events.extend(tracker.observe_market(&response.market, Utc::now())),
+            Err(e) => eprintln!(
+                "Lifecycle watcher failed to poll market '{}' - {}",
+                ticker, e
+            ),
+        }
+    }
+    if !tracker.awaiting_settlement() {
+        return events;
+    }
+
+    // Positions are checked before settlements, so a market that settled in between still
+    // has its settlement picked up below.
+    let mut positions = Vec::new();
+    for ticker in tracker.unchecked_positions() {
+        match backend
+            .get_positions_async(None, None, None, Some(&ticker), None)
+            .await
+        {
+            Ok(response) => {
+                let position = response
+                    .market_positions
+                    .iter()
+                    .find(|position| position.ticker == ticker)
+                    .map_or(0, |position| position.position);
+                positions.push((ticker, position));
+            }
+            Err(e) => eprintln!(
+                "Lifecycle watcher failed to poll the position in '{}' - {}",
+                ticker, e
+            ),
+        }
+    }
+
+    // The exchange lists settlements newest first, so paging stops at the first page that
+    // reaches settlements seen on an earlier poll.
+    let seen_until = tracker.settlements_seen_until;
+    let mut newest = seen_until;
+    let mut cursor: Option<String> = None;
+    loop {
+        let page = match backend
+            .get_settlements_async(Some(SETTLEMENTS_PAGE_SIZE), cursor)
+            .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                eprintln!("Lifecycle watcher failed to poll settlements - {}", e);
+                return events;
+            }
+        };
+        events.extend(
+            page.settlements
+                .iter()
+                .filter_map(|settlement| tracker.observe_settlement(settlement)),
+        );
+        newest = page
+            .settlements
+            .iter()
+            .map(|settlement| settlement.settled_time)
+            .chain(newest)
+            .max();
+        let reached_seen = page.settlements.iter().any(|settlement| {
+            seen_until.is_some_and(|seen_until| settlement.settled_time <= seen_until)
+        });
+        if page.cursor.is_empty() || reached_seen {
+            break;
+        }
+        cursor = Some(page.cursor);
+    }
+    // Recorded only after a complete poll, so a failed one is repeated in full.
+    tracker.settlements_seen_until = newest;
+    for (ticker, position) in positions {
+        tracker.observe_position(&ticker, position);
+    }
+    events
+}
@@ -1,63 +1,999 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use reqwest::Client;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use kalshi_api::auth;
-use kalshi_api::bids;
-use kalshi_api::exchange;
-use kalshi_api::market;
+use kalshi_api::arbitrage::{self, ArbitrageConfig, ArbitrageOpportunity};
+use kalshi_api::auth::{self, CredentialProvider, EnvCredentials, FileCredentials};
+use kalshi_api::client::{KalshiClient, MarketData, Portfolio, Trading};
+use kalshi_api::environment;
+use kalshi_api::exchange::{self, ExchangeStatus};
+use kalshi_api::export::{AccountHistory, ExportFormat};
+use kalshi_api::fees::{FeeSchedule, OrderCostEstimate};
+use kalshi_api::market::api_structs::Event;
+use kalshi_api::market::Market;
+use kalshi_api::money::{Cents, Price};
+use kalshi_api::portfolio::{Balance, CreateOrderRequest, Fill, MarketPosition, Order, Settlement};
+#[cfg(feature = "sqlite")]
+use kalshi_api::recorder::SqliteStore;
+use kalshi_api::recorder::{CsvStore, Recorder, RecorderConfig};
+use kalshi_api::scanner::{self, MarketFilter, Ranking, ScanConfig, ScanResult};
 
-const TSA_EVENT_TICKER: &str = "TSAW-23APR02";
+// Command line client for the Kalshi API. Commands that need an account log in with the
+// EMAIL and PASS environment variables, or a --credentials file, and log out again when
+// they are done.
+#[derive(Parser)]
+#[command(
+    name = "kalshi",
+    version,
+    about = "Command line client for the Kalshi API"
+)]
+struct Cli {
+    #[arg(long, value_enum, global = true, default_value_t = Env::Prod)]
+    env: Env,
+
+    #[arg(short, long, value_enum, global = true, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
+    /// Send requests to this base URL instead, e.g. a local mock server
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+
+    /// Log in with the email and password in this JSON file instead of the EMAIL and PASS
+    /// environment variables
+    #[arg(long, global = true)]
+    credentials: Option<PathBuf>,
+
+    /// Also print the library's request logging to stdout
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Env {
+    Prod,
+    Demo,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show whether the exchange and trading are active
+    Status,
+    /// List or look up events
+    #[command(subcommand)]
+    Events(EventsCommand),
+    /// List or look up markets and their orderbooks
+    #[command(subcommand)]
+    Markets(MarketsCommand),
+    /// Create, list or cancel orders
+    #[command(subcommand)]
+    Orders(OrdersCommand),
+    /// Show market positions
+    Positions {
+        #[arg(long)]
+        ticker: Option<String>,
+        #[arg(long)]
+        event: Option<String>,
+        /// all, settled or unsettled
+        #[arg(long)]
+        settlement_status: Option<String>,
+        #[command(flatten)]
+        page: Page,
+    },
+    /// Show fills
+    Fills {
+        #[arg(long)]
+        ticker: Option<String>,
+        #[arg(long)]
+        order_id: Option<String>,
+        #[command(flatten)]
+        page: Page,
+    },
+    /// Show settlements
+    Settlements {
+        #[command(flatten)]
+        page: Page,
+    },
+    /// Show the account balance
+    Balance,
+    /// Look for arbitrage across the markets of mutually exclusive events
+    Arbitrage {
+        event_tickers: Vec<String>,
+        #[arg(long, default_value_t = 20)]
+        depth: i32,
+    },
+    /// Rank markets, e.g. --filter 'spread<=3' --filter 'category=Economics' --rank volume_24h
+    Scan {
+        /// Filter expressions over spread, yes_bid, yes_ask, last_price, volume, volume_24h,
+        /// liquidity, open_interest, hours_to_close or category
+        #[arg(long = "filter")]
+        filters: Vec<MarketFilter>,
+        /// Field to rank by, optionally followed by :asc or :desc
+        #[arg(long, default_value = "volume_24h:desc")]
+        rank: Ranking,
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+        /// open, closed or settled
+        #[arg(long, default_value = "open")]
+        status: String,
+    },
+    /// Record market, orderbook and trade snapshots until interrupted
+    Record {
+        #[arg(long = "series")]
+        series_tickers: Vec<String>,
+        #[arg(long = "event")]
+        event_tickers: Vec<String>,
+        /// Directory for markets.csv, orderbooks.csv and trades.csv
+        #[arg(long)]
+        csv_dir: Option<PathBuf>,
+        /// SQLite database to write to instead, when built with the sqlite feature
+        #[arg(long)]
+        sqlite: Option<PathBuf>,
+        /// Seconds between snapshots
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+        #[arg(long, default_value_t = 10)]
+        depth: i32,
+    },
+    /// Export fills, orders, settlements and a tax-lot summary for accounting
+    Export {
+        /// Start of the range, as a date or an RFC 3339 time
+        #[arg(long, value_parser = parse_time)]
+        from: Option<DateTime<Utc>>,
+        /// End of the range, as a date or an RFC 3339 time
+        #[arg(long, value_parser = parse_time)]
+        to: Option<DateTime<Utc>>,
+        /// Directory to write the files to
+        #[arg(long)]
+        dir: PathBuf,
+        /// csv or jsonl
+        #[arg(long, default_value = "csv")]
+        format: ExportFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum EventsCommand {
+    List {
+        /// open, closed or settled
+        #[arg(long)]
+        status: Option<String>,
+        #[arg(long)]
+        series: Option<String>,
+        #[command(flatten)]
+        page: Page,
+    },
+    Get {
+        event_ticker: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum MarketsCommand {
+    List {
+        #[arg(long)]
+        event: Option<String>,
+        #[arg(long)]
+        series: Option<String>,
+        /// open, closed or settled
+        #[arg(long)]
+        status: Option<String>,
+        #[command(flatten)]
+        page: Page,
+    },
+    Get {
+        ticker: String,
+    },
+    Book {
+        ticker: String,
+        #[arg(long, default_value_t = 10)]
+        depth: i32,
+    },
+}
+
+#[derive(Subcommand)]
+enum OrdersCommand {
+    Create(CreateOrderArgs),
+    List {
+        #[arg(long)]
+        ticker: Option<String>,
+        #[arg(long)]
+        event: Option<String>,
+        /// resting, canceled or executed
+        #[arg(long)]
+        status: Option<String>,
+        #[command(flatten)]
+        page: Page,
+    },
+    Cancel {
+        order_id: String,
+    },
+}
+
+#[derive(Args)]
+struct CreateOrderArgs {
+    #[arg(long)]
+    ticker: String,
+    #[arg(long, value_parser = ["buy", "sell"])]
+    action: String,
+    #[arg(long, value_parser = ["yes", "no"])]
+    side: String,
+    #[arg(long)]
+    count: i32,
+    #[arg(long = "type", value_parser = ["limit", "market"], default_value = "limit")]
+    type_: String,
+    /// Limit price in cents for the yes side
+    #[arg(long, value_parser = parse_price, conflicts_with = "no_price")]
+    yes_price: Option<Price>,
+    /// Limit price in cents for the no side
+    #[arg(long, value_parser = parse_price)]
+    no_price: Option<Price>,
+    /// Maximum cost in cents of a market buy
+    #[arg(long)]
+    buy_max_cost: Option<i64>,
+    #[arg(long)]
+    client_order_id: Option<String>,
+    /// Show what the order would cost including fees instead of sending it
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[derive(Args)]
+struct Page {
+    #[arg(long)]
+    limit: Option<i32>,
+    #[arg(long)]
+    cursor: Option<String>,
+}
+
+fn parse_price(value: &str) -> Result<Price, String> {
+    let cents: i64 = value.parse().map_err(|e| format!("{}", e))?;
+    Price::try_from(cents).map_err(|e| format!("{}", e))
+}
+
+// Dates are taken as midnight UTC.
+fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = value.parse::<NaiveDate>() {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    value.parse().map_err(|e| format!("{}", e))
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let client = Client::new();
+async fn main() {
+    let cli = Cli::parse();
+    environment::set_logging_enabled(cli.verbose);
+    match (&cli.base_url, cli.env) {
+        (Some(base_url), _) => environment::set_default_api_base_url(base_url),
+        (None, Env::Demo) => environment::set_default_api_base_url(environment::DEMO_API_URL),
+        (None, Env::Prod) => {}
+    }
 
-    println!("Checking if exchange is open");
-    match exchange::get_exchange_status_async(&client).await {
-        Ok(status) => {
-            println!("Exchange status: {}", status.exchange_active);
-            println!("Trading status: {}", status.trading_active);
-        }
-        Err(e) => eprintln!("Error fetching exchange status - {}", e),
+    if let Err(e) = run(cli).await {
+        eprintln!("Error - {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let credentials: Box<dyn CredentialProvider> = match &cli.credentials {
+        Some(path) => Box::new(FileCredentials::new(path)),
+        None => Box::new(EnvCredentials::default()),
     };
+    let mut session = Session {
+        client: Client::new(),
+        credentials,
+        token: None,
+    };
+    let result = run_command(&mut session, cli.command, cli.output).await;
+    if let Err(e) = session.logout().await {
+        eprintln!("Error while logging out - {}", e);
+    }
+    result
+}
 
-    println!("Logging into Kalshi API");
-    let token = auth::login(&client).await;
-
-    match token {
-        Ok(token) => {
-            match market::get_event_async(&client, TSA_EVENT_TICKER, &token).await {
-                Ok(event) => {
-                    for market in event.markets {
-                        let bids: bids::Bids = market.clone().into();
-
-                        println!("Current market '{}' bids: {:?}", market.ticker, bids);
-
-                        match market::get_market_orderbook_async(&client, &market.ticker, 4, &token)
-                            .await
-                        {
-                            Ok(_) => {
-                                println!("Successfully fetched market orderbook");
-                            }
-                            Err(e) => eprintln!("Error fetching market - {}", e),
-                        };
-                    }
-                }
-                Err(e) => eprintln!("Error fetching event - {}", e),
-            };
+// Logs in the first time a command needs an account, so commands that don't, like status
+// or a dry run, work without credentials.
+struct Session {
+    client: Client,
+    credentials: Box<dyn CredentialProvider>,
+    token: Option<String>,
+}
 
-            match auth::logout(client, &token).await {
-                Ok(()) => {
-                    println!("Logged out successfully");
-                }
-                Err(e) => {
-                    eprintln!("Error while logging out - {}", e);
+impl Session {
+    async fn login(&mut self) -> Result<KalshiClient, Box<dyn std::error::Error + Send + Sync>> {
+        let token = match &self.token {
+            Some(token) => token.clone(),
+            None => {
+                let credentials = self.credentials.credentials()?;
+                let token = auth::login_with(&self.client, &credentials).await?;
+                self.token = Some(token.clone());
+                token
+            }
+        };
+        Ok(KalshiClient::new(self.client.clone(), token))
+    }
+
+    async fn logout(self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self.token {
+            Some(token) => auth::logout(self.client, &token).await,
+            None => Ok(()),
+        }
+    }
+}
+
+async fn run_command(
+    session: &mut Session,
+    command: Command,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match command {
+        Command::Status => {
+            let status = exchange::get_exchange_status_async(&session.client).await?;
+            print_rows(format, &[status])
+        }
+        Command::Events(EventsCommand::List {
+            status,
+            series,
+            page,
+        }) => {
+            let response = session
+                .login()
+                .await?
+                .get_events_async(
+                    page.limit.map(i64::from),
+                    page.cursor.as_deref(),
+                    status.as_deref(),
+                    series.as_deref(),
+                )
+                .await?;
+            print_next_cursor(&response.cursor);
+            print_rows(format, &response.events)
+        }
+        Command::Events(EventsCommand::Get { event_ticker }) => {
+            let response = session
+                .login()
+                .await?
+                .get_event_async(&event_ticker)
+                .await?;
+            match format {
+                OutputFormat::Json => return print_json(&response),
+                // One CSV table per output; the event itself is available with events list.
+                OutputFormat::Csv => return print_rows(format, &response.markets),
+                OutputFormat::Table => {}
+            }
+            print_rows(format, &[response.event])?;
+            println!();
+            print_rows(format, &response.markets)
+        }
+        Command::Markets(MarketsCommand::List {
+            event,
+            series,
+            status,
+            page,
+        }) => {
+            let response = session
+                .login()
+                .await?
+                .get_markets_async(
+                    page.limit.map(i64::from),
+                    page.cursor.as_deref(),
+                    event.as_deref(),
+                    series.as_deref(),
+                    None,
+                    None,
+                    status.as_deref(),
+                    None,
+                )
+                .await?;
+            print_next_cursor(&response.cursor);
+            print_rows(format, &response.markets)
+        }
+        Command::Markets(MarketsCommand::Get { ticker }) => {
+            let response = session.login().await?.get_market_async(&ticker).await?;
+            print_rows(format, &[response.market])
+        }
+        Command::Markets(MarketsCommand::Book { ticker, depth }) => {
+            let orderbook = session
+                .login()
+                .await?
+                .get_market_orderbook_async(&ticker, depth)
+                .await?
+                .orderbook;
+            let levels: Vec<BookLevel> = [("yes", &orderbook.yes), ("no", &orderbook.no)]
+                .into_iter()
+                .flat_map(|(side, levels)| {
+                    levels
+                        .iter()
+                        .filter(|level| level.len() == 2)
+                        .map(move |level| BookLevel {
+                            side,
+                            price: level[0],
+                            count: level[1],
+                        })
+                })
+                .collect();
+            print_rows(format, &levels)
+        }
+        Command::Orders(OrdersCommand::Create(args)) if args.dry_run => {
+            print_estimate(&args, format)
+        }
+        Command::Orders(OrdersCommand::Create(args)) => {
+            let request = CreateOrderRequest {
+                action: args.action,
+                buy_max_cost: args.buy_max_cost.map(Cents),
+                client_order_id: args
+                    .client_order_id
+                    .unwrap_or_else(|| format!("kalshi-cli-{}", Utc::now().timestamp_millis())),
+                count: args.count,
+                no_price: args.no_price,
+                side: args.side,
+                ticker: args.ticker,
+                type_: args.type_,
+                yes_price: args.yes_price,
+                ..CreateOrderRequest::default()
+            };
+            let response = session.login().await?.create_order_async(&request).await?;
+            print_rows(format, &[response.order])
+        }
+        Command::Orders(OrdersCommand::List {
+            ticker,
+            event,
+            status,
+            page,
+        }) => {
+            let response = session
+                .login()
+                .await?
+                .get_orders_async(
+                    ticker.as_deref(),
+                    event.as_deref(),
+                    None,
+                    None,
+                    status.as_deref(),
+                    page.cursor.as_deref(),
+                    page.limit,
+                )
+                .await?;
+            print_next_cursor(&response.cursor);
+            print_rows(format, &response.orders)
+        }
+        Command::Orders(OrdersCommand::Cancel { order_id }) => {
+            let response = session.login().await?.cancel_order_async(&order_id).await?;
+            print_rows(format, &[response.order])
+        }
+        Command::Positions {
+            ticker,
+            event,
+            settlement_status,
+            page,
+        } => {
+            let response = session
+                .login()
+                .await?
+                .get_positions_async(
+                    page.cursor.as_deref(),
+                    page.limit,
+                    settlement_status.as_deref(),
+                    ticker.as_deref(),
+                    event.as_deref(),
+                )
+                .await?;
+            print_next_cursor(&response.cursor);
+            print_rows(format, &response.market_positions)
+        }
+        Command::Fills {
+            ticker,
+            order_id,
+            page,
+        } => {
+            let response = session
+                .login()
+                .await?
+                .get_fills_async(
+                    ticker.as_deref(),
+                    order_id.as_deref(),
+                    None,
+                    None,
+                    page.limit,
+                    page.cursor.as_deref(),
+                )
+                .await?;
+            print_next_cursor(&response.cursor);
+            print_rows(format, &response.fills)
+        }
+        Command::Settlements { page } => {
+            let response = session
+                .login()
+                .await?
+                .get_settlements_async(page.limit.map(i64::from), page.cursor)
+                .await?;
+            print_next_cursor(&response.cursor);
+            print_rows(format, &response.settlements)
+        }
+        Command::Balance => {
+            let balance = session.login().await?.get_balance_async().await?;
+            print_rows(format, &[balance])
+        }
+        Command::Arbitrage {
+            event_tickers,
+            depth,
+        } => {
+            let backend = session.login().await?;
+            let mut opportunities = Vec::new();
+            for event_ticker in &event_tickers {
+                opportunities.extend(
+                    arbitrage::find_event_arbitrage_async(
+                        &backend,
+                        event_ticker,
+                        depth,
+                        &ArbitrageConfig::default(),
+                    )
+                    .await?,
+                );
+            }
+            print_rows(format, &opportunities)
+        }
+        Command::Scan {
+            filters,
+            rank,
+            top,
+            status,
+        } => {
+            let config = ScanConfig {
+                filters,
+                ranking: rank,
+                top_n: top,
+                status: Some(status),
+                ..ScanConfig::default()
+            };
+            let results = scanner::scan_markets_async(&session.login().await?, &config).await?;
+            print_rows(format, &results)
+        }
+        Command::Record {
+            series_tickers,
+            event_tickers,
+            csv_dir,
+            sqlite,
+            interval,
+            depth,
+        } => {
+            let config = RecorderConfig {
+                series_tickers,
+                event_tickers,
+                orderbook_depth: depth,
+                interval: Duration::from_secs(interval),
+                ..RecorderConfig::default()
+            };
+            let backend = session.login().await?;
+            let shutdown = async {
+                let _ = tokio::signal::ctrl_c().await;
+            };
+            if let Some(path) = sqlite {
+                #[cfg(feature = "sqlite")]
+                {
+                    let store = SqliteStore::open(path)?;
+                    Recorder::new(backend, config, store).run(shutdown).await;
+                    return Ok(());
                 }
+                #[cfg(not(feature = "sqlite"))]
+                return Err(format!(
+                    "Can't record to {}, kalshi was built without the sqlite feature",
+                    path.display()
+                )
+                .into());
             }
+            let dir = csv_dir.ok_or("record needs --csv-dir or --sqlite")?;
+            let store = CsvStore::open(dir)?;
+            Recorder::new(backend, config, store).run(shutdown).await;
+            Ok(())
         }
-        Err(e) => {
-            eprintln!("Login unsuccessful - {}", e);
+        Command::Export {
+            from,
+            to,
+            dir,
+            format,
+        } => {
+            let history = AccountHistory::fetch_async(&session.login().await?, from, to).await?;
+            for path in history.write_dir(&dir, format)? {
+                eprintln!("Wrote {}", path.display());
+            }
+            Ok(())
         }
+    }
+}
+
+// Prices the order with the default fee schedule, assuming it fills as a taker.
+fn print_estimate(
+    args: &CreateOrderArgs,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let request = CreateOrderRequest {
+        action: args.action.clone(),
+        count: args.count,
+        side: args.side.clone(),
+        ticker: args.ticker.clone(),
+        type_: args.type_.clone(),
+        yes_price: args.yes_price,
+        no_price: args.no_price,
+        ..CreateOrderRequest::default()
     };
+    let estimate = FeeSchedule::default()
+        .estimate_order(&request)
+        .ok_or("A --yes-price or --no-price is needed to estimate the cost")?;
+    print_rows(format, &[estimate])
+}
 
+// Goes to stderr so stdout stays parseable.
+fn print_next_cursor(cursor: &str) {
+    if !cursor.is_empty() {
+        eprintln!("More results with --cursor {}", cursor);
+    }
+}
+
+// A record that can be printed as a table or CSV row as well as JSON.
+trait Row: Serialize {
+    fn headers() -> &'static [&'static str];
+    fn cells(&self) -> Vec<String>;
+}
+
+fn print_rows<T: Row>(
+    format: OutputFormat,
+    rows: &[T],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match format {
+        OutputFormat::Json => {
+            if let [row] = rows {
+                print_json(row)
+            } else {
+                print_json(&rows)
+            }
+        }
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            writer.write_record(T::headers())?;
+            for row in rows {
+                writer.write_record(row.cells())?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+        OutputFormat::Table => {
+            let cells: Vec<Vec<String>> = rows.iter().map(Row::cells).collect();
+            let widths: Vec<usize> = T::headers()
+                .iter()
+                .enumerate()
+                .map(|(i, header)| {
+                    cells
+                        .iter()
+                        .map(|row| row[i].chars().count())
+                        .chain([header.len()])
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            let mut stdout = std::io::stdout().lock();
+            let headers: Vec<String> = T::headers().iter().map(|h| h.to_string()).collect();
+            for row in std::iter::once(&headers).chain(cells.iter()) {
+                let line: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect();
+                writeln!(stdout, "{}", line.join("  ").trim_end())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_json<T: Serialize + ?Sized>(
+    value: &T,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
     Ok(())
 }
+
+#[derive(Serialize)]
+struct BookLevel {
+    side: &'static str,
+    price: i32,
+    count: i32,
+}
+
+impl Row for BookLevel {
+    fn headers() -> &'static [&'static str] {
+        &["side", "price", "count"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.side.to_string(),
+            self.price.to_string(),
+            self.count.to_string(),
+        ]
+    }
+}
+
+impl Row for ExchangeStatus {
+    fn headers() -> &'static [&'static str] {
+        &["exchange_active", "trading_active"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.exchange_active.to_string(),
+            self.trading_active.to_string(),
+        ]
+    }
+}
+
+impl Row for Event {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "event_ticker",
+            "series_ticker",
+            "category",
+            "mutually_exclusive",
+            "title",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.event_ticker.clone(),
+            self.series_ticker.clone(),
+            self.category.clone(),
+            self.mutually_exclusive.to_string(),
+            self.title.clone(),
+        ]
+    }
+}
+
+impl Row for Market {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "ticker",
+            "yes_bid",
+            "yes_ask",
+            "last_price",
+            "volume",
+            "open_interest",
+            "close_time",
+            "subtitle",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.ticker.clone(),
+            self.yes_bid.value().to_string(),
+            self.yes_ask.value().to_string(),
+            self.last_price.value().to_string(),
+            self.volume.to_string(),
+            self.open_interest.to_string(),
+            self.close_time.to_rfc3339(),
+            self.subtitle.clone(),
+        ]
+    }
+}
+
+impl Row for Order {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "order_id",
+            "ticker",
+            "status",
+            "action",
+            "side",
+            "type",
+            "yes_price",
+            "no_price",
+            "remaining_count",
+            "created_time",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.order_id.clone(),
+            self.ticker.clone(),
+            self.status.clone(),
+            self.action.clone(),
+            self.side.clone(),
+            self.type_.clone(),
+            self.yes_price.value().to_string(),
+            self.no_price.value().to_string(),
+            self.remaining_count.to_string(),
+            self.created_time.to_rfc3339(),
+        ]
+    }
+}
+
+impl Row for MarketPosition {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "ticker",
+            "position",
+            "market_exposure",
+            "realized_pnl",
+            "fees_paid",
+            "total_cost",
+            "resting_order_count",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.ticker.clone(),
+            self.position.to_string(),
+            self.market_exposure.value().to_string(),
+            self.realized_pnl.value().to_string(),
+            self.fees_paid.value().to_string(),
+            self.total_cost.value().to_string(),
+            self.resting_order_count.to_string(),
+        ]
+    }
+}
+
+impl Row for Fill {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "trade_id",
+            "order_id",
+            "ticker",
+            "action",
+            "side",
+            "count",
+            "yes_price",
+            "no_price",
+            "is_taker",
+            "created_time",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.trade_id.clone(),
+            self.order_id.clone(),
+            self.ticker.clone(),
+            self.action.clone(),
+            self.side.clone(),
+            self.count.to_string(),
+            self.yes_price.value().to_string(),
+            self.no_price.value().to_string(),
+            self.is_taker.to_string(),
+            self.created_time.to_rfc3339(),
+        ]
+    }
+}
+
+impl Row for Settlement {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "ticker",
+            "market_result",
+            "yes_count",
+            "no_count",
+            "yes_total_cost",
+            "no_total_cost",
+            "revenue",
+            "settled_time",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.ticker.clone(),
+            self.market_result.clone(),
+            self.yes_count.to_string(),
+            self.no_count.to_string(),
+            self.yes_total_cost.value().to_string(),
+            self.no_total_cost.value().to_string(),
+            self.revenue.value().to_string(),
+            self.settled_time.to_rfc3339(),
+        ]
+    }
+}
+
+impl Row for Balance {
+    fn headers() -> &'static [&'static str] {
+        &["balance"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.balance.value().to_string()]
+    }
+}
+
+impl Row for ScanResult {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "ticker",
+            "score",
+            "yes_bid",
+            "yes_ask",
+            "volume_24h",
+            "liquidity",
+            "open_interest",
+            "close_time",
+            "category",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.market.ticker.clone(),
+            self.score.to_string(),
+            self.market.yes_bid.value().to_string(),
+            self.market.yes_ask.value().to_string(),
+            self.market.volume_24h.to_string(),
+            self.market.liquidity.value().to_string(),
+            self.market.open_interest.to_string(),
+            self.market.close_time.to_rfc3339(),
+            self.market.category.clone(),
+        ]
+    }
+}
+
+impl Row for ArbitrageOpportunity {
+    fn headers() -> &'static [&'static str] {
+        &[
+            "event_ticker",
+            "side",
+            "sets",
+            "cost",
+            "fees",
+            "payout",
+            "profit",
+            "legs",
+        ]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        let legs: Vec<String> = self
+            .legs
+            .iter()
+            .map(|leg| format!("{}@{}", leg.ticker, leg.limit_price.value()))
+            .collect();
+        vec![
+            self.event_ticker.clone(),
+            self.side.side().to_string(),
+            self.sets.to_string(),
+            self.cost.value().to_string(),
+            self.fees.value().to_string(),
+            self.payout.value().to_string(),
+            self.profit.value().to_string(),
+            legs.join(" "),
+        ]
+    }
+}
+
+impl Row for OrderCostEstimate {
+    fn headers() -> &'static [&'static str] {
+        &["price", "count", "notional", "fees", "all_in"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.price.value().to_string(),
+            self.count.to_string(),
+            self.notional.value().to_string(),
+            self.fees.value().to_string(),
+            self.all_in.value().to_string(),
+        ]
+    }
+}
@@ -1,11 +1,13 @@
-use serde::Deserialize;
+use crate::money::{Cents, Price};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::vec::Vec;
 
 // TODO: API Discrepancies are commented out. Specifically, the "strike" fields seem to
 //       be present on the api documentation, but not on the actual responses, which
 //       causes deserialization errors
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Event {
     pub category: String,
     pub event_ticker: String,
@@ -17,70 +19,123 @@ pub struct Event {
     pub title: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Market {
     pub can_close_early: bool,
     // cap_strike: Strike,
     pub category: String,
-    pub close_time: String,
+    pub close_time: DateTime<Utc>,
     // custom_strike: Strike,
     pub event_ticker: String,
-    pub expiration_time: String,
+    pub expiration_time: DateTime<Utc>,
     pub expiration_value: String,
     // floor_strike: Strike,
     pub floor_strike: i32,
-    pub last_price: i32,
-    pub liquidity: i32,
-    pub no_ask: i32,
-    pub no_bid: i32,
+    pub last_price: Cents,
+    pub liquidity: Cents,
+    pub no_ask: Cents,
+    pub no_bid: Cents,
     pub open_interest: i32,
-    pub open_time: String,
-    pub previous_price: i32,
-    pub previous_yes_ask: i32,
-    pub previous_yes_bid: i32,
+    pub open_time: DateTime<Utc>,
+    pub previous_price: Cents,
+    pub previous_yes_ask: Cents,
+    pub previous_yes_bid: Cents,
     pub result: String,
-    pub risk_limit_cents: i32,
+    pub risk_limit_cents: Cents,
     pub strike_type: String,
     pub subtitle: String,
     pub ticker: String,
     pub volume: i32,
     pub volume_24h: i32,
-    pub yes_ask: i32,
-    pub yes_bid: i32,
+    pub yes_ask: Cents,
+    pub yes_bid: Cents,
 }
 
 // TODO: We know the second vector has a size of 2 since it contains a (price, count) pair
 //       Can we use this to make the following struct more efficient?
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Orderbook {
     pub no: Vec<Vec<i32>>,
     pub yes: Vec<Vec<i32>>,
 }
 
-#[derive(Debug, Deserialize)]
+// A single execution on the exchange. taker_side is the side ("yes" or "no") that crossed
+// the spread.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Trade {
+    pub count: i32,
+    pub created_time: DateTime<Utc>,
+    pub no_price: Price,
+    pub taker_side: String,
+    pub ticker: String,
+    pub trade_id: String,
+    pub yes_price: Price,
+}
+
+// Open/high/low/close over one candlestick period. Missing when nothing happened.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CandlestickPrices {
+    #[serde(default)]
+    pub open: Option<Cents>,
+    #[serde(default)]
+    pub high: Option<Cents>,
+    #[serde(default)]
+    pub low: Option<Cents>,
+    #[serde(default)]
+    pub close: Option<Cents>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Candlestick {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub end_period_ts: DateTime<Utc>,
+    pub open_interest: i64,
+    // Prices of trades during the period.
+    #[serde(default)]
+    pub price: CandlestickPrices,
+    pub volume: i64,
+    #[serde(default)]
+    pub yes_ask: CandlestickPrices,
+    #[serde(default)]
+    pub yes_bid: CandlestickPrices,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GetEventResponse {
     pub event: Event,
     pub markets: Vec<Market>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GetEventsResponse {
     pub cursor: String,
     pub events: Vec<Event>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GetMarketResponse {
     pub market: Market,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GetMarketsResponse {
     pub cursor: String,
     pub markets: Vec<Market>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GetMarketOrderbookResponse {
     pub orderbook: Orderbook,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetTradesResponse {
+    pub cursor: String,
+    pub trades: Vec<Trade>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetMarketCandlesticksResponse {
+    pub candlesticks: Vec<Candlestick>,
+    pub ticker: String,
+}
@@ -1,18 +1,24 @@
+use crate::environment;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
 pub use crate::market::api_structs::{
-    GetEventResponse, GetMarketOrderbookResponse, GetMarketResponse, Market,
+    Candlestick, CandlestickPrices, GetEventResponse, GetMarketCandlesticksResponse,
+    GetMarketOrderbookResponse, GetMarketResponse, GetTradesResponse, Market, Orderbook, Trade,
 };
 
 use self::api_structs::{GetEventsResponse, GetMarketsResponse};
 
 pub mod api_structs;
 
-const GET_EVENT_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/events/";
-const GET_EVENTS_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/events/";
-const GET_MARKET_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/markets/";
-const GET_MARKETS_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/markets/";
+const GET_EVENT_PATH: &str = "events/";
+const GET_EVENTS_PATH: &str = "events/";
+const GET_MARKET_PATH: &str = "markets/";
+const GET_MARKETS_PATH: &str = "markets/";
 const MARKET_ORDERBOOK_SUFFIX: &str = "/orderbook";
+const GET_TRADES_PATH: &str = "markets/trades";
+const GET_SERIES_PATH: &str = "series/";
+const MARKET_CANDLESTICKS_SUFFIX: &str = "/candlesticks";
 
 pub async fn get_event_async(
     client: &reqwest::Client,
@@ -20,9 +26,9 @@ pub async fn get_event_async(
     token: &str,
 ) -> Result<GetEventResponse, Box<dyn std::error::Error + Send + Sync>> {
     // TODO: Sanitize event_ticker?
-    let url = reqwest::Url::parse(GET_EVENT_URL)?;
+    let url = environment::api_url(GET_EVENT_PATH)?;
     let url = url.join(event_ticker)?;
-    println!(
+    info!(
         "Fetching details for event '{}' at endpoint '{}'",
         event_ticker, url
     );
@@ -40,14 +46,14 @@ pub async fn get_event_async(
             if result.status().is_success() {
                 match result.json::<GetEventResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed event response body: {:?}", body);
+                        info!("Successfully parsed event response body: {:?}", body);
                         for market in body.markets.clone() {
-                            println!("Market: {:?}", market);
+                            info!("Market: {:?}", market);
                         }
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse event response body. Error - {}", e);
+                        info!("Unable to parse event response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -77,7 +83,7 @@ pub async fn get_events_async(
     status: Option<&str>, // open, closed, settled
     series_ticker: Option<&str>,
 ) -> Result<GetEventsResponse, Box<dyn std::error::Error + Send + Sync>> {
-    let url = reqwest::Url::parse(GET_EVENTS_URL)?;
+    let url = environment::api_url(GET_EVENTS_PATH)?;
     let mut query_params: HashMap<String, String> = HashMap::new();
     if let Some(limit) = limit {
         query_params.insert("limit".to_string(), limit.to_string());
@@ -91,7 +97,7 @@ pub async fn get_events_async(
     if let Some(series_ticker) = series_ticker {
         query_params.insert("series_ticker".to_string(), series_ticker.to_string());
     }
-    println!("Fetching events at endpoint '{}'", url);
+    info!("Fetching events at endpoint '{}'", url);
     match client
         .get(url)
         .bearer_auth(token)
@@ -107,11 +113,11 @@ pub async fn get_events_async(
             if result.status().is_success() {
                 match result.json::<GetEventsResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed events response body: {:?}", body);
+                        info!("Successfully parsed events response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse events response body. Error - {}", e);
+                        info!("Unable to parse events response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -139,9 +145,9 @@ pub async fn get_market_async(
     token: &str,
 ) -> Result<GetMarketResponse, Box<dyn std::error::Error + Send + Sync>> {
     // TODO: Sanitize market ticker?
-    let url = reqwest::Url::parse(GET_MARKET_URL)?;
+    let url = environment::api_url(GET_MARKET_PATH)?;
     let url = url.join(market_ticker)?;
-    println!(
+    info!(
         "Fetching details for market '{}' at endpoint '{}'",
         market_ticker, url
     );
@@ -159,7 +165,7 @@ pub async fn get_market_async(
             if result.status().is_success() {
                 match result.json::<GetMarketResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed market response body: {:?}", body);
+                        info!("Successfully parsed market response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
@@ -184,19 +190,20 @@ pub async fn get_market_async(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get_markets_async(
     client: &reqwest::Client,
     token: &str,
     limit: Option<i64>,
     cursor: Option<&str>,
-    event_ticker: &str,
+    event_ticker: Option<&str>,
     series_ticker: Option<&str>,
-    max_close_ts: Option<i64>,
-    min_close_ts: Option<i64>,
+    max_close_ts: Option<DateTime<Utc>>,
+    min_close_ts: Option<DateTime<Utc>>,
     status: Option<&str>,  // open, closed, settled
     tickers: Option<&str>, // comma separated list
 ) -> Result<GetMarketsResponse, Box<dyn std::error::Error + Send + Sync>> {
-    let url = reqwest::Url::parse(GET_MARKETS_URL)?;
+    let url = environment::api_url(GET_MARKETS_PATH)?;
     let mut query_params: HashMap<String, String> = HashMap::new();
     if let Some(limit) = limit {
         query_params.insert("limit".to_string(), limit.to_string());
@@ -204,14 +211,23 @@ pub async fn get_markets_async(
     if let Some(cursor) = cursor {
         query_params.insert("cursor".to_string(), cursor.to_string());
     }
+    if let Some(event_ticker) = event_ticker {
+        query_params.insert("event_ticker".to_string(), event_ticker.to_string());
+    }
     if let Some(series_ticker) = series_ticker {
         query_params.insert("series_ticker".to_string(), series_ticker.to_string());
     }
     if let Some(max_close_ts) = max_close_ts {
-        query_params.insert("max_close_ts".to_string(), max_close_ts.to_string());
+        query_params.insert(
+            "max_close_ts".to_string(),
+            max_close_ts.timestamp().to_string(),
+        );
     }
     if let Some(min_close_ts) = min_close_ts {
-        query_params.insert("min_close_ts".to_string(), min_close_ts.to_string());
+        query_params.insert(
+            "min_close_ts".to_string(),
+            min_close_ts.timestamp().to_string(),
+        );
     }
     if let Some(status) = status {
         query_params.insert("status".to_string(), status.to_string());
@@ -219,8 +235,7 @@ pub async fn get_markets_async(
     if let Some(tickers) = tickers {
         query_params.insert("tickers".to_string(), tickers.to_string());
     }
-    query_params.insert("event_ticker".to_string(), event_ticker.to_string());
-    println!("Fetching markets at endpoint '{}'", url);
+    info!("Fetching markets at endpoint '{}'", url);
     match client
         .get(url)
         .bearer_auth(token)
@@ -236,7 +251,7 @@ pub async fn get_markets_async(
             if result.status().is_success() {
                 match result.json::<GetMarketsResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed markets response body: {:?}", body);
+                        info!("Successfully parsed markets response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
@@ -268,10 +283,11 @@ pub async fn get_market_orderbook_async(
     token: &str,
 ) -> Result<GetMarketOrderbookResponse, Box<dyn std::error::Error + Send + Sync>> {
     // TODO: Sanitize input?
-    let url = reqwest::Url::parse(GET_MARKET_URL)?;
-    let url = url.join(market_ticker)?;
-    let url = url.join(MARKET_ORDERBOOK_SUFFIX)?;
-    println!(
+    let url = environment::api_url(&format!(
+        "{}{}{}",
+        GET_MARKET_PATH, market_ticker, MARKET_ORDERBOOK_SUFFIX
+    ))?;
+    info!(
         "Fetching orderbook for market '{}' at endpoint '{}'. Depth='{}'",
         market_ticker, url, depth
     );
@@ -289,7 +305,7 @@ pub async fn get_market_orderbook_async(
             if result.status().is_success() {
                 match result.json::<GetMarketOrderbookResponse>().await {
                     Ok(body) => {
-                        println!(
+                        info!(
                             "Successfully parsed market orderbook response body: {:?}",
                             body
                         );
@@ -319,3 +335,140 @@ pub async fn get_market_orderbook_async(
         }
     }
 }
+
+pub async fn get_trades_async(
+    client: &reqwest::Client,
+    token: &str,
+    ticker: Option<&str>,
+    min_ts: Option<DateTime<Utc>>,
+    max_ts: Option<DateTime<Utc>>,
+    limit: Option<i32>,
+    cursor: Option<&str>,
+) -> Result<GetTradesResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let url = environment::api_url(GET_TRADES_PATH)?;
+    let mut query_params: HashMap<String, String> = HashMap::new();
+    if let Some(ticker) = ticker {
+        query_params.insert("ticker".to_string(), ticker.to_string());
+    }
+    if let Some(min_ts) = min_ts {
+        query_params.insert("min_ts".to_string(), min_ts.timestamp().to_string());
+    }
+    if let Some(max_ts) = max_ts {
+        query_params.insert("max_ts".to_string(), max_ts.timestamp().to_string());
+    }
+    if let Some(limit) = limit {
+        query_params.insert("limit".to_string(), limit.to_string());
+    }
+    if let Some(cursor) = cursor {
+        query_params.insert("cursor".to_string(), cursor.to_string());
+    }
+    info!("Fetching trades at endpoint '{}'", url);
+    match client
+        .get(url)
+        .bearer_auth(token)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .query(&query_params)
+        .send()
+        .await
+    {
+        Ok(result) => {
+            if result.status().is_success() {
+                match result.json::<GetTradesResponse>().await {
+                    Ok(body) => {
+                        info!("Successfully parsed trades response body: {:?}", body);
+                        Ok(body)
+                    }
+                    Err(e) => {
+                        eprintln!("Unable to parse trades response body. Error - {}", e);
+                        Err(Box::new(e))
+                    }
+                }
+            } else {
+                let error_message = format!(
+                    "get_trades unsuccessful with status {} and message {}",
+                    result.status().as_str(),
+                    result.text().await?
+                );
+                eprintln!("{}", error_message);
+                Err(Box::from(error_message))
+            }
+        }
+        Err(e) => {
+            let error_message = format!("Error from get_trades: {}", e);
+            eprintln!("{}", error_message);
+            Err(Box::from(error_message))
+        }
+    }
+}
+
+// period_interval is the candlestick length in minutes: 1, 60 or 1440.
+pub async fn get_market_candlesticks_async(
+    client: &reqwest::Client,
+    token: &str,
+    series_ticker: &str,
+    market_ticker: &str,
+    start_ts: DateTime<Utc>,
+    end_ts: DateTime<Utc>,
+    period_interval: i32,
+) -> Result<GetMarketCandlesticksResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let url = environment::api_url(&format!(
+        "{}{}/{}{}{}",
+        GET_SERIES_PATH, series_ticker, GET_MARKET_PATH, market_ticker, MARKET_CANDLESTICKS_SUFFIX
+    ))?;
+    let mut query_params: HashMap<String, String> = HashMap::new();
+    query_params.insert("start_ts".to_string(), start_ts.timestamp().to_string());
+    query_params.insert("end_ts".to_string(), end_ts.timestamp().to_string());
+    query_params.insert("period_interval".to_string(), period_interval.to_string());
+    info!(
+        "Fetching candlesticks for market '{}' at endpoint '{}'",
+        market_ticker, url
+    );
+    match client
+        .get(url)
+        .bearer_auth(token)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .query(&query_params)
+        .send()
+        .await
+    {
+        Ok(result) => {
+            if result.status().is_success() {
+                match result.json::<GetMarketCandlesticksResponse>().await {
+                    Ok(body) => {
+                        info!(
+                            "Successfully parsed market candlesticks response body: {:?}",
+                            body
+                        );
+                        Ok(body)
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "Unable to parse market candlesticks response body. Error - {}",
+                            e
+                        );
+                        Err(Box::new(e))
+                    }
+                }
+            } else {
+                let error_message = format!(
+                    "get_market_candlesticks unsuccessful with status {} and message {}",
+                    result.status().as_str(),
+                    result.text().await?
+                );
+                eprintln!("{}", error_message);
+                Err(Box::from(error_message))
+            }
+        }
+        Err(e) => {
+            let error_message = format!("Error from get_market_candlesticks: {}", e);
+            eprintln!("{}", error_message);
+            Err(Box::from(error_message))
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::market::api_structs::{Candlestick, CandlestickPrices, Event, Market, Orderbook, Trade};
+use crate::money::{Cents, Price};
+use crate::portfolio::api_structs::{Fill, MarketPosition, Order, Settlement};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+// Plausible defaults for building mock state. Every field is public, so tests adjust
+// whatever they care about after construction.
+
+pub const FIXTURE_USER_ID: &str = "mock-user";
+
+pub fn fixture_time() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2023, 4, 1, 12, 0, 0).unwrap()
+}
+
+// Panics outside 1 to 99 cents, so a typo in a test fails loudly.
+pub fn price(cents: i64) -> Price {
+    Price::try_from(cents).unwrap()
+}
+
+pub fn event(event_ticker: &str, series_ticker: &str) -> Event {
+    Event {
+        category: "Mock".to_string(),
+        event_ticker: event_ticker.to_string(),
+        mutually_exclusive: false,
+        series_ticker: series_ticker.to_string(),
+        strike_period: String::new(),
+        sub_title: String::new(),
+        title: format!("Mock event {}", event_ticker),
+    }
+}
+
+pub fn market(ticker: &str, event_ticker: &str, yes_bid: i64, yes_ask: i64) -> Market {
+    let open_time = fixture_time() - Duration::days(1);
+    let close_time = fixture_time() + Duration::days(1);
+    Market {
+        can_close_early: false,
+        category: "Mock".to_string(),
+        close_time,
+        event_ticker: event_ticker.to_string(),
+        expiration_time: close_time,
+        expiration_value: String::new(),
+        floor_strike: 0,
+        last_price: Cents(yes_bid),
+        liquidity: Cents(0),
+        no_ask: Cents(100 - yes_bid),
+        no_bid: Cents(100 - yes_ask),
+        open_interest: 0,
+        open_time,
+        previous_price: Cents(yes_bid),
+        previous_yes_ask: Cents(yes_ask),
+        previous_yes_bid: Cents(yes_bid),
+        result: String::new(),
+        risk_limit_cents: Cents(0),
+        strike_type: String::new(),
+        subtitle: String::new(),
+        ticker: ticker.to_string(),
+        volume: 0,
+        volume_24h: 0,
+        yes_ask: Cents(yes_ask),
+        yes_bid: Cents(yes_bid),
+    }
+}
+
+// Levels are (price, count) pairs of resting bids on each side.
+pub fn orderbook(yes: &[(i32, i32)], no: &[(i32, i32)]) -> Orderbook {
+    Orderbook {
+        no: no
+            .iter()
+            .map(|(price, count)| vec![*price, *count])
+            .collect(),
+        yes: yes
+            .iter()
+            .map(|(price, count)| vec![*price, *count])
+            .collect(),
+    }
+}
+
+pub fn trade(
+    trade_id: &str,
+    ticker: &str,
+    taker_side: &str,
+    yes_price: Price,
+    count: i32,
+) -> Trade {
+    Trade {
+        count,
+        created_time: fixture_time(),
+        no_price: yes_price.complement(),
+        taker_side: taker_side.to_string(),
+        ticker: ticker.to_string(),
+        trade_id: trade_id.to_string(),
+        yes_price,
+    }
+}
+
+// A candlestick where every price is the given yes price.
+pub fn candlestick(end_period_ts: DateTime<Utc>, yes_price: i64, volume: i64) -> Candlestick {
+    let prices = CandlestickPrices {
+        open: Some(Cents(yes_price)),
+        high: Some(Cents(yes_price)),
+        low: Some(Cents(yes_price)),
+        close: Some(Cents(yes_price)),
+    };
+    Candlestick {
+        end_period_ts,
+        open_interest: 0,
+        price: prices.clone(),
+        volume,
+        yes_ask: prices.clone(),
+        yes_bid: prices,
+    }
+}
+
+pub fn order(order_id: &str, ticker: &str, side: &str, yes_price: Price, count: i32) -> Order {
+    Order {
+        action: "buy".to_string(),
+        client_order_id: format!("client-{}", order_id),
+        close_cancel_count: 0,
+        created_time: fixture_time(),
+        decrease_count: 0,
+        expiration_time: None,
+        fcc_cancel_count: 0,
+        last_update_time: fixture_time(),
+        maker_fill_count: 0,
+        maker_fill_cost: Cents::ZERO,
+        maker_fees: Cents::ZERO,
+        no_price: yes_price.complement(),
+        order_id: order_id.to_string(),
+        place_count: count,
+        queue_position: 0,
+        remaining_count: count,
+        side: side.to_string(),
+        status: "resting".to_string(),
+        taker_fees: Cents::ZERO,
+        taker_fill_cost: Cents::ZERO,
+        taker_fill_count: 0,
+        ticker: ticker.to_string(),
+        type_: "limit".to_string(),
+        user_id: FIXTURE_USER_ID.to_string(),
+        yes_price,
+    }
+}
+
+pub fn fill(
+    trade_id: &str,
+    order_id: &str,
+    ticker: &str,
+    side: &str,
+    yes_price: Price,
+    count: i32,
+) -> Fill {
+    Fill {
+        action: "buy".to_string(),
+        count,
+        created_time: fixture_time(),
+        is_taker: true,
+        no_price: yes_price.complement(),
+        order_id: order_id.to_string(),
+        side: side.to_string(),
+        ticker: ticker.to_string(),
+        trade_id: trade_id.to_string(),
+        yes_price,
+    }
+}
+
+pub fn market_position(ticker: &str, position: i64, total_cost: Cents) -> MarketPosition {
+    MarketPosition {
+        fees_paid: Cents::ZERO,
+        market_exposure: total_cost,
+        position,
+        realized_pnl: Cents::ZERO,
+        resting_order_count: 0,
+        ticker: ticker.to_string(),
+        total_cost,
+    }
+}
+
+pub fn settlement(ticker: &str, market_result: &str, yes_count: i64, revenue: Cents) -> Settlement {
+    Settlement {
+        market_result: market_result.to_string(),
+        no_count: 0,
+        no_total_cost: Cents::ZERO,
+        revenue,
+        settled_time: fixture_time() + Duration::days(2),
+        ticker: ticker.to_string(),
+        yes_count,
+        yes_total_cost: Cents::ZERO,
+    }
+}
@@ -0,0 +1,891 @@
+pub mod fixtures;
+
+use crate::environment;
+use crate::exchange::api_structs::{
+    Announcement, DailyHours, ExchangeSchedule, ExchangeStatus, StandardHours,
+};
+use crate::market::api_structs::{Candlestick, Event, Market, Orderbook, Trade};
+use crate::money::{Cents, Price};
+use crate::portfolio::api_structs::{
+    BatchOrderError, EventPosition, Fill, MarketPosition, Order, Settlement,
+};
+use chrono::{DateTime, NaiveTime, TimeZone, Utc};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MALFORMED_JSON: &str = "{\"malformed\": ";
+
+// Failures that can be injected in front of any endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MockError {
+    Unauthorized,
+    RateLimited,
+    InternalServerError,
+    // A 200 response whose body isn't valid JSON.
+    MalformedJson,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub body: Option<Value>,
+    pub authorization: Option<String>,
+}
+
+// Everything the mock server knows about. Tests script it by editing the public fields,
+// before or while the server is running.
+#[derive(Clone, Debug)]
+pub struct MockState {
+    // Credentials accepted by login. None accepts anything.
+    pub email: Option<String>,
+    pub password: Option<String>,
+    pub member_id: String,
+    // Token handed out by login and required by logout and the portfolio endpoints.
+    pub token: String,
+    pub exchange_status: ExchangeStatus,
+    // Open around the clock unless a test says otherwise.
+    pub exchange_schedule: ExchangeSchedule,
+    pub announcements: Vec<Announcement>,
+    pub events: Vec<Event>,
+    pub markets: Vec<Market>,
+    pub orderbooks: HashMap<String, Orderbook>,
+    pub trades: Vec<Trade>,
+    // Keyed by market ticker.
+    pub candlesticks: HashMap<String, Vec<Candlestick>>,
+    pub balance: Cents,
+    // Orders rest until a test changes them; nothing is ever matched.
+    pub orders: Vec<Order>,
+    pub fills: Vec<Fill>,
+    pub event_positions: Vec<EventPosition>,
+    pub market_positions: Vec<MarketPosition>,
+    pub settlements: Vec<Settlement>,
+    // When false the batched order endpoints return 404, as on API versions without them.
+    pub batch_orders_enabled: bool,
+    // (path prefix, matching requests to let through first, error)
+    injected_errors: VecDeque<(String, usize, MockError)>,
+    requests: Vec<RecordedRequest>,
+    next_order_id: u64,
+}
+
+impl Default for MockState {
+    fn default() -> Self {
+        MockState {
+            email: None,
+            password: None,
+            member_id: "mock-member".to_string(),
+            token: "mock-token".to_string(),
+            exchange_status: ExchangeStatus {
+                exchange_active: true,
+                trading_active: true,
+            },
+            exchange_schedule: always_open_schedule(),
+            announcements: Vec::new(),
+            events: Vec::new(),
+            markets: Vec::new(),
+            orderbooks: HashMap::new(),
+            trades: Vec::new(),
+            candlesticks: HashMap::new(),
+            balance: Cents::ZERO,
+            orders: Vec::new(),
+            fills: Vec::new(),
+            event_positions: Vec::new(),
+            market_positions: Vec::new(),
+            settlements: Vec::new(),
+            batch_orders_enabled: true,
+            injected_errors: VecDeque::new(),
+            requests: Vec::new(),
+            next_order_id: 1,
+        }
+    }
+}
+
+impl MockState {
+    // Makes the next request whose path starts with path_prefix (e.g. "portfolio/orders")
+    // fail with the given error. An empty prefix matches any request. Errors queue up, so
+    // calling this twice fails the next two matching requests.
+    pub fn fail_next(&mut self, path_prefix: &str, error: MockError) {
+        self.fail_after(path_prefix, 0, error);
+    }
+
+    // Like fail_next, but lets the given number of matching requests succeed first.
+    pub fn fail_after(&mut self, path_prefix: &str, successes: usize, error: MockError) {
+        self.injected_errors.push_back((
+            path_prefix.trim_start_matches('/').to_string(),
+            successes,
+            error,
+        ));
+    }
+
+    // Every request received so far, oldest first.
+    pub fn requests(&self) -> &[RecordedRequest] {
+        &self.requests
+    }
+
+    fn take_injected_error(&mut self, path: &str) -> Option<MockError> {
+        let index = self
+            .injected_errors
+            .iter()
+            .position(|(prefix, _, _)| path.starts_with(prefix.as_str()))?;
+        let (_, successes, _) = &mut self.injected_errors[index];
+        if *successes > 0 {
+            *successes -= 1;
+            return None;
+        }
+        self.injected_errors
+            .remove(index)
+            .map(|(_, _, error)| error)
+    }
+
+    fn event_ticker_for(&self, market_ticker: &str) -> Option<&str> {
+        self.markets
+            .iter()
+            .find(|market| market.ticker == market_ticker)
+            .map(|market| market.event_ticker.as_str())
+    }
+
+    fn series_ticker_for(&self, event_ticker: &str) -> Option<&str> {
+        self.events
+            .iter()
+            .find(|event| event.event_ticker == event_ticker)
+            .map(|event| event.series_ticker.as_str())
+    }
+
+    fn create_order(&mut self, body: &Value) -> Result<Order, BatchOrderError> {
+        let ticker = field_string(body, "ticker").unwrap_or_default();
+        if self.event_ticker_for(&ticker).is_none() {
+            return Err(batch_error(
+                "market_not_found",
+                &format!("Market '{}' not found", ticker),
+            ));
+        }
+        let count = field_i64(body, "count").unwrap_or(0);
+        if count <= 0 {
+            return Err(batch_error("invalid_parameters", "count must be positive"));
+        }
+        let side = field_string(body, "side").unwrap_or_default();
+        let yes_price = match order_prices(body)? {
+            Some(yes_price) => yes_price,
+            // Market orders without a price are willing to pay up to 99 cents.
+            None if side == "no" => Price::MIN,
+            None => Price::MAX,
+        };
+        let now = Utc::now();
+        let order = Order {
+            action: field_string(body, "action").unwrap_or_default(),
+            client_order_id: field_string(body, "client_order_id").unwrap_or_default(),
+            close_cancel_count: 0,
+            created_time: now,
+            decrease_count: 0,
+            expiration_time: field_i64(body, "expiration_ts")
+                .and_then(|ts| Utc.timestamp_opt(ts, 0).single()),
+            fcc_cancel_count: 0,
+            last_update_time: now,
+            maker_fill_count: 0,
+            maker_fill_cost: Cents::ZERO,
+            maker_fees: Cents::ZERO,
+            no_price: yes_price.complement(),
+            order_id: format!("mock-order-{}", self.next_order_id),
+            place_count: count as i32,
+            queue_position: 0,
+            remaining_count: count as i32,
+            side,
+            status: "resting".to_string(),
+            taker_fees: Cents::ZERO,
+            taker_fill_cost: Cents::ZERO,
+            taker_fill_count: 0,
+            ticker,
+            type_: field_string(body, "type").unwrap_or_else(|| "limit".to_string()),
+            user_id: fixtures::FIXTURE_USER_ID.to_string(),
+            yes_price,
+        };
+        self.next_order_id += 1;
+        self.orders.push(order.clone());
+        Ok(order)
+    }
+
+    // Cancels a resting order, returning it and the number of contracts removed.
+    fn cancel_order(&mut self, order_id: &str) -> Result<(Order, i64), BatchOrderError> {
+        let order = self.open_order_mut(order_id)?;
+        let reduced_by = order.remaining_count as i64;
+        order.remaining_count = 0;
+        order.status = "canceled".to_string();
+        order.last_update_time = Utc::now();
+        Ok((order.clone(), reduced_by))
+    }
+
+    fn open_order_mut(&mut self, order_id: &str) -> Result<&mut Order, BatchOrderError> {
+        let order = self
+            .orders
+            .iter_mut()
+            .find(|order| order.order_id == order_id)
+            .ok_or_else(|| batch_error("not_found", &format!("Order '{}' not found", order_id)))?;
+        if order.status != "resting" {
+            return Err(batch_error(
+                "order_not_open",
+                &format!("Order '{}' is {}", order_id, order.status),
+            ));
+        }
+        Ok(order)
+    }
+}
+
+fn always_open_schedule() -> ExchangeSchedule {
+    let hours = Some(DailyHours {
+        open_time: NaiveTime::MIN,
+        close_time: NaiveTime::MIN,
+    });
+    ExchangeSchedule {
+        standard_hours: StandardHours {
+            monday: hours.clone(),
+            tuesday: hours.clone(),
+            wednesday: hours.clone(),
+            thursday: hours.clone(),
+            friday: hours.clone(),
+            saturday: hours.clone(),
+            sunday: hours,
+        },
+        maintenance_windows: Vec::new(),
+    }
+}
+
+// An in-process Kalshi API for integration tests. Requests made inside MockServer::run are
+// served from a scriptable MockState instead of the real API.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    shutdown: oneshot::Sender<()>,
+    handle: JoinHandle<Result<(), hyper::Error>>,
+}
+
+impl MockServer {
+    pub async fn start(state: MockState) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let state = Arc::new(Mutex::new(state));
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_| {
+            let state = service_state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle_request(state.clone(), request)
+                }))
+            }
+        });
+        let server = Server::try_bind(&SocketAddr::from(([127, 0, 0, 1], 0)))?.serve(make_service);
+        let addr = server.local_addr();
+        info!("Started mock Kalshi server at 'http://{}/'", addr);
+        let (shutdown, shutdown_signal) = oneshot::channel::<()>();
+        let handle = tokio::spawn(server.with_graceful_shutdown(async {
+            shutdown_signal.await.ok();
+        }));
+
+        Ok(MockServer {
+            addr,
+            state,
+            shutdown,
+            handle,
+        })
+    }
+
+    pub fn base_url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    // Runs the future with all API requests sent to this server.
+    pub async fn run<F: Future>(&self, future: F) -> F::Output {
+        environment::with_api_base_url(&self.base_url(), future).await
+    }
+
+    // Don't hold the guard across an await that talks to the server, or it will deadlock.
+    pub fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    // Stops the server and returns its final state.
+    pub async fn stop(self) -> Result<MockState, Box<dyn std::error::Error + Send + Sync>> {
+        self.shutdown.send(()).ok();
+        self.handle.await??;
+        let state = self.state.lock().unwrap().clone();
+        Ok(state)
+    }
+}
+
+async fn handle_request(
+    state: Arc<Mutex<MockState>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let method = request.method().to_string();
+    let path = request.uri().path().trim_start_matches('/').to_string();
+    let query = parse_query(request.uri().query());
+    let authorization = request
+        .headers()
+        .get(hyper::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(bytes) if bytes.is_empty() => None,
+        Ok(bytes) => serde_json::from_slice(&bytes).ok(),
+        Err(e) => return Ok(error_response(400, "bad_request", &e.to_string())),
+    };
+
+    let mut state = state.lock().unwrap();
+    state.requests.push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        query: query.clone(),
+        body: body.clone(),
+        authorization: authorization.clone(),
+    });
+    if let Some(error) = state.take_injected_error(&path) {
+        return Ok(injected_error_response(error));
+    }
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let requires_token = matches!(segments.first(), Some(&"portfolio") | Some(&"logout"));
+    if requires_token && authorization != Some(format!("Bearer {}", state.token)) {
+        return Ok(injected_error_response(MockError::Unauthorized));
+    }
+    let body = body.unwrap_or(Value::Null);
+    Ok(route(&mut state, &method, &segments, &query, &body))
+}
+
+fn route(
+    state: &mut MockState,
+    method: &str,
+    segments: &[&str],
+    query: &HashMap<String, String>,
+    body: &Value,
+) -> Response<Body> {
+    match (method, segments) {
+        ("POST", ["login"]) => login(state, body),
+        ("POST", ["logout"]) => json_response(200, &json!({})),
+        ("GET", ["exchange", "status"]) => json_response(200, &state.exchange_status),
+        ("GET", ["exchange", "announcements"]) => {
+            json_response(200, &json!({ "announcements": state.announcements }))
+        }
+        ("GET", ["exchange", "schedule"]) => {
+            json_response(200, &json!({ "schedule": state.exchange_schedule }))
+        }
+        ("GET", ["events"]) => get_events(state, query),
+        ("GET", ["events", event_ticker]) => get_event(state, event_ticker),
+        ("GET", ["markets"]) => get_markets(state, query),
+        ("GET", ["markets", "trades"]) => get_trades(state, query),
+        ("GET", ["series", _, "markets", ticker, "candlesticks"]) => {
+            get_candlesticks(state, ticker, query)
+        }
+        ("GET", ["markets", ticker]) => match find_market(state, ticker) {
+            Some(market) => json_response(200, &json!({ "market": market })),
+            None => not_found(&format!("Market '{}' not found", ticker)),
+        },
+        ("GET", ["markets", ticker, "orderbook"]) => match find_market(state, ticker) {
+            Some(_) => {
+                let orderbook = state.orderbooks.get(*ticker).cloned().unwrap_or(Orderbook {
+                    no: Vec::new(),
+                    yes: Vec::new(),
+                });
+                json_response(200, &json!({ "orderbook": orderbook }))
+            }
+            None => not_found(&format!("Market '{}' not found", ticker)),
+        },
+        ("GET", ["portfolio", "balance"]) => {
+            json_response(200, &json!({ "balance": state.balance }))
+        }
+        ("GET", ["portfolio", "fills"]) => get_fills(state, query),
+        ("GET", ["portfolio", "orders"]) => get_orders(state, query),
+        ("POST", ["portfolio", "orders"]) => create_order(state, body),
+        ("POST", ["portfolio", "orders", "batched"]) if state.batch_orders_enabled => {
+            batch_create_orders(state, body)
+        }
+        ("DELETE", ["portfolio", "orders", "batched"]) if state.batch_orders_enabled => {
+            batch_cancel_orders(state, body)
+        }
+        ("GET", ["portfolio", "orders", order_id]) => {
+            match state
+                .orders
+                .iter()
+                .find(|order| order.order_id == *order_id)
+            {
+                Some(order) => json_response(200, &json!({ "order": order })),
+                None => not_found(&format!("Order '{}' not found", order_id)),
+            }
+        }
+        ("DELETE", ["portfolio", "orders", order_id]) => match state.cancel_order(order_id) {
+            Ok((order, reduced_by)) => {
+                json_response(200, &json!({ "order": order, "reduced_by": reduced_by }))
+            }
+            Err(error) => order_error_response(error),
+        },
+        ("POST", ["portfolio", "orders", order_id, "amend"]) => amend_order(state, order_id, body),
+        ("POST", ["portfolio", "orders", order_id, "decrease"]) => {
+            decrease_order(state, order_id, body)
+        }
+        ("GET", ["portfolio", "positions"]) => get_positions(state, query),
+        ("GET", ["portfolio", "settlements"]) => {
+            let (settlements, cursor) = paginate(state.settlements.clone(), query);
+            json_response(
+                200,
+                &json!({ "cursor": cursor, "settlements": settlements }),
+            )
+        }
+        _ => not_found(&format!("No route for {} /{}", method, segments.join("/"))),
+    }
+}
+
+fn login(state: &MockState, body: &Value) -> Response<Body> {
+    let email_matches = state
+        .email
+        .as_ref()
+        .is_none_or(|email| field_string(body, "email").as_ref() == Some(email));
+    let password_matches = state
+        .password
+        .as_ref()
+        .is_none_or(|password| field_string(body, "password").as_ref() == Some(password));
+    if !email_matches || !password_matches {
+        return error_response(401, "invalid_credentials", "Invalid email or password");
+    }
+    json_response(
+        200,
+        &json!({ "member_id": state.member_id, "token": state.token }),
+    )
+}
+
+fn get_events(state: &MockState, query: &HashMap<String, String>) -> Response<Body> {
+    let events: Vec<Event> = state
+        .events
+        .iter()
+        .filter(|event| {
+            query
+                .get("series_ticker")
+                .is_none_or(|series_ticker| &event.series_ticker == series_ticker)
+        })
+        .cloned()
+        .collect();
+    let (events, cursor) = paginate(events, query);
+    json_response(200, &json!({ "cursor": cursor, "events": events }))
+}
+
+fn get_event(state: &MockState, event_ticker: &str) -> Response<Body> {
+    let Some(event) = state
+        .events
+        .iter()
+        .find(|event| event.event_ticker == event_ticker)
+    else {
+        return not_found(&format!("Event '{}' not found", event_ticker));
+    };
+    let markets: Vec<&Market> = state
+        .markets
+        .iter()
+        .filter(|market| market.event_ticker == event_ticker)
+        .collect();
+    json_response(200, &json!({ "event": event, "markets": markets }))
+}
+
+fn find_market<'a>(state: &'a MockState, ticker: &str) -> Option<&'a Market> {
+    state.markets.iter().find(|market| market.ticker == ticker)
+}
+
+fn get_markets(state: &MockState, query: &HashMap<String, String>) -> Response<Body> {
+    let tickers: Option<Vec<&str>> = query
+        .get("tickers")
+        .map(|tickers| tickers.split(',').collect());
+    let min_close_ts = query_time(query, "min_close_ts");
+    let max_close_ts = query_time(query, "max_close_ts");
+    let markets: Vec<Market> = state
+        .markets
+        .iter()
+        .filter(|market| {
+            query
+                .get("event_ticker")
+                .is_none_or(|event_ticker| &market.event_ticker == event_ticker)
+                && query.get("series_ticker").is_none_or(|series_ticker| {
+                    state.series_ticker_for(&market.event_ticker) == Some(series_ticker.as_str())
+                })
+                && tickers
+                    .as_ref()
+                    .is_none_or(|tickers| tickers.contains(&market.ticker.as_str()))
+                && min_close_ts.is_none_or(|min| market.close_time >= min)
+                && max_close_ts.is_none_or(|max| market.close_time <= max)
+        })
+        .cloned()
+        .collect();
+    let (markets, cursor) = paginate(markets, query);
+    json_response(200, &json!({ "cursor": cursor, "markets": markets }))
+}
+
+fn get_trades(state: &MockState, query: &HashMap<String, String>) -> Response<Body> {
+    let min_ts = query_time(query, "min_ts");
+    let max_ts = query_time(query, "max_ts");
+    let trades: Vec<Trade> = state
+        .trades
+        .iter()
+        .filter(|trade| {
+            query
+                .get("ticker")
+                .is_none_or(|ticker| &trade.ticker == ticker)
+                && min_ts.is_none_or(|min| trade.created_time >= min)
+                && max_ts.is_none_or(|max| trade.created_time <= max)
+        })
+        .cloned()
+        .collect();
+    let (trades, cursor) = paginate(trades, query);
+    json_response(200, &json!({ "cursor": cursor, "trades": trades }))
+}
+
+fn get_candlesticks(
+    state: &MockState,
+    ticker: &str,
+    query: &HashMap<String, String>,
+) -> Response<Body> {
+    if find_market(state, ticker).is_none() {
+        return not_found(&format!("Market '{}' not found", ticker));
+    }
+    let start_ts = query_time(query, "start_ts");
+    let end_ts = query_time(query, "end_ts");
+    let candlesticks: Vec<&Candlestick> = state
+        .candlesticks
+        .get(ticker)
+        .into_iter()
+        .flatten()
+        .filter(|candlestick| {
+            start_ts.is_none_or(|start| candlestick.end_period_ts >= start)
+                && end_ts.is_none_or(|end| candlestick.end_period_ts <= end)
+        })
+        .collect();
+    json_response(
+        200,
+        &json!({ "candlesticks": candlesticks, "ticker": ticker }),
+    )
+}
+
+fn get_fills(state: &MockState, query: &HashMap<String, String>) -> Response<Body> {
+    let min_ts = query_time(query, "min_ts");
+    let max_ts = query_time(query, "max_ts");
+    let fills: Vec<Fill> = state
+        .fills
+        .iter()
+        .filter(|fill| {
+            query
+                .get("ticker")
+                .is_none_or(|ticker| &fill.ticker == ticker)
+                && query
+                    .get("order_id")
+                    .is_none_or(|order_id| &fill.order_id == order_id)
+                && min_ts.is_none_or(|min| fill.created_time >= min)
+                && max_ts.is_none_or(|max| fill.created_time <= max)
+        })
+        .cloned()
+        .collect();
+    let (fills, cursor) = paginate(fills, query);
+    json_response(200, &json!({ "cursor": cursor, "fills": fills }))
+}
+
+fn get_orders(state: &MockState, query: &HashMap<String, String>) -> Response<Body> {
+    let min_ts = query_time(query, "min_ts");
+    let max_ts = query_time(query, "max_ts");
+    let orders: Vec<Order> = state
+        .orders
+        .iter()
+        .filter(|order| {
+            query
+                .get("ticker")
+                .is_none_or(|ticker| &order.ticker == ticker)
+                && query.get("event_ticker").is_none_or(|event_ticker| {
+                    state.event_ticker_for(&order.ticker) == Some(event_ticker.as_str())
+                })
+                && query
+                    .get("status")
+                    .is_none_or(|status| &order.status == status)
+                && min_ts.is_none_or(|min| order.created_time >= min)
+                && max_ts.is_none_or(|max| order.created_time <= max)
+        })
+        .cloned()
+        .collect();
+    let (orders, cursor) = paginate(orders, query);
+    json_response(200, &json!({ "cursor": cursor, "orders": orders }))
+}
+
+fn create_order(state: &mut MockState, body: &Value) -> Response<Body> {
+    if let Some(error) = require_numbers(
+        body,
+        &[
+            "buy_max_cost",
+            "count",
+            "expiration_ts",
+            "no_price",
+            "sell_position_floor",
+            "yes_price",
+        ],
+    ) {
+        return error;
+    }
+    match state.create_order(body) {
+        Ok(order) => json_response(201, &json!({ "order": order })),
+        Err(error) => error_response(400, &error.code, &error.message),
+    }
+}
+
+fn batch_create_orders(state: &mut MockState, body: &Value) -> Response<Body> {
+    let requests = body["orders"].as_array().cloned().unwrap_or_default();
+    let results: Vec<Value> = requests
+        .iter()
+        .map(|request| {
+            let client_order_id = field_string(request, "client_order_id");
+            match state.create_order(request) {
+                Ok(order) => json!({ "client_order_id": client_order_id, "order": order }),
+                Err(error) => json!({ "client_order_id": client_order_id, "error": error }),
+            }
+        })
+        .collect();
+    json_response(201, &json!({ "orders": results }))
+}
+
+fn batch_cancel_orders(state: &mut MockState, body: &Value) -> Response<Body> {
+    let order_ids = body["ids"].as_array().cloned().unwrap_or_default();
+    let results: Vec<Value> = order_ids
+        .iter()
+        .map(|order_id| {
+            let order_id = order_id.as_str().unwrap_or_default();
+            match state.cancel_order(order_id) {
+                Ok((order, reduced_by)) => json!({
+                    "order_id": order_id,
+                    "order": order,
+                    "reduced_by": reduced_by,
+                }),
+                Err(error) => json!({ "order_id": order_id, "error": error }),
+            }
+        })
+        .collect();
+    json_response(200, &json!({ "orders": results }))
+}
+
+fn amend_order(state: &mut MockState, order_id: &str, body: &Value) -> Response<Body> {
+    if let Some(error) = require_numbers(body, &["count", "yes_price", "no_price"]) {
+        return error;
+    }
+    let yes_price = match order_prices(body) {
+        Ok(yes_price) => yes_price,
+        Err(error) => return error_response(400, &error.code, &error.message),
+    };
+    let order = match state.open_order_mut(order_id) {
+        Ok(order) => order,
+        Err(error) => return order_error_response(error),
+    };
+    let old_order = order.clone();
+    if let Some(count) = field_i64(body, "count") {
+        let filled = order.place_count - order.remaining_count;
+        order.place_count = count as i32;
+        order.remaining_count = (count as i32 - filled).max(0);
+    }
+    if let Some(yes_price) = yes_price {
+        order.yes_price = yes_price;
+        order.no_price = yes_price.complement();
+    }
+    if let Some(client_order_id) = field_string(body, "updated_client_order_id") {
+        order.client_order_id = client_order_id;
+    }
+    order.last_update_time = Utc::now();
+    json_response(200, &json!({ "old_order": old_order, "order": order }))
+}
+
+fn decrease_order(state: &mut MockState, order_id: &str, body: &Value) -> Response<Body> {
+    if let Some(error) = require_numbers(body, &["reduce_by", "reduce_to"]) {
+        return error;
+    }
+    let order = match state.open_order_mut(order_id) {
+        Ok(order) => order,
+        Err(error) => return order_error_response(error),
+    };
+    let reduce_by = match (field_i64(body, "reduce_by"), field_i64(body, "reduce_to")) {
+        (Some(reduce_by), None) => reduce_by as i32,
+        (None, Some(reduce_to)) => order.remaining_count - reduce_to as i32,
+        _ => {
+            return error_response(
+                400,
+                "invalid_parameters",
+                "Exactly one of reduce_by or reduce_to is required",
+            )
+        }
+    };
+    let reduce_by = reduce_by.clamp(0, order.remaining_count);
+    order.remaining_count -= reduce_by;
+    order.decrease_count += reduce_by;
+    if order.remaining_count == 0 {
+        order.status = "canceled".to_string();
+    }
+    order.last_update_time = Utc::now();
+    json_response(200, &json!({ "order": order }))
+}
+
+fn get_positions(state: &MockState, query: &HashMap<String, String>) -> Response<Body> {
+    let event_positions: Vec<EventPosition> = state
+        .event_positions
+        .iter()
+        .filter(|position| {
+            query
+                .get("event_ticker")
+                .is_none_or(|event_ticker| &position.event_ticker == event_ticker)
+        })
+        .cloned()
+        .collect();
+    let market_positions: Vec<MarketPosition> = state
+        .market_positions
+        .iter()
+        .filter(|position| {
+            query
+                .get("ticker")
+                .is_none_or(|ticker| &position.ticker == ticker)
+                && query.get("event_ticker").is_none_or(|event_ticker| {
+                    state.event_ticker_for(&position.ticker) == Some(event_ticker.as_str())
+                })
+        })
+        .cloned()
+        .collect();
+    let (market_positions, cursor) = paginate(market_positions, query);
+    json_response(
+        200,
+        &json!({
+            "cursor": cursor,
+            "event_positions": event_positions,
+            "market_positions": market_positions,
+        }),
+    )
+}
+
+// Cursors are plain offsets into the filtered list; an empty cursor means no more pages.
+fn paginate<T>(items: Vec<T>, query: &HashMap<String, String>) -> (Vec<T>, String) {
+    let offset = query
+        .get("cursor")
+        .and_then(|cursor| cursor.parse::<usize>().ok())
+        .unwrap_or(0);
+    let limit = query
+        .get("limit")
+        .and_then(|limit| limit.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_PAGE_SIZE);
+    let total = items.len();
+    let end = offset.saturating_add(limit).min(total);
+    let cursor = if end < total {
+        end.to_string()
+    } else {
+        String::new()
+    };
+    let page = items.into_iter().skip(offset).take(limit).collect();
+    (page, cursor)
+}
+
+fn parse_query(query: Option<&str>) -> HashMap<String, String> {
+    let Some(query) = query else {
+        return HashMap::new();
+    };
+    match reqwest::Url::parse(&format!("http://localhost/?{}", query)) {
+        Ok(url) => url.query_pairs().into_owned().collect(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn query_time(query: &HashMap<String, String>, key: &str) -> Option<DateTime<Utc>> {
+    query
+        .get(key)
+        .and_then(|ts| ts.parse::<i64>().ok())
+        .and_then(|ts| Utc.timestamp_opt(ts, 0).single())
+}
+
+// The single-order endpoints send every field as a string, the batched ones as JSON types.
+fn field_string(body: &Value, key: &str) -> Option<String> {
+    match &body[key] {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+// The exchange rejects numeric fields sent as strings.
+fn require_numbers(body: &Value, keys: &[&str]) -> Option<Response<Body>> {
+    keys.iter()
+        .find(|key| !matches!(body[**key], Value::Null | Value::Number(_)))
+        .map(|key| {
+            error_response(
+                400,
+                "invalid_parameters",
+                &format!("{} must be a number", key),
+            )
+        })
+}
+
+fn field_i64(body: &Value, key: &str) -> Option<i64> {
+    match &body[key] {
+        Value::String(value) => value.parse().ok(),
+        Value::Number(value) => value.as_i64(),
+        _ => None,
+    }
+}
+
+// Reads yes_price or no_price from an order body and returns the yes price.
+fn order_prices(body: &Value) -> Result<Option<Price>, BatchOrderError> {
+    let to_price = |cents: i64| {
+        Price::try_from(cents).map_err(|e| batch_error("invalid_parameters", &e.to_string()))
+    };
+    match (field_i64(body, "yes_price"), field_i64(body, "no_price")) {
+        (Some(yes_price), _) => to_price(yes_price).map(Some),
+        (None, Some(no_price)) => to_price(no_price).map(|price| Some(price.complement())),
+        (None, None) => Ok(None),
+    }
+}
+
+fn batch_error(code: &str, message: &str) -> BatchOrderError {
+    BatchOrderError {
+        code: code.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn order_error_response(error: BatchOrderError) -> Response<Body> {
+    let status = if error.code == "not_found" { 404 } else { 400 };
+    error_response(status, &error.code, &error.message)
+}
+
+fn not_found(message: &str) -> Response<Body> {
+    error_response(404, "not_found", message)
+}
+
+fn injected_error_response(error: MockError) -> Response<Body> {
+    match error {
+        MockError::Unauthorized => error_response(401, "unauthorized", "Unauthorized"),
+        MockError::RateLimited => error_response(429, "too_many_requests", "Too many requests"),
+        MockError::InternalServerError => {
+            error_response(500, "internal_server_error", "Internal server error")
+        }
+        MockError::MalformedJson => raw_response(200, MALFORMED_JSON),
+    }
+}
+
+fn error_response(status: u16, code: &str, message: &str) -> Response<Body> {
+    json_response(
+        status,
+        &json!({ "error": { "code": code, "message": message } }),
+    )
+}
+
+fn json_response<T: Serialize>(status: u16, body: &T) -> Response<Body> {
+    match serde_json::to_string(body) {
+        Ok(text) => raw_response(status, &text),
+        Err(e) => raw_response(500, &e.to_string()),
+    }
+}
+
+fn raw_response(status: u16, body: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A signed amount of money in cents.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Cents(pub i64);
+
+impl Cents {
+    pub const ZERO: Cents = Cents(0);
+
+    pub fn new(cents: i64) -> Self {
+        Cents(cents)
+    }
+
+    pub fn value(self) -> i64 {
+        self.0
+    }
+
+    pub fn as_dollars(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// Dollars as a plain decimal without a currency sign, e.g. `12.34` or `-0.05`.
+    pub fn to_decimal_string(self) -> String {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        format!("{}{}.{:02}", sign, abs / 100, abs % 100)
+    }
+
+    pub fn checked_add(self, other: Cents) -> Option<Cents> {
+        self.0.checked_add(other.0).map(Cents)
+    }
+
+    pub fn checked_sub(self, other: Cents) -> Option<Cents> {
+        self.0.checked_sub(other.0).map(Cents)
+    }
+
+    pub fn checked_mul(self, count: i64) -> Option<Cents> {
+        self.0.checked_mul(count).map(Cents)
+    }
+
+    pub fn checked_neg(self) -> Option<Cents> {
+        self.0.checked_neg().map(Cents)
+    }
+
+    pub fn abs(self) -> Cents {
+        Cents(self.0.abs())
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+}
+
+impl From<i64> for Cents {
+    fn from(cents: i64) -> Self {
+        Cents(cents)
+    }
+}
+
+impl From<i32> for Cents {
+    fn from(cents: i32) -> Self {
+        Cents(cents as i64)
+    }
+}
+
+impl From<Price> for Cents {
+    fn from(price: Price) -> Self {
+        Cents(price.0 as i64)
+    }
+}
+
+impl Add for Cents {
+    type Output = Cents;
+
+    fn add(self, other: Cents) -> Cents {
+        self.checked_add(other).expect("overflow when adding Cents")
+    }
+}
+
+impl AddAssign for Cents {
+    fn add_assign(&mut self, other: Cents) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Cents {
+    type Output = Cents;
+
+    fn sub(self, other: Cents) -> Cents {
+        self.checked_sub(other)
+            .expect("overflow when subtracting Cents")
+    }
+}
+
+impl SubAssign for Cents {
+    fn sub_assign(&mut self, other: Cents) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Cents {
+    type Output = Cents;
+
+    fn mul(self, count: i64) -> Cents {
+        self.checked_mul(count)
+            .expect("overflow when multiplying Cents")
+    }
+}
+
+impl Neg for Cents {
+    type Output = Cents;
+
+    fn neg(self) -> Cents {
+        self.checked_neg().expect("overflow when negating Cents")
+    }
+}
+
+impl Sum for Cents {
+    fn sum<I: Iterator<Item = Cents>>(iter: I) -> Cents {
+        iter.fold(Cents::ZERO, |total, cents| total + cents)
+    }
+}
+
+/// Formats as dollars, e.g. `$12.34` or `-$0.05`.
+impl fmt::Display for Cents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}${}.{:02}", sign, abs / 100, abs % 100)
+    }
+}
+
+/// The price of a single contract in cents. Kalshi contracts always trade
+/// between 1 and 99 cents inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "i64", into = "i64")]
+pub struct Price(u8);
+
+impl Price {
+    pub const MIN: Price = Price(1);
+    pub const MAX: Price = Price(99);
+
+    pub fn new(cents: u8) -> Option<Price> {
+        if (Self::MIN.0..=Self::MAX.0).contains(&cents) {
+            Some(Price(cents))
+        } else {
+            None
+        }
+    }
+
+    pub fn value(self) -> u8 {
+        self.0
+    }
+
+    pub fn cents(self) -> Cents {
+        self.into()
+    }
+
+    /// The equivalent price on the other side of the market, i.e. a yes price
+    /// of 30 is a no price of 70.
+    pub fn complement(self) -> Price {
+        Price(100 - self.0)
+    }
+
+    pub fn checked_add(self, cents: i64) -> Option<Price> {
+        (self.0 as i64)
+            .checked_add(cents)
+            .and_then(|cents| Price::try_from(cents).ok())
+    }
+
+    pub fn checked_sub(self, cents: i64) -> Option<Price> {
+        (self.0 as i64)
+            .checked_sub(cents)
+            .and_then(|cents| Price::try_from(cents).ok())
+    }
+
+    /// Total cost of `count` contracts at this price.
+    pub fn checked_mul(self, count: i64) -> Option<Cents> {
+        self.cents().checked_mul(count)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidPrice(pub i64);
+
+impl fmt::Display for InvalidPrice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Price must be between 1 and 99 cents, got {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidPrice {}
+
+impl TryFrom<i64> for Price {
+    type Error = InvalidPrice;
+
+    fn try_from(cents: i64) -> Result<Self, Self::Error> {
+        u8::try_from(cents)
+            .ok()
+            .and_then(Price::new)
+            .ok_or(InvalidPrice(cents))
+    }
+}
+
+impl TryFrom<i32> for Price {
+    type Error = InvalidPrice;
+
+    fn try_from(cents: i32) -> Result<Self, Self::Error> {
+        Price::try_from(cents as i64)
+    }
+}
+
+impl TryFrom<Cents> for Price {
+    type Error = InvalidPrice;
+
+    fn try_from(cents: Cents) -> Result<Self, Self::Error> {
+        Price::try_from(cents.0)
+    }
+}
+
+impl From<Price> for i64 {
+    fn from(price: Price) -> Self {
+        price.0 as i64
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}¢", self.0)
+    }
+}
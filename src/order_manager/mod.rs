@@ -0,0 +1,303 @@
+use crate::client::Trading;
+use crate::money::Cents;
+use crate::portfolio::{CreateOrderRequest, Fill, Order};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderState {
+    // Submitted to the exchange but not yet acknowledged.
+    Pending,
+    Resting,
+    PartiallyFilled,
+    Executed,
+    Canceled,
+}
+
+impl OrderState {
+    pub fn is_open(self) -> bool {
+        matches!(
+            self,
+            OrderState::Pending | OrderState::Resting | OrderState::PartiallyFilled
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TrackedOrder {
+    pub client_order_id: String,
+    pub order_id: Option<String>,
+    pub ticker: String,
+    pub action: String,
+    pub side: String,
+    pub count: i32,
+    pub state: OrderState,
+    pub filled_count: i32,
+    pub remaining_count: i32,
+    // Contracts removed from the book without filling, e.g. by decrease_order.
+    pub decreased_count: i32,
+    // Total paid (or received, for sells) across all fills, priced on the order's side.
+    pub fill_cost: Cents,
+    pub fees: Cents,
+    pub fills: Vec<Fill>,
+    pub last_exchange_order: Option<Order>,
+}
+
+impl TrackedOrder {
+    fn new(request: &CreateOrderRequest) -> Self {
+        TrackedOrder {
+            client_order_id: request.client_order_id.clone(),
+            order_id: None,
+            ticker: request.ticker.clone(),
+            action: request.action.clone(),
+            side: request.side.clone(),
+            count: request.count,
+            state: OrderState::Pending,
+            filled_count: 0,
+            remaining_count: request.count,
+            decreased_count: 0,
+            fill_cost: Cents::ZERO,
+            fees: Cents::ZERO,
+            fills: Vec::new(),
+            last_exchange_order: None,
+        }
+    }
+
+    // Average fill price in cents on the order's side, if anything has filled.
+    pub fn average_fill_price(&self) -> Option<f64> {
+        if self.filled_count == 0 {
+            None
+        } else {
+            Some(self.fill_cost.value() as f64 / self.filled_count as f64)
+        }
+    }
+
+    // Takes the filled count and cost from the recorded fills when they cover everything the
+    // exchange has reported, and from the last exchange order otherwise.
+    fn reconcile_fills(&mut self) {
+        let mut recorded_count = 0;
+        let mut recorded_cost = Cents::ZERO;
+        for fill in &self.fills {
+            let price = if self.side == "yes" {
+                fill.yes_price
+            } else {
+                fill.no_price
+            };
+            recorded_count += fill.count;
+            recorded_cost += price.cents() * fill.count as i64;
+        }
+        match &self.last_exchange_order {
+            Some(order) if exchange_filled_count(order) > recorded_count => {
+                self.filled_count = exchange_filled_count(order);
+                self.fill_cost = order.taker_fill_cost + order.maker_fill_cost;
+            }
+            _ => {
+                self.filled_count = recorded_count;
+                self.fill_cost = recorded_cost;
+            }
+        }
+    }
+}
+
+// A disagreement between what we have tracked locally and what the exchange reports.
+#[derive(Clone, Debug)]
+pub struct OrderDrift {
+    pub client_order_id: String,
+    pub order_id: String,
+    pub local_state: OrderState,
+    pub exchange_state: OrderState,
+    pub local_filled_count: i32,
+    pub exchange_filled_count: i32,
+    pub local_remaining_count: i32,
+    pub exchange_remaining_count: i32,
+}
+
+#[derive(Debug, Default)]
+pub struct OrderManager {
+    orders: HashMap<String, TrackedOrder>,
+    client_order_ids: HashMap<String, String>,
+    seen_trade_ids: HashSet<String>,
+}
+
+impl OrderManager {
+    pub fn new() -> Self {
+        OrderManager::default()
+    }
+
+    pub fn get(&self, client_order_id: &str) -> Option<&TrackedOrder> {
+        self.orders.get(client_order_id)
+    }
+
+    pub fn get_by_order_id(&self, order_id: &str) -> Option<&TrackedOrder> {
+        self.client_order_ids
+            .get(order_id)
+            .and_then(|client_order_id| self.orders.get(client_order_id))
+    }
+
+    pub fn orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders.values()
+    }
+
+    pub fn open_orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders.values().filter(|order| order.state.is_open())
+    }
+
+    // Submits the order through the backend and starts tracking it.
+    pub async fn create_order_async<T: Trading + ?Sized>(
+        &mut self,
+        backend: &T,
+        request: &CreateOrderRequest,
+    ) -> Result<Order, Box<dyn std::error::Error + Send + Sync>> {
+        self.track_pending(request);
+        match backend.create_order_async(request).await {
+            Ok(response) => {
+                self.on_order_update(&response.order);
+                Ok(response.order)
+            }
+            Err(e) => {
+                self.orders.remove(&request.client_order_id);
+                Err(e)
+            }
+        }
+    }
+
+    // Starts tracking an order that is about to be submitted by other means, e.g. in a batch.
+    pub fn track_pending(&mut self, request: &CreateOrderRequest) {
+        self.orders
+            .entry(request.client_order_id.clone())
+            .or_insert_with(|| TrackedOrder::new(request));
+    }
+
+    // Applies the exchange's view of an order, from a create, get or cancel response.
+    pub fn on_order_update(&mut self, order: &Order) {
+        self.client_order_ids
+            .insert(order.order_id.clone(), order.client_order_id.clone());
+        let tracked = self
+            .orders
+            .entry(order.client_order_id.clone())
+            .or_insert_with(|| TrackedOrder {
+                client_order_id: order.client_order_id.clone(),
+                order_id: None,
+                ticker: order.ticker.clone(),
+                action: order.action.clone(),
+                side: order.side.clone(),
+                count: exchange_filled_count(order)
+                    + order.remaining_count
+                    + order.decrease_count
+                    + order.close_cancel_count
+                    + order.fcc_cancel_count,
+                state: OrderState::Pending,
+                filled_count: 0,
+                remaining_count: order.remaining_count,
+                decreased_count: 0,
+                fill_cost: Cents::ZERO,
+                fees: Cents::ZERO,
+                fills: Vec::new(),
+                last_exchange_order: None,
+            });
+        tracked.order_id = Some(order.order_id.clone());
+        tracked.state = exchange_state(order);
+        tracked.decreased_count =
+            order.decrease_count + order.close_cancel_count + order.fcc_cancel_count;
+        tracked.fees = order.taker_fees + order.maker_fees;
+        tracked.last_exchange_order = Some(order.clone());
+        tracked.reconcile_fills();
+        // Fills we have already seen may be newer than the order the exchange returned.
+        tracked.remaining_count = order
+            .remaining_count
+            .min(tracked.count - tracked.filled_count - tracked.decreased_count)
+            .max(0);
+        if tracked.state.is_open() && tracked.filled_count > exchange_filled_count(order) {
+            tracked.state = if tracked.remaining_count == 0 {
+                OrderState::Executed
+            } else {
+                OrderState::PartiallyFilled
+            };
+        }
+    }
+
+    // Applies a fill. Fills for orders we aren't tracking and duplicate fills are ignored.
+    pub fn on_fill(&mut self, fill: &Fill) {
+        let Some(client_order_id) = self.client_order_ids.get(&fill.order_id) else {
+            return;
+        };
+        let Some(tracked) = self.orders.get_mut(client_order_id) else {
+            return;
+        };
+        if !self.seen_trade_ids.insert(fill.trade_id.clone()) {
+            return;
+        }
+        tracked.fills.push(fill.clone());
+        // The exchange may already have reported this fill in an order update.
+        tracked.reconcile_fills();
+        tracked.remaining_count = tracked
+            .remaining_count
+            .min(tracked.count - tracked.filled_count - tracked.decreased_count)
+            .max(0);
+        if tracked.state != OrderState::Canceled {
+            tracked.state = if tracked.remaining_count == 0 {
+                OrderState::Executed
+            } else {
+                OrderState::PartiallyFilled
+            };
+        }
+    }
+
+    // Compares the local view of an order against the exchange's without applying it.
+    pub fn detect_drift(&self, order: &Order) -> Option<OrderDrift> {
+        let tracked = self.orders.get(&order.client_order_id)?;
+        let exchange_state = exchange_state(order);
+        let exchange_filled_count = exchange_filled_count(order);
+        if tracked.state == exchange_state
+            && tracked.filled_count == exchange_filled_count
+            && tracked.remaining_count == order.remaining_count
+        {
+            return None;
+        }
+        Some(OrderDrift {
+            client_order_id: tracked.client_order_id.clone(),
+            order_id: order.order_id.clone(),
+            local_state: tracked.state,
+            exchange_state,
+            local_filled_count: tracked.filled_count,
+            exchange_filled_count,
+            local_remaining_count: tracked.remaining_count,
+            exchange_remaining_count: order.remaining_count,
+        })
+    }
+
+    // Polls get_order_async for every open order, records any drift from the local state and then
+    // adopts the exchange's view.
+    pub async fn poll_async<T: Trading + ?Sized>(
+        &mut self,
+        backend: &T,
+    ) -> Result<Vec<OrderDrift>, Box<dyn std::error::Error + Send + Sync>> {
+        let order_ids: Vec<String> = self
+            .open_orders()
+            .filter_map(|order| order.order_id.clone())
+            .collect();
+        let mut drift = Vec::new();
+        for order_id in order_ids {
+            let response = backend.get_order_async(&order_id).await?;
+            if let Some(order_drift) = self.detect_drift(&response.order) {
+                info!("Order drift detected: {:?}", order_drift);
+                drift.push(order_drift);
+            }
+            self.on_order_update(&response.order);
+        }
+        Ok(drift)
+    }
+}
+
+fn exchange_filled_count(order: &Order) -> i32 {
+    order.maker_fill_count + order.taker_fill_count as i32
+}
+
+fn exchange_state(order: &Order) -> OrderState {
+    match order.status.as_str() {
+        "resting" if exchange_filled_count(order) > 0 => OrderState::PartiallyFilled,
+        "resting" => OrderState::Resting,
+        "executed" => OrderState::Executed,
+        "canceled" => OrderState::Canceled,
+        _ => OrderState::Pending,
+    }
+}
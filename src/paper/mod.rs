@@ -0,0 +1,1205 @@
+use crate::client::{KalshiClient, MarketData, Portfolio, Trading};
+use crate::exchange::{ExchangeSchedule, ExchangeStatus};
+use crate::fees::FeeSchedule;
+use crate::ledger::Ledger;
+use crate::market::{
+    self,
+    api_structs::{GetEventsResponse, GetMarketsResponse, Orderbook},
+    GetEventResponse, GetMarketOrderbookResponse, GetMarketResponse, GetTradesResponse,
+};
+use crate::money::{Cents, Price};
+use crate::portfolio::{
+    self, AmendOrderResponse, Balance, CancelOrderResponse, CreateOrderRequest,
+    CreateOrderResponse, DecreaseOrderResponse, EventPosition, Fill, GetFillsResponse,
+    GetOrderResponse, GetOrdersResponse, GetPositionsResponse, GetSettlementsResponse,
+    MarketPosition, Order,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const PAPER_USER_ID: &str = "paper";
+const ORDERBOOK_DEPTH: i32 = 100;
+
+// Simulates the portfolio endpoints locally. Orders are matched against real orderbook
+// snapshots fetched from the exchange, fees are charged from a FeeSchedule, and positions
+// and cash are tracked with a Ledger.
+// Every method has the same parameters and return type as its counterpart in
+// crate::portfolio so strategies can switch between the two.
+#[derive(Debug)]
+pub struct PaperTrading {
+    state: Mutex<PaperState>,
+}
+
+#[derive(Debug)]
+struct PaperState {
+    balance: Cents,
+    orders: Vec<Order>,
+    fills: Vec<Fill>,
+    ledger: Ledger,
+    orderbooks: HashMap<String, Orderbook>,
+    // Contracts taken from orderbook levels by paper fills, keyed by ticker and then by
+    // (level is in the yes book, level price). Snapshots from the exchange don't know about
+    // paper fills, so this is subtracted from every later snapshot while the level lasts.
+    consumed: HashMap<String, HashMap<(bool, i32), i32>>,
+    fees: FeeSchedule,
+    next_id: u64,
+}
+
+impl PaperTrading {
+    pub fn new(starting_balance: Cents) -> Self {
+        PaperTrading::with_fee_schedule(starting_balance, FeeSchedule::default())
+    }
+
+    pub fn with_fee_schedule(starting_balance: Cents, fees: FeeSchedule) -> Self {
+        PaperTrading {
+            state: Mutex::new(PaperState {
+                balance: starting_balance,
+                orders: Vec::new(),
+                fills: Vec::new(),
+                ledger: Ledger::new(),
+                orderbooks: HashMap::new(),
+                consumed: HashMap::new(),
+                fees,
+                next_id: 1,
+            }),
+        }
+    }
+
+    // Records an orderbook snapshot and matches any resting orders in the market against it.
+    pub fn update_orderbook(&self, ticker: &str, orderbook: &Orderbook) {
+        let mut state = self.state.lock().unwrap();
+        state.set_orderbook(ticker, orderbook.clone());
+        state.match_resting_orders(ticker);
+    }
+
+    // Fetches a fresh orderbook for every market with resting orders.
+    pub async fn refresh_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let tickers: Vec<String> = {
+            let state = self.state.lock().unwrap();
+            let mut tickers: Vec<String> = state
+                .orders
+                .iter()
+                .filter(|order| order.status == "resting")
+                .map(|order| order.ticker.clone())
+                .collect();
+            tickers.sort();
+            tickers.dedup();
+            tickers
+        };
+        for ticker in tickers {
+            let response =
+                market::get_market_orderbook_async(client, &ticker, ORDERBOOK_DEPTH, token).await?;
+            self.update_orderbook(&ticker, &response.orderbook);
+        }
+        Ok(())
+    }
+
+    pub async fn get_balance_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+    ) -> Result<Balance, Box<dyn std::error::Error + Send + Sync>> {
+        self.refresh_async(client, token).await?;
+        let state = self.state.lock().unwrap();
+        Ok(Balance {
+            balance: state.balance,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_fills_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        ticker: Option<&str>,
+        order_id: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        limit: Option<i32>,
+        cursor: Option<&str>,
+    ) -> Result<GetFillsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.refresh_async(client, token).await?;
+        let state = self.state.lock().unwrap();
+        let fills: Vec<Fill> = state
+            .fills
+            .iter()
+            .rev()
+            .filter(|fill| ticker.is_none_or(|ticker| fill.ticker == ticker))
+            .filter(|fill| order_id.is_none_or(|order_id| fill.order_id == order_id))
+            .filter(|fill| min_ts.is_none_or(|min_ts| fill.created_time >= min_ts))
+            .filter(|fill| max_ts.is_none_or(|max_ts| fill.created_time <= max_ts))
+            .cloned()
+            .collect();
+        let (fills, cursor) = paginate(fills, cursor, limit)?;
+        Ok(GetFillsResponse { cursor, fills })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_orders_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        status: Option<&str>,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+    ) -> Result<GetOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.refresh_async(client, token).await?;
+        let state = self.state.lock().unwrap();
+        let event_prefix = event_ticker.map(|event_ticker| format!("{}-", event_ticker));
+        let orders: Vec<Order> = state
+            .orders
+            .iter()
+            .rev()
+            .filter(|order| ticker.is_none_or(|ticker| order.ticker == ticker))
+            .filter(|order| {
+                event_prefix
+                    .as_ref()
+                    .is_none_or(|prefix| order.ticker.starts_with(prefix))
+            })
+            .filter(|order| min_ts.is_none_or(|min_ts| order.created_time >= min_ts))
+            .filter(|order| max_ts.is_none_or(|max_ts| order.created_time <= max_ts))
+            .filter(|order| status.is_none_or(|status| order.status == status))
+            .cloned()
+            .collect();
+        let (orders, cursor) = paginate(orders, cursor, limit)?;
+        Ok(GetOrdersResponse { cursor, orders })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_order_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        action: &str,
+        buy_max_cost: Option<Cents>,
+        client_order_id: &str,
+        count: i32,
+        expiration_ts: Option<DateTime<Utc>>,
+        no_price: Option<Price>,
+        _sell_position_floor: Option<i32>,
+        side: &str, // yes or no
+        ticker: &str,
+        type_: &str, // limit or market
+        yes_price: Option<Price>,
+    ) -> Result<CreateOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let market = market::get_market_async(client, ticker, token)
+            .await?
+            .market;
+        let orderbook = market::get_market_orderbook_async(client, ticker, ORDERBOOK_DEPTH, token)
+            .await?
+            .orderbook;
+
+        // Market orders are treated as limit orders at the most aggressive price.
+        let (yes_price, no_price) = match (yes_price, no_price) {
+            (Some(yes_price), _) => (yes_price, yes_price.complement()),
+            (None, Some(no_price)) => (no_price.complement(), no_price),
+            (None, None) if type_ == "market" => {
+                let limit = if action == "buy" {
+                    Price::MAX
+                } else {
+                    Price::MIN
+                };
+                if side == "yes" {
+                    (limit, limit.complement())
+                } else {
+                    (limit.complement(), limit)
+                }
+            }
+            (None, None) => {
+                return Err(Box::from(
+                    "create_order requires a yes_price or no_price for limit orders",
+                ))
+            }
+        };
+        let price = if side == "yes" { yes_price } else { no_price };
+        if action == "buy" {
+            let (worst_case_cost, balance) = {
+                let state = self.state.lock().unwrap();
+                let cost = buy_max_cost.unwrap_or_else(|| {
+                    price.cents() * count as i64 + state.fees.taker_fee(ticker, count as i64, price)
+                });
+                (cost, state.balance)
+            };
+            if worst_case_cost > balance {
+                return Err(Box::from(format!(
+                    "create_order unsuccessful with status 400 and message insufficient balance: {} required, {} available",
+                    worst_case_cost, balance
+                )));
+            }
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if action == "sell" {
+            state.check_sell_position(ticker, side, count)?;
+        }
+        state.ledger.mark(&market);
+        state.set_orderbook(ticker, orderbook);
+        let now = Utc::now();
+        let order = Order {
+            action: action.to_string(),
+            client_order_id: client_order_id.to_string(),
+            close_cancel_count: 0,
+            created_time: now,
+            decrease_count: 0,
+            expiration_time: expiration_ts,
+            fcc_cancel_count: 0,
+            last_update_time: now,
+            maker_fill_count: 0,
+            maker_fill_cost: Cents::ZERO,
+            maker_fees: Cents::ZERO,
+            no_price,
+            order_id: state.next_order_id(),
+            place_count: count,
+            queue_position: 0,
+            remaining_count: count,
+            side: side.to_string(),
+            status: "resting".to_string(),
+            taker_fees: Cents::ZERO,
+            taker_fill_cost: Cents::ZERO,
+            taker_fill_count: 0,
+            ticker: ticker.to_string(),
+            type_: type_.to_string(),
+            user_id: PAPER_USER_ID.to_string(),
+            yes_price,
+        };
+        let index = state.orders.len();
+        state.orders.push(order);
+        state.match_order(index, true);
+
+        let order = &mut state.orders[index];
+        if order.type_ == "market" && order.remaining_count > 0 {
+            order.decrease_count = order.remaining_count;
+            order.remaining_count = 0;
+        }
+        if order.remaining_count == 0 && order.status == "resting" {
+            order.status = if order.taker_fill_count > 0 {
+                "executed".to_string()
+            } else {
+                "canceled".to_string()
+            };
+        }
+        info!("Paper order created: {:?}", order);
+        Ok(CreateOrderResponse {
+            order: order.clone(),
+        })
+    }
+
+    pub async fn get_order(
+        &self,
+        _client: &reqwest::Client,
+        _token: &str,
+        order_id: &str,
+    ) -> Result<GetOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let state = self.state.lock().unwrap();
+        match state.orders.iter().find(|order| order.order_id == order_id) {
+            Some(order) => Ok(GetOrderResponse {
+                order: order.clone(),
+            }),
+            None => Err(Box::from(format!(
+                "get_order unsuccessful with status 404 and message order '{}' not found",
+                order_id
+            ))),
+        }
+    }
+
+    pub async fn decrease_order_async(
+        &self,
+        _client: &reqwest::Client,
+        _token: &str,
+        order_id: &str,
+        reduce_by: Option<i32>,
+        reduce_to: Option<i32>,
+    ) -> Result<DecreaseOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.state.lock().unwrap();
+        let Some(order) = state
+            .orders
+            .iter_mut()
+            .find(|order| order.order_id == order_id && order.status == "resting")
+        else {
+            return Err(Box::from(format!(
+                "decrease_order unsuccessful with status 404 and message resting order '{}' not found",
+                order_id
+            )));
+        };
+        let reduce_by = match (reduce_by, reduce_to) {
+            (Some(reduce_by), None) => reduce_by,
+            (None, Some(reduce_to)) => order.remaining_count - reduce_to,
+            _ => {
+                return Err(Box::from(
+                    "decrease_order requires exactly one of reduce_by or reduce_to",
+                ))
+            }
+        };
+        let reduce_by = reduce_by.clamp(0, order.remaining_count);
+        order.remaining_count -= reduce_by;
+        order.decrease_count += reduce_by;
+        if order.remaining_count == 0 {
+            order.status = "canceled".to_string();
+        }
+        order.last_update_time = Utc::now();
+        Ok(DecreaseOrderResponse {
+            order: order.clone(),
+        })
+    }
+
+    pub async fn cancel_order_async(
+        &self,
+        _client: &reqwest::Client,
+        _token: &str,
+        order_id: &str,
+    ) -> Result<CancelOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.state.lock().unwrap();
+        let Some(order) = state
+            .orders
+            .iter_mut()
+            .find(|order| order.order_id == order_id)
+        else {
+            return Err(Box::from(format!(
+                "cancel_order unsuccessful with status 404 and message order '{}' not found",
+                order_id
+            )));
+        };
+        if order.status != "resting" {
+            return Err(Box::from(format!(
+                "cancel_order unsuccessful with status 400 and message order '{}' is {}",
+                order_id, order.status
+            )));
+        }
+        let reduced_by = order.remaining_count as i64;
+        order.decrease_count += order.remaining_count;
+        order.remaining_count = 0;
+        order.status = "canceled".to_string();
+        order.last_update_time = Utc::now();
+        Ok(CancelOrderResponse {
+            order: order.clone(),
+            reduced_by,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_positions_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        _cursor: Option<&str>,
+        _limit: Option<i32>,
+        _settlement_status: Option<&str>,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+    ) -> Result<GetPositionsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.refresh_async(client, token).await?;
+        let state = self.state.lock().unwrap();
+        let resting_order_count = |matches: &dyn Fn(&Order) -> bool| {
+            state
+                .orders
+                .iter()
+                .filter(|order| order.status == "resting" && matches(order))
+                .count() as i64
+        };
+        let market_positions = state
+            .ledger
+            .markets()
+            .filter(|market| ticker.is_none_or(|ticker| market.ticker == ticker))
+            .filter(|market| {
+                event_ticker
+                    .is_none_or(|event_ticker| market.event_ticker.as_deref() == Some(event_ticker))
+            })
+            .map(|market| MarketPosition {
+                fees_paid: market.fees,
+                market_exposure: market.total_cost(),
+                position: market.position,
+                realized_pnl: market.realized_pnl,
+                resting_order_count: resting_order_count(&|order| order.ticker == market.ticker),
+                ticker: market.ticker.clone(),
+                total_cost: market.total_cost(),
+            })
+            .collect();
+        let event_positions = state
+            .ledger
+            .events()
+            .into_iter()
+            .filter(|event| {
+                event_ticker.is_none_or(|event_ticker| event.event_ticker == event_ticker)
+            })
+            .map(|event| EventPosition {
+                event_exposure: event.total_cost,
+                fees_paid: event.fees,
+                realized_pnl: event.realized_pnl,
+                resting_order_count: resting_order_count(&|order| {
+                    event.tickers.contains(&order.ticker)
+                }) as i32,
+                total_cost: event.total_cost,
+                event_ticker: event.event_ticker,
+            })
+            .collect();
+        Ok(GetPositionsResponse {
+            cursor: String::new(),
+            event_positions,
+            market_positions,
+        })
+    }
+}
+
+impl PaperTrading {
+    // Paper positions are never settled by the exchange, so there are no settlements.
+    pub async fn get_settlements_async(
+        &self,
+        _client: &reqwest::Client,
+        _token: &str,
+        _limit: Option<i64>,
+        _cursor: Option<String>,
+    ) -> Result<GetSettlementsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(GetSettlementsResponse {
+            cursor: String::new(),
+            settlements: Vec::new(),
+        })
+    }
+}
+
+impl PaperState {
+    fn next_order_id(&mut self) -> String {
+        let order_id = format!("paper-{}", self.next_id);
+        self.next_id += 1;
+        order_id
+    }
+
+    // Selling more than is held, less what resting sells already cover, would open a short
+    // position, which the exchange doesn't allow.
+    fn check_sell_position(
+        &self,
+        ticker: &str,
+        side: &str,
+        count: i32,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let position = self
+            .ledger
+            .market(ticker)
+            .map_or(0, |market| market.position);
+        let held = if side == "yes" {
+            position.max(0)
+        } else {
+            (-position).max(0)
+        };
+        let resting_sells: i64 = self
+            .orders
+            .iter()
+            .filter(|order| {
+                order.status == "resting"
+                    && order.action == "sell"
+                    && order.ticker == ticker
+                    && order.side == side
+            })
+            .map(|order| order.remaining_count as i64)
+            .sum();
+        let available = held - resting_sells;
+        if count as i64 > available {
+            return Err(Box::from(format!(
+                "create_order unsuccessful with status 400 and message insufficient position: selling {} {} contracts with {} available",
+                count, side, available.max(0)
+            )));
+        }
+        Ok(())
+    }
+
+    // Stores a new snapshot. Levels that have gone, or shrunk below what paper fills took,
+    // were traded or pulled by others, so only the part still showing stays consumed.
+    fn set_orderbook(&mut self, ticker: &str, orderbook: Orderbook) {
+        let consumed = self.consumed.entry(ticker.to_string()).or_default();
+        consumed.retain(|(is_yes, price), count| {
+            let levels = if *is_yes {
+                &orderbook.yes
+            } else {
+                &orderbook.no
+            };
+            match levels
+                .iter()
+                .find(|level| level.len() == 2 && level[0] == *price)
+            {
+                Some(level) => {
+                    *count = (*count).min(level[1]);
+                    *count > 0
+                }
+                None => false,
+            }
+        });
+        self.orderbooks.insert(ticker.to_string(), orderbook);
+    }
+
+    fn match_resting_orders(&mut self, ticker: &str) {
+        let indices: Vec<usize> = self
+            .orders
+            .iter()
+            .enumerate()
+            .filter(|(_, order)| order.status == "resting" && order.ticker == ticker)
+            .map(|(index, _)| index)
+            .collect();
+        for index in indices {
+            self.match_order(index, false);
+        }
+    }
+
+    // Fills as much of the order as the current orderbook allows. Orderbooks only contain
+    // bids, so buying yes at p takes no bids at 100 - p or better, and selling yes at p
+    // hits yes bids at p or better. The same holds for no with the sides swapped. Each fill
+    // uses up the size it took from the level.
+    fn match_order(&mut self, index: usize, is_taker: bool) {
+        let order = &self.orders[index];
+        let Some(orderbook) = self.orderbooks.get(&order.ticker) else {
+            return;
+        };
+        let is_buy = order.action == "buy";
+        // Buys take from the other side's bids and sells hit the same side's.
+        let book_is_yes = (order.side == "yes") != is_buy;
+        let book = if book_is_yes {
+            &orderbook.yes
+        } else {
+            &orderbook.no
+        };
+        let limit = if order.side == "yes" {
+            order.yes_price.value() as i32
+        } else {
+            order.no_price.value() as i32
+        };
+        let consumed = self.consumed.get(&order.ticker);
+
+        // (price on the order's side, level price in the book, available count), best price
+        // first.
+        let mut levels: Vec<(i32, i32, i32)> = book
+            .iter()
+            .filter(|level| level.len() == 2)
+            .map(|level| {
+                let taken = consumed
+                    .and_then(|consumed| consumed.get(&(book_is_yes, level[0])))
+                    .copied()
+                    .unwrap_or(0);
+                let price = if is_buy { 100 - level[0] } else { level[0] };
+                (price, level[0], level[1] - taken)
+            })
+            .filter(|(price, _, _)| {
+                if is_buy {
+                    *price <= limit
+                } else {
+                    *price >= limit
+                }
+            })
+            .collect();
+        if is_buy {
+            levels.sort_by_key(|(price, _, _)| *price);
+        } else {
+            levels.sort_by_key(|(price, _, _)| -price);
+        }
+
+        let mut executions = Vec::new();
+        let mut remaining = order.remaining_count;
+        for (price, level_price, available) in levels {
+            if remaining == 0 {
+                break;
+            }
+            let count = remaining.min(available);
+            if count <= 0 {
+                continue;
+            }
+            let Ok(price) = Price::try_from(price) else {
+                continue;
+            };
+            executions.push((price, level_price, count));
+            remaining -= count;
+        }
+        let ticker = order.ticker.clone();
+        for (price, level_price, count) in executions {
+            *self
+                .consumed
+                .entry(ticker.clone())
+                .or_default()
+                .entry((book_is_yes, level_price))
+                .or_insert(0) += count;
+            self.execute(index, price, count, is_taker);
+        }
+    }
+
+    fn execute(&mut self, index: usize, price: Price, count: i32, is_taker: bool) {
+        let now = Utc::now();
+        let trade_id = format!("paper-trade-{}", self.next_id);
+        self.next_id += 1;
+
+        let order = &mut self.orders[index];
+        let (yes_price, no_price) = if order.side == "yes" {
+            (price, price.complement())
+        } else {
+            (price.complement(), price)
+        };
+        let fee = self.fees.fee(&order.ticker, count as i64, price, is_taker);
+        order.remaining_count -= count;
+        if is_taker {
+            order.taker_fill_count += count as i64;
+            order.taker_fill_cost += price.cents() * count as i64;
+            order.taker_fees += fee;
+        } else {
+            order.maker_fill_count += count;
+            order.maker_fill_cost += price.cents() * count as i64;
+            order.maker_fees += fee;
+        }
+        if order.remaining_count == 0 {
+            order.status = "executed".to_string();
+        }
+        order.last_update_time = now;
+
+        let fill = Fill {
+            action: order.action.clone(),
+            count,
+            created_time: now,
+            is_taker,
+            no_price,
+            order_id: order.order_id.clone(),
+            side: order.side.clone(),
+            ticker: order.ticker.clone(),
+            trade_id,
+            yes_price,
+        };
+
+        // Cash moves by the change in what we've paid for open positions plus whatever
+        // P&L was realized by closing positions, less fees.
+        let before = self
+            .ledger
+            .market(&fill.ticker)
+            .cloned()
+            .unwrap_or_default();
+        self.ledger.apply_fill(&fill);
+        self.ledger.apply_fee(&fill.ticker, fee);
+        let after = self
+            .ledger
+            .market(&fill.ticker)
+            .cloned()
+            .unwrap_or_default();
+        self.balance += (before.total_cost() - after.total_cost())
+            + (after.realized_pnl - before.realized_pnl)
+            - fee;
+        info!("Paper fill: {:?}", fill);
+        self.fills.push(fill);
+    }
+}
+
+// Returns one page of items and the cursor for the next page, which is empty on the last
+// page. Cursors are offsets into the full list.
+fn paginate<T>(
+    items: Vec<T>,
+    cursor: Option<&str>,
+    limit: Option<i32>,
+) -> Result<(Vec<T>, String), Box<dyn std::error::Error + Send + Sync>> {
+    let offset = match cursor.filter(|cursor| !cursor.is_empty()) {
+        Some(cursor) => cursor.parse::<usize>().map_err(|_| {
+            format!(
+                "request unsuccessful with status 400 and message invalid cursor '{}'",
+                cursor
+            )
+        })?,
+        None => 0,
+    };
+    let limit = limit.map_or(usize::MAX, |limit| limit.max(1) as usize);
+    let end = offset.saturating_add(limit).min(items.len());
+    let cursor = if end < items.len() {
+        end.to_string()
+    } else {
+        String::new()
+    };
+    let page = items.into_iter().skip(offset).take(limit).collect();
+    Ok((page, cursor))
+}
+
+// Selects between live trading and paper trading with a single flag. The methods forward to
+// crate::portfolio or to the PaperTrading engine.
+#[derive(Debug)]
+pub enum Trader {
+    Live,
+    Paper(Box<PaperTrading>),
+}
+
+impl Trader {
+    pub fn new(paper_trading: bool, paper_starting_balance: Cents) -> Self {
+        if paper_trading {
+            Trader::Paper(Box::new(PaperTrading::new(paper_starting_balance)))
+        } else {
+            Trader::Live
+        }
+    }
+
+    pub async fn get_balance_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+    ) -> Result<Balance, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Trader::Live => portfolio::get_balance_async(client, token).await,
+            Trader::Paper(paper) => paper.get_balance_async(client, token).await,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_fills_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        ticker: Option<&str>,
+        order_id: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        limit: Option<i32>,
+        cursor: Option<&str>,
+    ) -> Result<GetFillsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Trader::Live => {
+                portfolio::get_fills_async(
+                    client, token, ticker, order_id, min_ts, max_ts, limit, cursor,
+                )
+                .await
+            }
+            Trader::Paper(paper) => {
+                paper
+                    .get_fills_async(
+                        client, token, ticker, order_id, min_ts, max_ts, limit, cursor,
+                    )
+                    .await
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_orders_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        status: Option<&str>,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+    ) -> Result<GetOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Trader::Live => {
+                portfolio::get_orders_async(
+                    client,
+                    token,
+                    ticker,
+                    event_ticker,
+                    min_ts,
+                    max_ts,
+                    status,
+                    cursor,
+                    limit,
+                )
+                .await
+            }
+            Trader::Paper(paper) => {
+                paper
+                    .get_orders_async(
+                        client,
+                        token,
+                        ticker,
+                        event_ticker,
+                        min_ts,
+                        max_ts,
+                        status,
+                        cursor,
+                        limit,
+                    )
+                    .await
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_order_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        action: &str,
+        buy_max_cost: Option<Cents>,
+        client_order_id: &str,
+        count: i32,
+        expiration_ts: Option<DateTime<Utc>>,
+        no_price: Option<Price>,
+        sell_position_floor: Option<i32>,
+        side: &str, // yes or no
+        ticker: &str,
+        type_: &str, // limit or market
+        yes_price: Option<Price>,
+    ) -> Result<CreateOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Trader::Live => {
+                portfolio::create_order_async(
+                    client,
+                    token,
+                    action,
+                    buy_max_cost,
+                    client_order_id,
+                    count,
+                    expiration_ts,
+                    no_price,
+                    sell_position_floor,
+                    side,
+                    ticker,
+                    type_,
+                    yes_price,
+                )
+                .await
+            }
+            Trader::Paper(paper) => {
+                paper
+                    .create_order_async(
+                        client,
+                        token,
+                        action,
+                        buy_max_cost,
+                        client_order_id,
+                        count,
+                        expiration_ts,
+                        no_price,
+                        sell_position_floor,
+                        side,
+                        ticker,
+                        type_,
+                        yes_price,
+                    )
+                    .await
+            }
+        }
+    }
+
+    pub async fn cancel_order_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        order_id: &str,
+    ) -> Result<CancelOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Trader::Live => portfolio::cancel_order_async(client, token, order_id).await,
+            Trader::Paper(paper) => paper.cancel_order_async(client, token, order_id).await,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_positions_async(
+        &self,
+        client: &reqwest::Client,
+        token: &str,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+        settlement_status: Option<&str>,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+    ) -> Result<GetPositionsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Trader::Live => {
+                portfolio::get_positions_async(
+                    client,
+                    token,
+                    cursor,
+                    limit,
+                    settlement_status,
+                    ticker,
+                    event_ticker,
+                )
+                .await
+            }
+            Trader::Paper(paper) => {
+                paper
+                    .get_positions_async(
+                        client,
+                        token,
+                        cursor,
+                        limit,
+                        settlement_status,
+                        ticker,
+                        event_ticker,
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+// Pairs the paper-trading engine with a live client for market data, so it can be used
+// anywhere the MarketData, Trading and Portfolio traits are expected.
+#[derive(Debug)]
+pub struct PaperClient {
+    pub live: KalshiClient,
+    pub paper: PaperTrading,
+}
+
+impl PaperClient {
+    pub fn new(live: KalshiClient, starting_balance: Cents) -> Self {
+        PaperClient {
+            live,
+            paper: PaperTrading::new(starting_balance),
+        }
+    }
+}
+
+#[async_trait]
+impl MarketData for PaperClient {
+    async fn get_exchange_status_async(
+        &self,
+    ) -> Result<ExchangeStatus, Box<dyn std::error::Error + Send + Sync>> {
+        self.live.get_exchange_status_async().await
+    }
+
+    async fn get_exchange_schedule_async(
+        &self,
+    ) -> Result<ExchangeSchedule, Box<dyn std::error::Error + Send + Sync>> {
+        self.live.get_exchange_schedule_async().await
+    }
+
+    async fn get_event_async(
+        &self,
+        event_ticker: &str,
+    ) -> Result<GetEventResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.live.get_event_async(event_ticker).await
+    }
+
+    async fn get_events_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        status: Option<&str>,
+        series_ticker: Option<&str>,
+    ) -> Result<GetEventsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.live
+            .get_events_async(limit, cursor, status, series_ticker)
+            .await
+    }
+
+    async fn get_market_async(
+        &self,
+        market_ticker: &str,
+    ) -> Result<GetMarketResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.live.get_market_async(market_ticker).await
+    }
+
+    async fn get_markets_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<&str>,
+        event_ticker: Option<&str>,
+        series_ticker: Option<&str>,
+        max_close_ts: Option<DateTime<Utc>>,
+        min_close_ts: Option<DateTime<Utc>>,
+        status: Option<&str>,
+        tickers: Option<&str>,
+    ) -> Result<GetMarketsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.live
+            .get_markets_async(
+                limit,
+                cursor,
+                event_ticker,
+                series_ticker,
+                max_close_ts,
+                min_close_ts,
+                status,
+                tickers,
+            )
+            .await
+    }
+
+    async fn get_market_orderbook_async(
+        &self,
+        market_ticker: &str,
+        depth: i32,
+    ) -> Result<GetMarketOrderbookResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let response = self
+            .live
+            .get_market_orderbook_async(market_ticker, depth)
+            .await?;
+        self.paper
+            .update_orderbook(market_ticker, &response.orderbook);
+        Ok(response)
+    }
+
+    async fn get_trades_async(
+        &self,
+        ticker: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        limit: Option<i32>,
+        cursor: Option<&str>,
+    ) -> Result<GetTradesResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.live
+            .get_trades_async(ticker, min_ts, max_ts, limit, cursor)
+            .await
+    }
+}
+
+#[async_trait]
+impl Trading for PaperClient {
+    async fn create_order_async(
+        &self,
+        order: &CreateOrderRequest,
+    ) -> Result<CreateOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .create_order_async(
+                &self.live.client,
+                &self.live.token,
+                &order.action,
+                order.buy_max_cost,
+                &order.client_order_id,
+                order.count,
+                order.expiration_ts,
+                order.no_price,
+                order.sell_position_floor,
+                &order.side,
+                &order.ticker,
+                &order.type_,
+                order.yes_price,
+            )
+            .await
+    }
+
+    async fn get_order_async(
+        &self,
+        order_id: &str,
+    ) -> Result<GetOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .get_order(&self.live.client, &self.live.token, order_id)
+            .await
+    }
+
+    async fn cancel_order_async(
+        &self,
+        order_id: &str,
+    ) -> Result<CancelOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .cancel_order_async(&self.live.client, &self.live.token, order_id)
+            .await
+    }
+
+    async fn amend_order_async(
+        &self,
+        _order_id: &str,
+        _action: &str,
+        _client_order_id: &str,
+        _count: i32,
+        _no_price: Option<Price>,
+        _side: &str,
+        _ticker: &str,
+        _updated_client_order_id: &str,
+        _yes_price: Option<Price>,
+    ) -> Result<AmendOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        Err(Box::from("amend_order is not supported by paper trading"))
+    }
+
+    async fn decrease_order_async(
+        &self,
+        order_id: &str,
+        reduce_by: Option<i32>,
+        reduce_to: Option<i32>,
+    ) -> Result<DecreaseOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .decrease_order_async(
+                &self.live.client,
+                &self.live.token,
+                order_id,
+                reduce_by,
+                reduce_to,
+            )
+            .await
+    }
+}
+
+#[async_trait]
+impl Portfolio for PaperClient {
+    async fn get_balance_async(&self) -> Result<Balance, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .get_balance_async(&self.live.client, &self.live.token)
+            .await
+    }
+
+    async fn get_fills_async(
+        &self,
+        ticker: Option<&str>,
+        order_id: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        limit: Option<i32>,
+        cursor: Option<&str>,
+    ) -> Result<GetFillsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .get_fills_async(
+                &self.live.client,
+                &self.live.token,
+                ticker,
+                order_id,
+                min_ts,
+                max_ts,
+                limit,
+                cursor,
+            )
+            .await
+    }
+
+    async fn get_orders_async(
+        &self,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+        min_ts: Option<DateTime<Utc>>,
+        max_ts: Option<DateTime<Utc>>,
+        status: Option<&str>,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+    ) -> Result<GetOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .get_orders_async(
+                &self.live.client,
+                &self.live.token,
+                ticker,
+                event_ticker,
+                min_ts,
+                max_ts,
+                status,
+                cursor,
+                limit,
+            )
+            .await
+    }
+
+    async fn get_positions_async(
+        &self,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+        settlement_status: Option<&str>,
+        ticker: Option<&str>,
+        event_ticker: Option<&str>,
+    ) -> Result<GetPositionsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .get_positions_async(
+                &self.live.client,
+                &self.live.token,
+                cursor,
+                limit,
+                settlement_status,
+                ticker,
+                event_ticker,
+            )
+            .await
+    }
+
+    async fn get_settlements_async(
+        &self,
+        limit: Option<i64>,
+        cursor: Option<String>,
+    ) -> Result<GetSettlementsResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.paper
+            .get_settlements_async(&self.live.client, &self.live.token, limit, cursor)
+            .await
+    }
+}
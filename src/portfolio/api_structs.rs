@@ -1,121 +1,233 @@
-use serde::Deserialize;
+use crate::money::{Cents, Price};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Fill {
     pub action: String,
     pub count: i32,
-    pub created_time: String,
+    pub created_time: DateTime<Utc>,
     pub is_taker: bool,
-    pub no_price: i32,
+    pub no_price: Price,
     pub order_id: String,
     pub side: String,
     pub ticker: String,
     pub trade_id: String,
-    pub yes_price: i32,
+    pub yes_price: Price,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Balance {
-    pub balance: i32,
+    pub balance: Cents,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Order {
     pub action: String,
     pub client_order_id: String,
     pub close_cancel_count: i32,
-    pub created_time: String,
+    pub created_time: DateTime<Utc>,
     pub decrease_count: i32,
-    pub expiration_time: String,
+    pub expiration_time: Option<DateTime<Utc>>,
     pub fcc_cancel_count: i32,
-    pub last_update_time: String,
+    pub last_update_time: DateTime<Utc>,
+    // Not in older API responses.
+    #[serde(default)]
+    pub maker_fees: Cents,
     pub maker_fill_count: i32,
-    pub no_price: i64,
+    // Not in older API responses.
+    #[serde(default)]
+    pub maker_fill_cost: Cents,
+    pub no_price: Price,
     pub order_id: String,
     pub place_count: i32,
     pub queue_position: i32,
     pub remaining_count: i32,
     pub side: String,
     pub status: String,
-    pub taker_fees: i32,
-    pub taker_fill_cost: i64,
+    pub taker_fees: Cents,
+    pub taker_fill_cost: Cents,
     pub taker_fill_count: i64,
     pub ticker: String,
+    #[serde(rename = "type")]
     pub type_: String,
     pub user_id: String,
-    pub yes_price: i64,
+    pub yes_price: Price,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EventPosition {
-    pub event_exposure: i32,
+    pub event_exposure: Cents,
     pub event_ticker: String,
-    pub fees_paid: i32,
-    pub realized_pnl: i32,
+    pub fees_paid: Cents,
+    pub realized_pnl: Cents,
     pub resting_order_count: i32,
-    pub total_cost: i32,
+    pub total_cost: Cents,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MarketPosition {
-    pub fees_paid: i32,
-    pub market_exposure: i32,
+    pub fees_paid: Cents,
+    pub market_exposure: Cents,
     pub position: i64,
-    pub realized_pnl: i64,
+    pub realized_pnl: Cents,
     pub resting_order_count: i64,
     pub ticker: String,
-    pub total_cost: i64,
+    pub total_cost: Cents,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Settlement {
     pub market_result: String,
     pub no_count: i64,
-    pub no_total_cost: i64,
-    pub revenue: i64,
-    pub settled_time: String,
+    pub no_total_cost: Cents,
+    pub revenue: Cents,
+    pub settled_time: DateTime<Utc>,
     pub ticker: String,
     pub yes_count: i64,
-    pub yes_total_cost: i32,
+    pub yes_total_cost: Cents,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetFillsResponse {
     pub cursor: String,
     pub fills: Vec<Fill>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetOrdersResponse {
     pub cursor: String,
     pub orders: Vec<Order>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CreateOrderResponse {
     pub order: Order,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetOrderResponse {
     pub order: Order,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CancelOrderResponse {
     pub order: Order,
     pub reduced_by: i64,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AmendOrderResponse {
+    pub old_order: Order,
+    pub order: Order,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DecreaseOrderResponse {
+    pub order: Order,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetPositionsResponse {
     pub cursor: String,
     pub event_positions: Vec<EventPosition>,
     pub market_positions: Vec<MarketPosition>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GetSettlementsResponse {
     pub cursor: String,
     pub settlements: Vec<Settlement>,
 }
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CreateOrderRequest {
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_max_cost: Option<Cents>,
+    pub client_order_id: String,
+    pub count: i32,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        with = "chrono::serde::ts_seconds_option"
+    )]
+    pub expiration_ts: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_price: Option<Price>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_position_floor: Option<i32>,
+    pub side: String, // yes or no
+    pub ticker: String,
+    #[serde(rename = "type")]
+    pub type_: String, // limit or market
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yes_price: Option<Price>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct AmendOrderRequest {
+    pub action: String,
+    pub client_order_id: String,
+    pub count: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_price: Option<Price>,
+    pub side: String, // yes or no
+    pub ticker: String,
+    pub updated_client_order_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yes_price: Option<Price>,
+}
+
+// Exactly one of reduce_by or reduce_to is set.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct DecreaseOrderRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_by: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_to: Option<i32>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BatchCreateOrdersRequest {
+    pub orders: Vec<CreateOrderRequest>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BatchCancelOrdersRequest {
+    pub ids: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchOrderError {
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchCreateOrderResult {
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+    pub order: Option<Order>,
+    pub error: Option<BatchOrderError>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchCreateOrdersResponse {
+    pub orders: Vec<BatchCreateOrderResult>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchCancelOrderResult {
+    pub order_id: String,
+    pub order: Option<Order>,
+    #[serde(default)]
+    pub reduced_by: i64,
+    pub error: Option<BatchOrderError>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchCancelOrdersResponse {
+    pub orders: Vec<BatchCancelOrderResult>,
+}
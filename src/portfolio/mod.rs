@@ -1,33 +1,48 @@
 pub use crate::portfolio::api_structs::{
-    Balance, CancelOrderResponse, CreateOrderResponse, EventPosition, Fill, GetFillsResponse,
-    GetOrderResponse, GetOrdersResponse, GetPositionsResponse, GetSettlementsResponse,
-    MarketPosition, Order, Settlement,
+    AmendOrderResponse, Balance, BatchCancelOrderResult, BatchCancelOrdersResponse,
+    BatchCreateOrderResult, BatchCreateOrdersResponse, BatchOrderError, CancelOrderResponse,
+    CreateOrderRequest, CreateOrderResponse, DecreaseOrderResponse, EventPosition, Fill,
+    GetFillsResponse, GetOrderResponse, GetOrdersResponse, GetPositionsResponse,
+    GetSettlementsResponse, MarketPosition, Order, Settlement,
 };
 
 pub mod api_structs;
 
+use self::api_structs::{
+    AmendOrderRequest, BatchCancelOrdersRequest, BatchCreateOrdersRequest, DecreaseOrderRequest,
+};
+use crate::environment;
+use crate::money::{Cents, Price};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt};
 use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{Interval, MissedTickBehavior};
 
-const GET_BALANCE_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/portfolio/balance/";
-const GET_FILLS_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/portfolio/fills/";
-const GET_ORDERS_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/portfolio/orders/";
-const CREATE_ORDER_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/portfolio/orders/";
-const CANCEL_ORDER_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/portfolio/orders/";
-const GET_ORDER_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/portfolio/orders/";
-const GET_POSITIONS_URL: &str = "https://trading-api.kalshi.com/trade-api/v2/portfolio/positions/";
-const GET_SETTLEMENTS_URL: &str =
-    "https://trading-api.kalshi.com/trade-api/v2/portfolio/settlements/";
+const GET_BALANCE_PATH: &str = "portfolio/balance/";
+const GET_FILLS_PATH: &str = "portfolio/fills/";
+const GET_ORDERS_PATH: &str = "portfolio/orders/";
+const CREATE_ORDER_PATH: &str = "portfolio/orders/";
+const CANCEL_ORDER_PATH: &str = "portfolio/orders/";
+const GET_ORDER_PATH: &str = "portfolio/orders/";
+const AMEND_ORDER_SUFFIX: &str = "/amend";
+const DECREASE_ORDER_SUFFIX: &str = "/decrease";
+const BATCH_ORDERS_PATH: &str = "portfolio/orders/batched";
+const GET_POSITIONS_PATH: &str = "portfolio/positions/";
+const GET_SETTLEMENTS_PATH: &str = "portfolio/settlements/";
 
 pub async fn get_balance_async(
     client: &reqwest::Client,
     token: &str,
 ) -> Result<Balance, Box<dyn std::error::Error + Send + Sync>> {
-    println!(
+    info!(
         "Fetching balance for user at endpoint '{}'",
-        GET_BALANCE_URL
+        GET_BALANCE_PATH
     );
+    let url = environment::api_url(GET_BALANCE_PATH)?;
     match client
-        .get(GET_BALANCE_URL)
+        .get(url)
         .bearer_auth(token)
         .header(
             reqwest::header::CONTENT_TYPE,
@@ -40,11 +55,11 @@ pub async fn get_balance_async(
             if result.status().is_success() {
                 match result.json::<Balance>().await {
                     Ok(body) => {
-                        println!("Successfully parsed balance response body: {:?}", body);
+                        info!("Successfully parsed balance response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse balance response body. Error - {}", e);
+                        info!("Unable to parse balance response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -66,18 +81,19 @@ pub async fn get_balance_async(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get_fills_async(
     client: &reqwest::Client,
     token: &str,
     ticker: Option<&str>,
     order_id: Option<&str>,
-    min_ts: Option<i64>,
-    max_ts: Option<i64>,
+    min_ts: Option<DateTime<Utc>>,
+    max_ts: Option<DateTime<Utc>>,
     limit: Option<i32>,
     cursor: Option<&str>,
 ) -> Result<GetFillsResponse, Box<dyn std::error::Error + Send + Sync>> {
-    println!("Fetching fills for user at endpoint '{}'", GET_FILLS_URL);
-    let url = reqwest::Url::parse(GET_FILLS_URL)?;
+    info!("Fetching fills for user at endpoint '{}'", GET_FILLS_PATH);
+    let url = environment::api_url(GET_FILLS_PATH)?;
     // let mut query_params: HashMap<Cow<'static, str>, Cow<'static, str>> = HashMap::new();
     let mut query_params: HashMap<String, String> = HashMap::new();
     if let Some(ticker) = ticker {
@@ -87,10 +103,10 @@ pub async fn get_fills_async(
         query_params.insert("order_id".to_string(), order_id.to_string());
     }
     if let Some(min_ts) = min_ts {
-        query_params.insert("min_ts".to_string(), min_ts.to_string());
+        query_params.insert("min_ts".to_string(), min_ts.timestamp().to_string());
     }
     if let Some(max_ts) = max_ts {
-        query_params.insert("max_ts".to_string(), max_ts.to_string());
+        query_params.insert("max_ts".to_string(), max_ts.timestamp().to_string());
     }
     if let Some(limit) = limit {
         query_params.insert("limit".to_string(), limit.to_string());
@@ -113,11 +129,11 @@ pub async fn get_fills_async(
             if result.status().is_success() {
                 match result.json::<GetFillsResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed fills response body: {:?}", body);
+                        info!("Successfully parsed fills response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse fills response body. Error - {}", e);
+                        info!("Unable to parse fills response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -139,19 +155,20 @@ pub async fn get_fills_async(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn get_orders_async(
     client: &reqwest::Client,
     token: &str,
     ticker: Option<&str>,
     event_ticker: Option<&str>,
-    min_ts: Option<i64>,
-    max_ts: Option<i64>,
+    min_ts: Option<DateTime<Utc>>,
+    max_ts: Option<DateTime<Utc>>,
     status: Option<&str>,
     cursor: Option<&str>,
     limit: Option<i32>,
-) -> Result<GetOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
-    println!("Fetching orders for user at endpoint '{}'", GET_ORDERS_URL);
-    let url = reqwest::Url::parse(GET_ORDERS_URL)?;
+) -> Result<GetOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+    info!("Fetching orders for user at endpoint '{}'", GET_ORDERS_PATH);
+    let url = environment::api_url(GET_ORDERS_PATH)?;
     let mut query_params: HashMap<String, String> = HashMap::new();
     if let Some(ticker) = ticker {
         query_params.insert("ticker".to_string(), ticker.to_string());
@@ -160,10 +177,10 @@ pub async fn get_orders_async(
         query_params.insert("event_ticker".to_string(), event_ticker.to_string());
     }
     if let Some(min_ts) = min_ts {
-        query_params.insert("min_ts".to_string(), min_ts.to_string());
+        query_params.insert("min_ts".to_string(), min_ts.timestamp().to_string());
     }
     if let Some(max_ts) = max_ts {
-        query_params.insert("max_ts".to_string(), max_ts.to_string());
+        query_params.insert("max_ts".to_string(), max_ts.timestamp().to_string());
     }
     if let Some(status) = status {
         query_params.insert("status".to_string(), status.to_string());
@@ -187,13 +204,13 @@ pub async fn get_orders_async(
     {
         Ok(result) => {
             if result.status().is_success() {
-                match result.json::<GetOrderResponse>().await {
+                match result.json::<GetOrdersResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed orders response body: {:?}", body);
+                        info!("Successfully parsed orders response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse orders response body. Error - {}", e);
+                        info!("Unable to parse orders response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -215,48 +232,40 @@ pub async fn get_orders_async(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_order_async(
     client: &reqwest::Client,
     token: &str,
     action: &str,
-    buy_max_cost: Option<i64>,
+    buy_max_cost: Option<Cents>,
     client_order_id: &str,
     count: i32,
-    expiration_ts: Option<i64>,
-    no_price: Option<i64>,
+    expiration_ts: Option<DateTime<Utc>>,
+    no_price: Option<Price>,
     sell_position_floor: Option<i32>,
     side: &str, // yes or no
     ticker: &str,
     type_: &str, // limit or market
-    yes_price: Option<i64>,
+    yes_price: Option<Price>,
 ) -> Result<CreateOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
-    println!("Creating order for user at endpoint '{}'", CREATE_ORDER_URL);
-    let url = reqwest::Url::parse(CREATE_ORDER_URL)?;
-    let mut body: HashMap<String, String> = HashMap::new();
-    body.insert("action".to_string(), action.to_string());
-    body.insert("client_order_id".to_string(), client_order_id.to_string());
-    body.insert("count".to_string(), count.to_string());
-    body.insert("side".to_string(), side.to_string());
-    body.insert("ticker".to_string(), ticker.to_string());
-    body.insert("type".to_string(), type_.to_string());
-    if let Some(buy_max_cost) = buy_max_cost {
-        body.insert("buy_max_cost".to_string(), buy_max_cost.to_string());
-    }
-    if let Some(expiration_ts) = expiration_ts {
-        body.insert("expiration_ts".to_string(), expiration_ts.to_string());
-    }
-    if let Some(no_price) = no_price {
-        body.insert("no_price".to_string(), no_price.to_string());
-    }
-    if let Some(sell_position_floor) = sell_position_floor {
-        body.insert(
-            "sell_position_floor".to_string(),
-            sell_position_floor.to_string(),
-        );
-    }
-    if let Some(yes_price) = yes_price {
-        body.insert("yes_price".to_string(), yes_price.to_string());
-    }
+    info!(
+        "Creating order for user at endpoint '{}'",
+        CREATE_ORDER_PATH
+    );
+    let url = environment::api_url(CREATE_ORDER_PATH)?;
+    let body = CreateOrderRequest {
+        action: action.to_string(),
+        buy_max_cost,
+        client_order_id: client_order_id.to_string(),
+        count,
+        expiration_ts,
+        no_price,
+        sell_position_floor,
+        side: side.to_string(),
+        ticker: ticker.to_string(),
+        type_: type_.to_string(),
+        yes_price,
+    };
     match client
         .post(url)
         .bearer_auth(token)
@@ -272,11 +281,11 @@ pub async fn create_order_async(
             if result.status().is_success() {
                 match result.json::<CreateOrderResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed create order response body: {:?}", body);
+                        info!("Successfully parsed create order response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse create order response body. Error - {}", e);
+                        info!("Unable to parse create order response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -303,8 +312,8 @@ pub async fn get_order(
     token: &str,
     order_id: &str,
 ) -> Result<GetOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
-    println!("Fetching order for user at endpoint '{}'", GET_ORDER_URL);
-    let url = reqwest::Url::parse(GET_ORDER_URL)?;
+    info!("Fetching order for user at endpoint '{}'", GET_ORDER_PATH);
+    let url = environment::api_url(GET_ORDER_PATH)?;
     let url = url.join(order_id)?;
     match client
         .get(url)
@@ -320,11 +329,11 @@ pub async fn get_order(
             if result.status().is_success() {
                 match result.json::<GetOrderResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed order response body: {:?}", body);
+                        info!("Successfully parsed order response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse order response body. Error - {}", e);
+                        info!("Unable to parse order response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -351,11 +360,11 @@ pub async fn cancel_order_async(
     token: &str,
     order_id: &str,
 ) -> Result<CancelOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
-    println!(
+    info!(
         "Cancelling order for user at endpoint '{}'",
-        CANCEL_ORDER_URL
+        CANCEL_ORDER_PATH
     );
-    let url = reqwest::Url::parse(CANCEL_ORDER_URL)?;
+    let url = environment::api_url(CANCEL_ORDER_PATH)?;
     let url = url.join(order_id)?;
     match client
         .delete(url)
@@ -371,11 +380,11 @@ pub async fn cancel_order_async(
             if result.status().is_success() {
                 match result.json::<CancelOrderResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed cancel order response body: {:?}", body);
+                        info!("Successfully parsed cancel order response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse cancel order response body. Error - {}", e);
+                        info!("Unable to parse cancel order response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -397,6 +406,534 @@ pub async fn cancel_order_async(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+pub async fn amend_order_async(
+    client: &reqwest::Client,
+    token: &str,
+    order_id: &str,
+    action: &str,
+    client_order_id: &str,
+    count: i32,
+    no_price: Option<Price>,
+    side: &str, // yes or no
+    ticker: &str,
+    updated_client_order_id: &str,
+    yes_price: Option<Price>,
+) -> Result<AmendOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+    info!("Amending order for user at endpoint '{}'", GET_ORDER_PATH);
+    let url = environment::api_url(GET_ORDER_PATH)?;
+    let url = url.join(&format!("{}{}", order_id, AMEND_ORDER_SUFFIX))?;
+    let body = AmendOrderRequest {
+        action: action.to_string(),
+        client_order_id: client_order_id.to_string(),
+        count,
+        no_price,
+        side: side.to_string(),
+        ticker: ticker.to_string(),
+        updated_client_order_id: updated_client_order_id.to_string(),
+        yes_price,
+    };
+    match client
+        .post(url)
+        .bearer_auth(token)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .json(&body)
+        .send()
+        .await
+    {
+        Ok(result) => {
+            if result.status().is_success() {
+                match result.json::<AmendOrderResponse>().await {
+                    Ok(body) => {
+                        info!("Successfully parsed amend order response body: {:?}", body);
+                        Ok(body)
+                    }
+                    Err(e) => {
+                        info!("Unable to parse amend order response body. Error - {}", e);
+                        Err(Box::new(e))
+                    }
+                }
+            } else {
+                let error_message = format!(
+                    "amend_order unsuccessful with status {} and message {}",
+                    result.status().as_str(),
+                    result.text().await?
+                );
+                eprintln!("{}", error_message);
+                Err(Box::from(error_message))
+            }
+        }
+        Err(e) => {
+            let error_message = format!("Error from amend_order: {}", e);
+            eprintln!("{}", error_message);
+            Err(Box::from(error_message))
+        }
+    }
+}
+
+// Exactly one of reduce_by or reduce_to must be set.
+pub async fn decrease_order_async(
+    client: &reqwest::Client,
+    token: &str,
+    order_id: &str,
+    reduce_by: Option<i32>,
+    reduce_to: Option<i32>,
+) -> Result<DecreaseOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+    info!("Decreasing order for user at endpoint '{}'", GET_ORDER_PATH);
+    if reduce_by.is_some() == reduce_to.is_some() {
+        return Err(Box::from(
+            "decrease_order requires exactly one of reduce_by or reduce_to",
+        ));
+    }
+    let body = DecreaseOrderRequest {
+        reduce_by,
+        reduce_to,
+    };
+    let url = environment::api_url(GET_ORDER_PATH)?;
+    let url = url.join(&format!("{}{}", order_id, DECREASE_ORDER_SUFFIX))?;
+    match client
+        .post(url)
+        .bearer_auth(token)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .json(&body)
+        .send()
+        .await
+    {
+        Ok(result) => {
+            if result.status().is_success() {
+                match result.json::<DecreaseOrderResponse>().await {
+                    Ok(body) => {
+                        info!(
+                            "Successfully parsed decrease order response body: {:?}",
+                            body
+                        );
+                        Ok(body)
+                    }
+                    Err(e) => {
+                        info!(
+                            "Unable to parse decrease order response body. Error - {}",
+                            e
+                        );
+                        Err(Box::new(e))
+                    }
+                }
+            } else {
+                let error_message = format!(
+                    "decrease_order unsuccessful with status {} and message {}",
+                    result.status().as_str(),
+                    result.text().await?
+                );
+                eprintln!("{}", error_message);
+                Err(Box::from(error_message))
+            }
+        }
+        Err(e) => {
+            let error_message = format!("Error from decrease_order: {}", e);
+            eprintln!("{}", error_message);
+            Err(Box::from(error_message))
+        }
+    }
+}
+
+// The exchange accepts at most this many orders in a single batched request.
+const MAX_BATCH_SIZE: usize = 20;
+
+// Creates orders MAX_BATCH_SIZE at a time. A chunk that fails outright is reported as one
+// error result per order in it, so the results of earlier chunks are never lost.
+pub async fn batch_create_orders_async(
+    client: &reqwest::Client,
+    token: &str,
+    orders: &[CreateOrderRequest],
+    max_concurrency: usize,
+) -> Result<BatchCreateOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+    info!(
+        "Creating {} orders for user at endpoint '{}'",
+        orders.len(),
+        BATCH_ORDERS_PATH
+    );
+    let mut results = Vec::with_capacity(orders.len());
+    for chunk in orders.chunks(MAX_BATCH_SIZE) {
+        match batch_create_chunk_async(client, token, chunk).await {
+            Ok(Some(chunk_results)) => results.extend(chunk_results),
+            Ok(None) => results.extend(
+                create_orders_individually_async(client, token, chunk, max_concurrency).await,
+            ),
+            Err(e) => results.extend(chunk.iter().map(|order| BatchCreateOrderResult {
+                client_order_id: Some(order.client_order_id.clone()),
+                order: None,
+                error: Some(BatchOrderError {
+                    code: "request_failed".to_string(),
+                    message: e.to_string(),
+                }),
+            })),
+        }
+    }
+    Ok(BatchCreateOrdersResponse { orders: results })
+}
+
+// Creates up to MAX_BATCH_SIZE orders in one request. Returns None when the exchange
+// doesn't support batch orders, so the caller can fall back to single requests.
+async fn batch_create_chunk_async(
+    client: &reqwest::Client,
+    token: &str,
+    orders: &[CreateOrderRequest],
+) -> Result<Option<Vec<BatchCreateOrderResult>>, Box<dyn std::error::Error + Send + Sync>> {
+    let url = environment::api_url(BATCH_ORDERS_PATH)?;
+    let body = BatchCreateOrdersRequest {
+        orders: orders.to_vec(),
+    };
+    match client
+        .post(url)
+        .bearer_auth(token)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .json(&body)
+        .send()
+        .await
+    {
+        Ok(result) => {
+            if result.status().is_success() {
+                match result.json::<BatchCreateOrdersResponse>().await {
+                    Ok(body) => {
+                        info!(
+                            "Successfully parsed batch create orders response body: {:?}",
+                            body
+                        );
+                        Ok(Some(body.orders))
+                    }
+                    Err(e) => {
+                        info!(
+                            "Unable to parse batch create orders response body. Error - {}",
+                            e
+                        );
+                        Err(Box::new(e))
+                    }
+                }
+            } else if is_batch_endpoint_unavailable(result.status()) {
+                info!(
+                    "Batch create orders unavailable with status {}. Falling back to individual requests",
+                    result.status().as_str()
+                );
+                Ok(None)
+            } else {
+                let error_message = format!(
+                    "batch_create_orders unsuccessful with status {} and message {}",
+                    result.status().as_str(),
+                    result.text().await?
+                );
+                eprintln!("{}", error_message);
+                Err(Box::from(error_message))
+            }
+        }
+        Err(e) => {
+            let error_message = format!("Error from batch_create_orders: {}", e);
+            eprintln!("{}", error_message);
+            Err(Box::from(error_message))
+        }
+    }
+}
+
+// Cancels orders MAX_BATCH_SIZE at a time, reporting a failed chunk the same way as
+// batch_create_orders_async.
+pub async fn batch_cancel_orders_async(
+    client: &reqwest::Client,
+    token: &str,
+    order_ids: &[String],
+    max_concurrency: usize,
+) -> Result<BatchCancelOrdersResponse, Box<dyn std::error::Error + Send + Sync>> {
+    info!(
+        "Cancelling {} orders for user at endpoint '{}'",
+        order_ids.len(),
+        BATCH_ORDERS_PATH
+    );
+    let mut results = Vec::with_capacity(order_ids.len());
+    for chunk in order_ids.chunks(MAX_BATCH_SIZE) {
+        match batch_cancel_chunk_async(client, token, chunk).await {
+            Ok(Some(chunk_results)) => results.extend(chunk_results),
+            Ok(None) => results.extend(
+                cancel_orders_individually_async(client, token, chunk, max_concurrency, None).await,
+            ),
+            Err(e) => results.extend(chunk.iter().map(|order_id| BatchCancelOrderResult {
+                order_id: order_id.clone(),
+                order: None,
+                reduced_by: 0,
+                error: Some(BatchOrderError {
+                    code: "request_failed".to_string(),
+                    message: e.to_string(),
+                }),
+            })),
+        }
+    }
+    Ok(BatchCancelOrdersResponse { orders: results })
+}
+
+// Cancels up to MAX_BATCH_SIZE orders in one request. Returns None when the exchange
+// doesn't support batch orders, so the caller can fall back to single cancels.
+async fn batch_cancel_chunk_async(
+    client: &reqwest::Client,
+    token: &str,
+    order_ids: &[String],
+) -> Result<Option<Vec<BatchCancelOrderResult>>, Box<dyn std::error::Error + Send + Sync>> {
+    let url = environment::api_url(BATCH_ORDERS_PATH)?;
+    let body = BatchCancelOrdersRequest {
+        ids: order_ids.to_vec(),
+    };
+    match client
+        .delete(url)
+        .bearer_auth(token)
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            "application/json; charset=utf-8",
+        )
+        .json(&body)
+        .send()
+        .await
+    {
+        Ok(result) => {
+            if result.status().is_success() {
+                match result.json::<BatchCancelOrdersResponse>().await {
+                    Ok(body) => {
+                        info!(
+                            "Successfully parsed batch cancel orders response body: {:?}",
+                            body
+                        );
+                        Ok(Some(body.orders))
+                    }
+                    Err(e) => {
+                        info!(
+                            "Unable to parse batch cancel orders response body. Error - {}",
+                            e
+                        );
+                        Err(Box::new(e))
+                    }
+                }
+            } else if is_batch_endpoint_unavailable(result.status()) {
+                info!(
+                    "Batch cancel orders unavailable with status {}. Falling back to individual requests",
+                    result.status().as_str()
+                );
+                Ok(None)
+            } else {
+                let error_message = format!(
+                    "batch_cancel_orders unsuccessful with status {} and message {}",
+                    result.status().as_str(),
+                    result.text().await?
+                );
+                eprintln!("{}", error_message);
+                Err(Box::from(error_message))
+            }
+        }
+        Err(e) => {
+            let error_message = format!("Error from batch_cancel_orders: {}", e);
+            eprintln!("{}", error_message);
+            Err(Box::from(error_message))
+        }
+    }
+}
+
+fn is_batch_endpoint_unavailable(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED
+}
+
+async fn create_orders_individually_async(
+    client: &reqwest::Client,
+    token: &str,
+    orders: &[CreateOrderRequest],
+    max_concurrency: usize,
+) -> Vec<BatchCreateOrderResult> {
+    // Items are owned so the resulting future is Send for any caller lifetime.
+    stream::iter(orders.to_vec())
+        .map(|order| async move {
+            match create_order_async(
+                client,
+                token,
+                &order.action,
+                order.buy_max_cost,
+                &order.client_order_id,
+                order.count,
+                order.expiration_ts,
+                order.no_price,
+                order.sell_position_floor,
+                &order.side,
+                &order.ticker,
+                &order.type_,
+                order.yes_price,
+            )
+            .await
+            {
+                Ok(response) => BatchCreateOrderResult {
+                    client_order_id: Some(order.client_order_id.clone()),
+                    order: Some(response.order),
+                    error: None,
+                },
+                Err(e) => BatchCreateOrderResult {
+                    client_order_id: Some(order.client_order_id.clone()),
+                    order: None,
+                    error: Some(BatchOrderError {
+                        code: "request_failed".to_string(),
+                        message: e.to_string(),
+                    }),
+                },
+            }
+        })
+        .buffered(max_concurrency.max(1))
+        .collect()
+        .await
+}
+
+async fn cancel_orders_individually_async(
+    client: &reqwest::Client,
+    token: &str,
+    order_ids: &[String],
+    max_concurrency: usize,
+    limiter: Option<&Mutex<Interval>>,
+) -> Vec<BatchCancelOrderResult> {
+    stream::iter(order_ids.to_vec())
+        .map(|order_id| async move {
+            if let Some(limiter) = limiter {
+                limiter.lock().await.tick().await;
+            }
+            match cancel_order_async(client, token, &order_id).await {
+                Ok(response) => BatchCancelOrderResult {
+                    order_id,
+                    order: Some(response.order),
+                    reduced_by: response.reduced_by,
+                    error: None,
+                },
+                Err(e) => BatchCancelOrderResult {
+                    order_id,
+                    order: None,
+                    reduced_by: 0,
+                    error: Some(BatchOrderError {
+                        code: "request_failed".to_string(),
+                        message: e.to_string(),
+                    }),
+                },
+            }
+        })
+        .buffered(max_concurrency.max(1))
+        .collect()
+        .await
+}
+
+#[derive(Clone, Debug)]
+pub enum CancelScope {
+    Ticker(String),
+    Event(String),
+    All,
+}
+
+#[derive(Clone, Debug)]
+pub struct FailedCancel {
+    pub order_id: String,
+    pub error: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelAllReport {
+    pub cancelled: Vec<Order>,
+    pub failed: Vec<FailedCancel>,
+}
+
+const CANCEL_ALL_PAGE_SIZE: i32 = 100;
+
+// Cancels every resting order within the scope. Orders are collected up front so that
+// cancellations don't shift the pagination cursor, then cancelled in batches, with no more
+// than requests_per_second requests started. If the batch endpoint is unavailable or a
+// batch fails, orders are cancelled one at a time with at most max_concurrency requests in
+// flight.
+pub async fn cancel_all_async(
+    client: &reqwest::Client,
+    token: &str,
+    scope: CancelScope,
+    max_concurrency: usize,
+    requests_per_second: u32,
+) -> Result<CancelAllReport, Box<dyn std::error::Error + Send + Sync>> {
+    let (ticker, event_ticker) = match &scope {
+        CancelScope::Ticker(ticker) => (Some(ticker.as_str()), None),
+        CancelScope::Event(event_ticker) => (None, Some(event_ticker.as_str())),
+        CancelScope::All => (None, None),
+    };
+    info!("Cancelling all resting orders with scope {:?}", scope);
+
+    let mut order_ids: Vec<String> = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let page = get_orders_async(
+            client,
+            token,
+            ticker,
+            event_ticker,
+            None,
+            None,
+            Some("resting"),
+            cursor.as_deref(),
+            Some(CANCEL_ALL_PAGE_SIZE),
+        )
+        .await?;
+        order_ids.extend(page.orders.into_iter().map(|order| order.order_id));
+        if page.cursor.is_empty() {
+            break;
+        }
+        cursor = Some(page.cursor);
+    }
+    info!("Found {} resting orders to cancel", order_ids.len());
+
+    // Each batch request and each single cancel takes a tick. Delay keeps a slow request
+    // from being followed by a burst of catch-up ticks.
+    let period = Duration::from_secs(1) / requests_per_second.max(1);
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let limiter = Mutex::new(interval);
+
+    let mut results = Vec::with_capacity(order_ids.len());
+    let mut batch_available = true;
+    for chunk in order_ids.chunks(MAX_BATCH_SIZE) {
+        if batch_available {
+            limiter.lock().await.tick().await;
+            match batch_cancel_chunk_async(client, token, chunk).await {
+                Ok(Some(chunk_results)) => {
+                    results.extend(chunk_results);
+                    continue;
+                }
+                Ok(None) => batch_available = false,
+                Err(e) => eprintln!("Batch cancel failed, cancelling individually - {}", e),
+            }
+        }
+        results.extend(
+            cancel_orders_individually_async(client, token, chunk, max_concurrency, Some(&limiter))
+                .await,
+        );
+    }
+
+    let mut report = CancelAllReport::default();
+    for result in results {
+        match (result.order, result.error) {
+            (Some(order), None) => report.cancelled.push(order),
+            (_, error) => report.failed.push(FailedCancel {
+                order_id: result.order_id,
+                error: error
+                    .map(|error| format!("{} - {}", error.code, error.message))
+                    .unwrap_or_else(|| "No order returned".to_string()),
+            }),
+        }
+    }
+    info!(
+        "Cancelled {} orders, {} failed",
+        report.cancelled.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
 pub async fn get_positions_async(
     client: &reqwest::Client,
     token: &str,
@@ -406,11 +943,11 @@ pub async fn get_positions_async(
     ticker: Option<&str>,
     event_ticker: Option<&str>,
 ) -> Result<GetPositionsResponse, Box<dyn std::error::Error + Send + Sync>> {
-    println!(
+    info!(
         "Fetching positions for user at endpoint '{}'",
-        GET_POSITIONS_URL
+        GET_POSITIONS_PATH
     );
-    let url = reqwest::Url::parse(GET_POSITIONS_URL)?;
+    let url = environment::api_url(GET_POSITIONS_PATH)?;
     let mut query_params: HashMap<String, String> = HashMap::new();
     if let Some(cursor) = cursor {
         query_params.insert("cursor".to_string(), cursor.to_string());
@@ -445,11 +982,11 @@ pub async fn get_positions_async(
             if result.status().is_success() {
                 match result.json::<GetPositionsResponse>().await {
                     Ok(body) => {
-                        println!("Successfully parsed positions response body: {:?}", body);
+                        info!("Successfully parsed positions response body: {:?}", body);
                         Ok(body)
                     }
                     Err(e) => {
-                        println!("Unable to parse positions response body. Error - {}", e);
+                        info!("Unable to parse positions response body. Error - {}", e);
                         Err(Box::new(e))
                     }
                 }
@@ -477,11 +1014,11 @@ pub async fn get_settlements_async(
     limit: Option<i64>,
     cursor: Option<String>,
 ) -> Result<GetSettlementsResponse, Box<dyn std::error::Error + Send + Sync>> {
-    println!(
+    info!(
         "Fetching portfolio settlements for user at endpoint '{}'",
-        GET_SETTLEMENTS_URL
+        GET_SETTLEMENTS_PATH
     );
-    let url = reqwest::Url::parse(GET_SETTLEMENTS_URL)?;
+    let url = environment::api_url(GET_SETTLEMENTS_PATH)?;
     let mut query_params: HashMap<String, String> = HashMap::new();
     if let Some(limit) = limit {
         query_params.insert("limit".to_string(), limit.to_string());
@@ -504,14 +1041,14 @@ pub async fn get_settlements_async(
             if result.status().is_success() {
                 match result.json::<GetSettlementsResponse>().await {
                     Ok(body) => {
-                        println!(
+                        info!(
                             "Successfully parsed portfolio settlements response body: {:?}",
                             body
                         );
                         Ok(body)
                     }
                     Err(e) => {
-                        println!(
+                        info!(
                             "Unable to parse portfolio settlements response body. Error - {}",
                             e
                         );
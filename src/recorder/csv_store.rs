@@ -0,0 +1,152 @@
+use crate::backtest::MarketDataEvent;
+use crate::recorder::schema::{self, Rows, TradeRow};
+use crate::recorder::SnapshotStore;
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+// Trades can be reported a little after their created_time, so ids are kept for trades up
+// to this long before the newest one recorded.
+const TRADE_OVERLAP_SECS: i64 = 60;
+const MIN_PRUNE_LEN: usize = 1024;
+
+// Appends snapshots to markets.csv, orderbooks.csv and trades.csv in a directory. Each file
+// starts with a header row naming the columns of its schema row. Trades already in
+// trades.csv are skipped, like the primary key does for SqliteStore. Only the ids of recent
+// trades are kept in memory; anything older than the overlap window of the newest recorded
+// trade is taken to be recorded already.
+pub struct CsvStore {
+    dir: PathBuf,
+    newest_trade_time: Option<DateTime<Utc>>,
+    // Ids of the trades recorded within the overlap window, with their created_time.
+    trade_ids: HashMap<String, DateTime<Utc>>,
+}
+
+impl CsvStore {
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        std::fs::create_dir_all(dir.as_ref())?;
+        let mut store = CsvStore {
+            dir: dir.as_ref().to_path_buf(),
+            newest_trade_time: None,
+            trade_ids: HashMap::new(),
+        };
+        let path = store.path(schema::TRADES_TABLE);
+        if path.exists() {
+            // Streamed rather than read, so a long recording isn't held in memory. Pruning
+            // whenever the ids double keeps loading linear.
+            let mut reader = csv::Reader::from_path(path)?;
+            let mut prune_at = MIN_PRUNE_LEN;
+            for row in reader.deserialize::<TradeRow>() {
+                store.remember_trade(row?);
+                if store.trade_ids.len() >= prune_at {
+                    store.prune_trade_ids();
+                    prune_at = (2 * store.trade_ids.len()).max(MIN_PRUNE_LEN);
+                }
+            }
+            store.prune_trade_ids();
+        }
+        Ok(store)
+    }
+
+    // Trades created before this are skipped without looking at their id.
+    fn trade_cutoff(&self) -> Option<DateTime<Utc>> {
+        self.newest_trade_time
+            .map(|newest| newest - Duration::seconds(TRADE_OVERLAP_SECS))
+    }
+
+    fn remember_trade(&mut self, row: TradeRow) {
+        if self
+            .newest_trade_time
+            .is_none_or(|newest| row.created_time > newest)
+        {
+            self.newest_trade_time = Some(row.created_time);
+        }
+        if self
+            .trade_cutoff()
+            .is_none_or(|cutoff| row.created_time >= cutoff)
+        {
+            self.trade_ids.insert(row.trade_id, row.created_time);
+        }
+    }
+
+    fn prune_trade_ids(&mut self) {
+        if let Some(cutoff) = self.trade_cutoff() {
+            self.trade_ids
+                .retain(|_, created_time| *created_time >= cutoff);
+        }
+    }
+
+    fn path(&self, table: &str) -> PathBuf {
+        self.dir.join(format!("{}.csv", table))
+    }
+
+    fn append<T: Serialize>(
+        &self,
+        table: &str,
+        rows: &[T],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(table))?;
+        let is_new = file.metadata()?.len() == 0;
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(is_new)
+            .from_writer(file);
+        for row in rows {
+            writer.serialize(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn read<T: DeserializeOwned>(
+        &self,
+        table: &str,
+    ) -> Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>> {
+        let path = self.path(table);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut reader = csv::Reader::from_path(path)?;
+        let rows = reader.deserialize().collect::<Result<Vec<T>, _>>()?;
+        Ok(rows)
+    }
+}
+
+impl SnapshotStore for CsvStore {
+    fn write(
+        &mut self,
+        events: &[MarketDataEvent],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut rows = Rows::from_events(events)?;
+        let cutoff = self.trade_cutoff();
+        rows.trades.retain(|row| {
+            cutoff.is_none_or(|cutoff| row.created_time >= cutoff)
+                && !self.trade_ids.contains_key(&row.trade_id)
+        });
+        self.append(schema::MARKETS_TABLE, &rows.markets)?;
+        self.append(schema::ORDERBOOKS_TABLE, &rows.orderbooks)?;
+        self.append(schema::TRADES_TABLE, &rows.trades)?;
+        for row in rows.trades {
+            self.remember_trade(row);
+        }
+        self.prune_trade_ids();
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<MarketDataEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        Rows {
+            markets: self.read(schema::MARKETS_TABLE)?,
+            orderbooks: self.read(schema::ORDERBOOKS_TABLE)?,
+            trades: self.read(schema::TRADES_TABLE)?,
+        }
+        .into_events()
+    }
+}
@@ -0,0 +1,238 @@
+pub use crate::recorder::csv_store::CsvStore;
+#[cfg(feature = "sqlite")]
+pub use crate::recorder::sqlite_store::SqliteStore;
+
+use crate::backtest::MarketDataEvent;
+use crate::client::{KalshiClient, MarketData};
+use crate::market::{self, Market};
+use chrono::{DateTime, SubsecRound, Utc};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+pub mod csv_store;
+pub mod schema;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_store;
+
+const MARKETS_PAGE_SIZE: i64 = 200;
+const TRADES_PAGE_SIZE: i32 = 1000;
+
+// Where recorded snapshots go. load reads everything written so far back as events, in
+// time order, ready for backtest::Backtest::run.
+pub trait SnapshotStore {
+    fn write(
+        &mut self,
+        events: &[MarketDataEvent],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    fn load(&self) -> Result<Vec<MarketDataEvent>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[derive(Clone, Debug)]
+pub struct RecorderConfig {
+    // Every open market in these series and events is recorded.
+    pub series_tickers: Vec<String>,
+    pub event_tickers: Vec<String>,
+    pub orderbook_depth: i32,
+    pub record_trades: bool,
+    pub interval: Duration,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        RecorderConfig {
+            series_tickers: Vec::new(),
+            event_tickers: Vec::new(),
+            orderbook_depth: 10,
+            record_trades: true,
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+// Periodically snapshots markets, orderbooks and new trades into a SnapshotStore. The set
+// of markets is looked up again on every snapshot, so newly listed markets are picked up.
+pub struct Recorder<S: SnapshotStore, B: MarketData = KalshiClient> {
+    backend: B,
+    config: RecorderConfig,
+    store: S,
+    // Trades are fetched from the previous snapshot onwards, and the first snapshot takes
+    // every trade the API has. Ids already written are skipped so overlapping windows don't
+    // record a trade twice. Stores also skip trades they already hold, so restarting on an
+    // existing recording doesn't duplicate it.
+    trades_since: Option<DateTime<Utc>>,
+    // Trade ids written since trades_since, with their created_time.
+    seen_trade_ids: HashMap<String, DateTime<Utc>>,
+}
+
+impl<S: SnapshotStore, B: MarketData> Recorder<S, B> {
+    pub fn new(backend: B, config: RecorderConfig, store: S) -> Self {
+        Recorder {
+            backend,
+            config,
+            store,
+            trades_since: None,
+            seen_trade_ids: HashMap::new(),
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    // Takes a snapshot every interval until shutdown completes, e.g. with
+    // tokio::signal::ctrl_c(), then hands the store back. Failed snapshots are logged and
+    // retried on the next tick.
+    pub async fn run<F: Future>(mut self, shutdown: F) -> S {
+        info!(
+            "Starting recorder for series {:?} and events {:?}",
+            self.config.series_tickers, self.config.event_tickers
+        );
+        let mut interval = tokio::time::interval(self.config.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        tokio::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = interval.tick() => {
+                    if let Err(e) = self.snapshot_async().await {
+                        eprintln!("Error recording snapshot - {}", e);
+                    }
+                }
+            }
+        }
+        info!("Shutting down recorder");
+        self.store
+    }
+
+    // Records one snapshot of every configured market and returns the number of events
+    // written.
+    pub async fn snapshot_async(
+        &mut self,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let time = Utc::now();
+        let markets = self.get_markets_async().await?;
+        let mut events = Vec::new();
+        for market in &markets {
+            let orderbook = self
+                .backend
+                .get_market_orderbook_async(&market.ticker, self.config.orderbook_depth)
+                .await?
+                .orderbook;
+            events.push(MarketDataEvent::Orderbook {
+                time,
+                ticker: market.ticker.clone(),
+                orderbook,
+            });
+            if self.config.record_trades {
+                events.extend(
+                    self.get_new_trades_async(&market.ticker)
+                        .await?
+                        .into_iter()
+                        .map(MarketDataEvent::Trade),
+                );
+            }
+        }
+        events.extend(
+            markets
+                .into_iter()
+                .map(|market| MarketDataEvent::Market { time, market }),
+        );
+        self.store.write(&events)?;
+        for event in &events {
+            if let MarketDataEvent::Trade(trade) = event {
+                self.seen_trade_ids
+                    .insert(trade.trade_id.clone(), trade.created_time);
+            }
+        }
+        // min_ts is sent in whole seconds, so older trades can't be returned again.
+        let trades_since = time.trunc_subsecs(0);
+        self.seen_trade_ids
+            .retain(|_, created_time| *created_time >= trades_since);
+        self.trades_since = Some(time);
+        info!("Recorded {} events", events.len());
+        Ok(events.len())
+    }
+
+    async fn get_markets_async(
+        &self,
+    ) -> Result<Vec<Market>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut markets: Vec<Market> = Vec::new();
+        let filters = self
+            .config
+            .series_tickers
+            .iter()
+            .map(|series_ticker| (None, Some(series_ticker.as_str())))
+            .chain(
+                self.config
+                    .event_tickers
+                    .iter()
+                    .map(|event_ticker| (Some(event_ticker.as_str()), None)),
+            );
+        for (event_ticker, series_ticker) in filters {
+            let mut cursor: Option<String> = None;
+            loop {
+                let page = self
+                    .backend
+                    .get_markets_async(
+                        Some(MARKETS_PAGE_SIZE),
+                        cursor.as_deref(),
+                        event_ticker,
+                        series_ticker,
+                        None,
+                        None,
+                        Some("open"),
+                        None,
+                    )
+                    .await?;
+                for market in page.markets {
+                    // A market can be in both a configured series and a configured event.
+                    if !markets.iter().any(|known| known.ticker == market.ticker) {
+                        markets.push(market);
+                    }
+                }
+                if page.cursor.is_empty() {
+                    break;
+                }
+                cursor = Some(page.cursor);
+            }
+        }
+        Ok(markets)
+    }
+
+    async fn get_new_trades_async(
+        &self,
+        ticker: &str,
+    ) -> Result<Vec<market::Trade>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut trades = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = self
+                .backend
+                .get_trades_async(
+                    Some(ticker),
+                    self.trades_since,
+                    None,
+                    Some(TRADES_PAGE_SIZE),
+                    cursor.as_deref(),
+                )
+                .await?;
+            trades.extend(
+                page.trades
+                    .into_iter()
+                    .filter(|trade| !self.seen_trade_ids.contains_key(&trade.trade_id)),
+            );
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+        Ok(trades)
+    }
+}
@@ -0,0 +1,190 @@
+use crate::backtest::MarketDataEvent;
+use crate::market::{Market, Orderbook, Trade};
+use crate::money::{Cents, Price};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// The rows every store writes, one table or file per struct. Columns are only ever added
+// to the end, so older recordings keep loading. The full market and the orderbook levels
+// are kept as JSON so loading them back doesn't lose anything; the other columns are
+// copies for querying.
+
+pub const MARKETS_TABLE: &str = "markets";
+pub const ORDERBOOKS_TABLE: &str = "orderbooks";
+pub const TRADES_TABLE: &str = "trades";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MarketRow {
+    pub time: DateTime<Utc>,
+    pub ticker: String,
+    pub event_ticker: String,
+    pub yes_bid: Cents,
+    pub yes_ask: Cents,
+    pub no_bid: Cents,
+    pub no_ask: Cents,
+    pub last_price: Cents,
+    pub volume: i32,
+    pub volume_24h: i32,
+    pub open_interest: i32,
+    pub liquidity: Cents,
+    // The whole Market as JSON.
+    pub market: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrderbookRow {
+    pub time: DateTime<Utc>,
+    pub ticker: String,
+    // Levels as JSON arrays of [price, count].
+    pub yes: String,
+    pub no: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TradeRow {
+    pub trade_id: String,
+    pub ticker: String,
+    pub created_time: DateTime<Utc>,
+    pub yes_price: Price,
+    pub no_price: Price,
+    pub count: i32,
+    pub taker_side: String,
+}
+
+impl MarketRow {
+    pub fn new(
+        time: DateTime<Utc>,
+        market: &Market,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(MarketRow {
+            time,
+            ticker: market.ticker.clone(),
+            event_ticker: market.event_ticker.clone(),
+            yes_bid: market.yes_bid,
+            yes_ask: market.yes_ask,
+            no_bid: market.no_bid,
+            no_ask: market.no_ask,
+            last_price: market.last_price,
+            volume: market.volume,
+            volume_24h: market.volume_24h,
+            open_interest: market.open_interest,
+            liquidity: market.liquidity,
+            market: serde_json::to_string(market)?,
+        })
+    }
+
+    pub fn into_event(self) -> Result<MarketDataEvent, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(MarketDataEvent::Market {
+            time: self.time,
+            market: serde_json::from_str(&self.market)?,
+        })
+    }
+}
+
+impl OrderbookRow {
+    pub fn new(
+        time: DateTime<Utc>,
+        ticker: &str,
+        orderbook: &Orderbook,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(OrderbookRow {
+            time,
+            ticker: ticker.to_string(),
+            yes: serde_json::to_string(&orderbook.yes)?,
+            no: serde_json::to_string(&orderbook.no)?,
+        })
+    }
+
+    pub fn into_event(self) -> Result<MarketDataEvent, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(MarketDataEvent::Orderbook {
+            time: self.time,
+            ticker: self.ticker,
+            orderbook: Orderbook {
+                no: serde_json::from_str(&self.no)?,
+                yes: serde_json::from_str(&self.yes)?,
+            },
+        })
+    }
+}
+
+impl From<&Trade> for TradeRow {
+    fn from(trade: &Trade) -> Self {
+        TradeRow {
+            trade_id: trade.trade_id.clone(),
+            ticker: trade.ticker.clone(),
+            created_time: trade.created_time,
+            yes_price: trade.yes_price,
+            no_price: trade.no_price,
+            count: trade.count,
+            taker_side: trade.taker_side.clone(),
+        }
+    }
+}
+
+impl From<TradeRow> for Trade {
+    fn from(row: TradeRow) -> Self {
+        Trade {
+            count: row.count,
+            created_time: row.created_time,
+            no_price: row.no_price,
+            taker_side: row.taker_side,
+            ticker: row.ticker,
+            trade_id: row.trade_id,
+            yes_price: row.yes_price,
+        }
+    }
+}
+
+// Rows for a batch of snapshots, split by table. Candlesticks aren't recorded; they can
+// always be fetched again.
+#[derive(Debug, Default)]
+pub struct Rows {
+    pub markets: Vec<MarketRow>,
+    pub orderbooks: Vec<OrderbookRow>,
+    pub trades: Vec<TradeRow>,
+}
+
+impl Rows {
+    pub fn from_events(
+        events: &[MarketDataEvent],
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut rows = Rows::default();
+        for event in events {
+            match event {
+                MarketDataEvent::Market { time, market } => {
+                    rows.markets.push(MarketRow::new(*time, market)?)
+                }
+                MarketDataEvent::Orderbook {
+                    time,
+                    ticker,
+                    orderbook,
+                } => rows
+                    .orderbooks
+                    .push(OrderbookRow::new(*time, ticker, orderbook)?),
+                MarketDataEvent::Trade(trade) => rows.trades.push(trade.into()),
+                MarketDataEvent::Candlestick { .. } => {}
+            }
+        }
+        Ok(rows)
+    }
+
+    // Every row as an event, in time order.
+    pub fn into_events(
+        self,
+    ) -> Result<Vec<MarketDataEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut events = Vec::new();
+        for row in self.markets {
+            events.push(row.into_event()?);
+        }
+        for row in self.orderbooks {
+            events.push(row.into_event()?);
+        }
+        events.extend(
+            self.trades
+                .into_iter()
+                .map(|row| MarketDataEvent::Trade(row.into())),
+        );
+        events.sort_by_key(MarketDataEvent::time);
+        Ok(events)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::backtest::MarketDataEvent;
+use crate::money::{Cents, Price};
+use crate::recorder::schema::{MarketRow, OrderbookRow, Rows, TradeRow};
+use crate::recorder::SnapshotStore;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+// Same columns as the CSV files. Times are stored as RFC 3339 text with a fixed number of
+// digits so they sort correctly as strings.
+const CREATE_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS markets (
+    time TEXT NOT NULL,
+    ticker TEXT NOT NULL,
+    event_ticker TEXT NOT NULL,
+    yes_bid INTEGER NOT NULL,
+    yes_ask INTEGER NOT NULL,
+    no_bid INTEGER NOT NULL,
+    no_ask INTEGER NOT NULL,
+    last_price INTEGER NOT NULL,
+    volume INTEGER NOT NULL,
+    volume_24h INTEGER NOT NULL,
+    open_interest INTEGER NOT NULL,
+    liquidity INTEGER NOT NULL,
+    market TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS markets_ticker_time ON markets (ticker, time);
+CREATE TABLE IF NOT EXISTS orderbooks (
+    time TEXT NOT NULL,
+    ticker TEXT NOT NULL,
+    yes TEXT NOT NULL,
+    no TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS orderbooks_ticker_time ON orderbooks (ticker, time);
+CREATE TABLE IF NOT EXISTS trades (
+    trade_id TEXT PRIMARY KEY,
+    ticker TEXT NOT NULL,
+    created_time TEXT NOT NULL,
+    yes_price INTEGER NOT NULL,
+    no_price INTEGER NOT NULL,
+    count INTEGER NOT NULL,
+    taker_side TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS trades_ticker_time ON trades (ticker, created_time);
+";
+
+// Writes snapshots to a SQLite database. Trades already in the database are skipped.
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(CREATE_TABLES)?;
+        Ok(SqliteStore { connection })
+    }
+
+    // A database that only lives as long as the store, mostly for tests.
+    pub fn open_in_memory() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let connection = Connection::open_in_memory()?;
+        connection.execute_batch(CREATE_TABLES)?;
+        Ok(SqliteStore { connection })
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+fn parse_time(time: &str) -> Result<DateTime<Utc>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(DateTime::parse_from_rfc3339(time)?.with_timezone(&Utc))
+}
+
+impl SnapshotStore for SqliteStore {
+    fn write(
+        &mut self,
+        events: &[MarketDataEvent],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let rows = Rows::from_events(events)?;
+        let transaction = self.connection.transaction()?;
+        {
+            let mut insert_market = transaction.prepare(
+                "INSERT INTO markets (time, ticker, event_ticker, yes_bid, yes_ask, no_bid,
+                    no_ask, last_price, volume, volume_24h, open_interest, liquidity, market)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            )?;
+            for row in &rows.markets {
+                insert_market.execute(params![
+                    format_time(row.time),
+                    row.ticker,
+                    row.event_ticker,
+                    row.yes_bid.value(),
+                    row.yes_ask.value(),
+                    row.no_bid.value(),
+                    row.no_ask.value(),
+                    row.last_price.value(),
+                    row.volume,
+                    row.volume_24h,
+                    row.open_interest,
+                    row.liquidity.value(),
+                    row.market,
+                ])?;
+            }
+            let mut insert_orderbook = transaction.prepare(
+                "INSERT INTO orderbooks (time, ticker, yes, no) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for row in &rows.orderbooks {
+                insert_orderbook.execute(params![
+                    format_time(row.time),
+                    row.ticker,
+                    row.yes,
+                    row.no,
+                ])?;
+            }
+            let mut insert_trade = transaction.prepare(
+                "INSERT OR IGNORE INTO trades (trade_id, ticker, created_time, yes_price,
+                    no_price, count, taker_side)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for row in &rows.trades {
+                insert_trade.execute(params![
+                    row.trade_id,
+                    row.ticker,
+                    format_time(row.created_time),
+                    row.yes_price.value(),
+                    row.no_price.value(),
+                    row.count,
+                    row.taker_side,
+                ])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<MarketDataEvent>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut rows = Rows::default();
+
+        let mut statement = self.connection.prepare(
+            "SELECT time, ticker, event_ticker, yes_bid, yes_ask, no_bid, no_ask, last_price,
+                volume, volume_24h, open_interest, liquidity, market
+             FROM markets ORDER BY time",
+        )?;
+        let mut query = statement.query([])?;
+        while let Some(row) = query.next()? {
+            rows.markets.push(MarketRow {
+                time: parse_time(&row.get::<_, String>(0)?)?,
+                ticker: row.get(1)?,
+                event_ticker: row.get(2)?,
+                yes_bid: Cents(row.get(3)?),
+                yes_ask: Cents(row.get(4)?),
+                no_bid: Cents(row.get(5)?),
+                no_ask: Cents(row.get(6)?),
+                last_price: Cents(row.get(7)?),
+                volume: row.get(8)?,
+                volume_24h: row.get(9)?,
+                open_interest: row.get(10)?,
+                liquidity: Cents(row.get(11)?),
+                market: row.get(12)?,
+            });
+        }
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT time, ticker, yes, no FROM orderbooks ORDER BY time")?;
+        let mut query = statement.query([])?;
+        while let Some(row) = query.next()? {
+            rows.orderbooks.push(OrderbookRow {
+                time: parse_time(&row.get::<_, String>(0)?)?,
+                ticker: row.get(1)?,
+                yes: row.get(2)?,
+                no: row.get(3)?,
+            });
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT trade_id, ticker, created_time, yes_price, no_price, count, taker_side
+             FROM trades ORDER BY created_time",
+        )?;
+        let mut query = statement.query([])?;
+        while let Some(row) = query.next()? {
+            rows.trades.push(TradeRow {
+                trade_id: row.get(0)?,
+                ticker: row.get(1)?,
+                created_time: parse_time(&row.get::<_, String>(2)?)?,
+                yes_price: Price::try_from(row.get::<_, i64>(3)?)?,
+                no_price: Price::try_from(row.get::<_, i64>(4)?)?,
+                count: row.get(5)?,
+                taker_side: row.get(6)?,
+            });
+        }
+
+        rows.into_events()
+    }
+}
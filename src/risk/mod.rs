@@ -0,0 +1,351 @@
+use crate::bids::Bids;
+use crate::client::{MarketData, Portfolio, Trading};
+use crate::money::{Cents, Price};
+use crate::portfolio::{CreateOrderRequest, CreateOrderResponse, EventPosition, MarketPosition};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Debug)]
+pub struct RiskLimits {
+    // Maximum contracts in a single order.
+    pub max_order_size: i32,
+    // Maximum absolute position in a single market, in contracts.
+    pub max_position_per_market: i64,
+    // Maximum sum of absolute positions across the markets in an event, in contracts.
+    pub max_position_per_event: i64,
+    // Maximum total exposure after the order, as a fraction of the account balance.
+    pub max_exposure_fraction: f64,
+    // How far outside the current bid/ask an order may be priced, in cents.
+    pub price_band: i64,
+    // How far from the last traded price an order may be priced, in cents.
+    pub max_last_price_deviation: i64,
+}
+
+impl Default for RiskLimits {
+    fn default() -> Self {
+        RiskLimits {
+            max_order_size: 100,
+            max_position_per_market: 500,
+            max_position_per_event: 1000,
+            max_exposure_fraction: 0.5,
+            price_band: 5,
+            max_last_price_deviation: 20,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RiskViolation {
+    OrderSizeExceeded {
+        count: i32,
+        limit: i32,
+    },
+    MarketPositionExceeded {
+        ticker: String,
+        resulting_position: i64,
+        limit: i64,
+    },
+    EventPositionExceeded {
+        event_ticker: String,
+        resulting_position: i64,
+        limit: i64,
+    },
+    ExposureExceeded {
+        resulting_exposure: Cents,
+        limit: Cents,
+    },
+    InsufficientBalance {
+        notional: Cents,
+        balance: Cents,
+    },
+    OutsidePriceBand {
+        price: Price,
+        bid: Cents,
+        ask: Cents,
+        band: i64,
+    },
+    FatFinger {
+        price: Price,
+        last_price: Cents,
+        max_deviation: i64,
+    },
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskViolation::OrderSizeExceeded { count, limit } => write!(
+                f,
+                "Order size {} exceeds the maximum of {} contracts",
+                count, limit
+            ),
+            RiskViolation::MarketPositionExceeded {
+                ticker,
+                resulting_position,
+                limit,
+            } => write!(
+                f,
+                "Resulting position {} in market '{}' exceeds the maximum of {} contracts",
+                resulting_position, ticker, limit
+            ),
+            RiskViolation::EventPositionExceeded {
+                event_ticker,
+                resulting_position,
+                limit,
+            } => write!(
+                f,
+                "Resulting position {} in event '{}' exceeds the maximum of {} contracts",
+                resulting_position, event_ticker, limit
+            ),
+            RiskViolation::ExposureExceeded {
+                resulting_exposure,
+                limit,
+            } => write!(
+                f,
+                "Resulting exposure {} exceeds the maximum of {}",
+                resulting_exposure, limit
+            ),
+            RiskViolation::InsufficientBalance { notional, balance } => write!(
+                f,
+                "Order notional {} exceeds the balance of {}",
+                notional, balance
+            ),
+            RiskViolation::OutsidePriceBand {
+                price,
+                bid,
+                ask,
+                band,
+            } => write!(
+                f,
+                "Price {} is more than {} cents outside the market {} bid / {} ask",
+                price, band, bid, ask
+            ),
+            RiskViolation::FatFinger {
+                price,
+                last_price,
+                max_deviation,
+            } => write!(
+                f,
+                "Price {} is more than {} cents from the last traded price {}",
+                price, max_deviation, last_price
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RiskViolation {}
+
+// Everything the checks need to know about the market and account at the time of the order.
+#[derive(Debug)]
+pub struct RiskContext {
+    pub ticker: String,
+    pub event_ticker: String,
+    pub bids: Bids,
+    pub last_price: Cents,
+    pub balance: Cents,
+    // Current position in the order's market.
+    pub market_position: i64,
+    // Sum of absolute positions across the markets in the order's event.
+    pub event_position: i64,
+    // Total exposure across all events.
+    pub exposure: Cents,
+}
+
+impl RiskContext {
+    pub async fn fetch_async<B: MarketData + Portfolio + ?Sized>(
+        backend: &B,
+        ticker: &str,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let market = backend.get_market_async(ticker).await?.market;
+        let balance = backend.get_balance_async().await?.balance;
+
+        // Exposure is summed over every event. The event's own markets come from asking the
+        // exchange for the positions in that event.
+        let (event_positions, _) = fetch_positions_async(backend, None).await?;
+        let exposure = event_positions
+            .iter()
+            .map(|position| position.event_exposure)
+            .sum();
+        let (_, market_positions) =
+            fetch_positions_async(backend, Some(&market.event_ticker)).await?;
+        let market_position = market_positions
+            .iter()
+            .find(|position| position.ticker == market.ticker)
+            .map_or(0, |position| position.position);
+        let event_position = market_positions
+            .iter()
+            .map(|position| position.position.abs())
+            .sum();
+
+        Ok(RiskContext {
+            ticker: market.ticker.clone(),
+            event_ticker: market.event_ticker.clone(),
+            last_price: market.last_price,
+            bids: market.into(),
+            balance,
+            market_position,
+            event_position,
+            exposure,
+        })
+    }
+}
+
+// Pages through positions, optionally limited to one event. Event positions are keyed by
+// event ticker so an event repeated on several pages is only counted once.
+async fn fetch_positions_async<B: Portfolio + ?Sized>(
+    backend: &B,
+    event_ticker: Option<&str>,
+) -> Result<(Vec<EventPosition>, Vec<MarketPosition>), Box<dyn std::error::Error + Send + Sync>> {
+    let mut event_positions: HashMap<String, EventPosition> = HashMap::new();
+    let mut market_positions = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let page = backend
+            .get_positions_async(cursor.as_deref(), Some(100), None, None, event_ticker)
+            .await?;
+        for position in page.event_positions {
+            event_positions.insert(position.event_ticker.clone(), position);
+        }
+        market_positions.extend(page.market_positions);
+        if page.cursor.is_empty() {
+            break;
+        }
+        cursor = Some(page.cursor);
+    }
+    Ok((event_positions.into_values().collect(), market_positions))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RiskChecker {
+    pub limits: RiskLimits,
+}
+
+impl RiskChecker {
+    pub fn new(limits: RiskLimits) -> Self {
+        RiskChecker { limits }
+    }
+
+    pub fn check(
+        &self,
+        order: &CreateOrderRequest,
+        context: &RiskContext,
+    ) -> Result<(), RiskViolation> {
+        let limits = &self.limits;
+        if order.count > limits.max_order_size {
+            return Err(RiskViolation::OrderSizeExceeded {
+                count: order.count,
+                limit: limits.max_order_size,
+            });
+        }
+
+        let delta = yes_equivalent_delta(order);
+        let resulting_position = context.market_position + delta;
+        if resulting_position.abs() > context.market_position.abs() {
+            if resulting_position.abs() > limits.max_position_per_market {
+                return Err(RiskViolation::MarketPositionExceeded {
+                    ticker: order.ticker.clone(),
+                    resulting_position,
+                    limit: limits.max_position_per_market,
+                });
+            }
+            let resulting_event_position =
+                context.event_position - context.market_position.abs() + resulting_position.abs();
+            if resulting_event_position > limits.max_position_per_event {
+                return Err(RiskViolation::EventPositionExceeded {
+                    event_ticker: context.event_ticker.clone(),
+                    resulting_position: resulting_event_position,
+                    limit: limits.max_position_per_event,
+                });
+            }
+        }
+
+        let price = order_price(order);
+        if order.action == "buy" {
+            // Market orders without a limit price are assumed to pay up to 99 cents.
+            let notional = price.unwrap_or(Price::MAX).cents() * order.count as i64;
+            if notional > context.balance {
+                return Err(RiskViolation::InsufficientBalance {
+                    notional,
+                    balance: context.balance,
+                });
+            }
+            let resulting_exposure = context.exposure + notional;
+            let limit =
+                Cents((context.balance.value() as f64 * limits.max_exposure_fraction) as i64);
+            if resulting_exposure > limit {
+                return Err(RiskViolation::ExposureExceeded {
+                    resulting_exposure,
+                    limit,
+                });
+            }
+        }
+
+        if let Some(price) = price {
+            let spread = if order.side == "yes" {
+                &context.bids.yes
+            } else {
+                &context.bids.no
+            };
+            let band = Cents(limits.price_band);
+            if price.cents() < spread.bid - band || price.cents() > spread.ask + band {
+                return Err(RiskViolation::OutsidePriceBand {
+                    price,
+                    bid: spread.bid,
+                    ask: spread.ask,
+                    band: limits.price_band,
+                });
+            }
+
+            // Markets that have never traded have nothing to compare against.
+            if context.last_price > Cents::ZERO {
+                let last_price = if order.side == "yes" {
+                    context.last_price
+                } else {
+                    Cents(100) - context.last_price
+                };
+                if (price.cents() - last_price).abs() > Cents(limits.max_last_price_deviation) {
+                    return Err(RiskViolation::FatFinger {
+                        price,
+                        last_price,
+                        max_deviation: limits.max_last_price_deviation,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Runs the risk checks against freshly fetched market and account state and only submits
+    // the order if they pass. Violations are returned as a boxed RiskViolation.
+    pub async fn create_order_async<B: MarketData + Trading + Portfolio + ?Sized>(
+        &self,
+        backend: &B,
+        order: &CreateOrderRequest,
+    ) -> Result<CreateOrderResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let context = RiskContext::fetch_async(backend, &order.ticker).await?;
+        if let Err(violation) = self.check(order, &context) {
+            eprintln!("Order rejected by risk checks - {}", violation);
+            return Err(Box::new(violation));
+        }
+        backend.create_order_async(order).await
+    }
+}
+
+// Change in position, in yes contracts, if the order fills completely.
+fn yes_equivalent_delta(order: &CreateOrderRequest) -> i64 {
+    let buys_yes = (order.side == "yes") == (order.action == "buy");
+    if buys_yes {
+        order.count as i64
+    } else {
+        -(order.count as i64)
+    }
+}
+
+fn order_price(order: &CreateOrderRequest) -> Option<Price> {
+    if order.side == "yes" {
+        order.yes_price.or(order.no_price.map(Price::complement))
+    } else {
+        order.no_price.or(order.yes_price.map(Price::complement))
+    }
+}
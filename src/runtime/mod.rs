@@ -0,0 +1,355 @@
+use crate::client::{Backend, KalshiClient};
+use crate::exchange::{ExchangeStatus, ExchangeStatusChange};
+use crate::money::Cents;
+use crate::order_manager::OrderManager;
+use crate::portfolio::CreateOrderRequest;
+use crate::risk::{RiskChecker, RiskContext, RiskLimits};
+use crate::strategy::{OrderIntent, Strategy, StrategyContext};
+use chrono::{DateTime, SubsecRound, Utc};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+const SHUTDOWN_CANCEL_CONCURRENCY: usize = 4;
+const FILLS_PAGE_SIZE: i32 = 100;
+// Fills can be reported a little after their created_time, so each poll asks for fills from
+// slightly before the newest one already seen.
+const FILLS_OVERLAP_SECS: i64 = 30;
+
+#[derive(Clone, Debug)]
+pub struct RuntimeConfig {
+    // Markets whose data is delivered to the strategy.
+    pub tickers: Vec<String>,
+    pub orderbook_depth: i32,
+    // How often markets, orderbooks, fills, orders and the account are polled.
+    pub poll_interval: Duration,
+    pub timer_interval: Duration,
+    pub exchange_status_interval: Duration,
+    pub risk_limits: RiskLimits,
+    // Cancel resting orders as soon as the exchange reports trading has halted.
+    pub cancel_on_trading_halt: bool,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        RuntimeConfig {
+            tickers: Vec::new(),
+            orderbook_depth: 10,
+            poll_interval: Duration::from_secs(2),
+            timer_interval: Duration::from_secs(10),
+            exchange_status_interval: Duration::from_secs(30),
+            risk_limits: RiskLimits::default(),
+            cancel_on_trading_halt: true,
+        }
+    }
+}
+
+// Runs a strategy against a backend, usually the live API through KalshiClient. Every order the strategy queues goes through the
+// risk checks and is tracked by an OrderManager, and new orders are refused while trading
+// is halted. There is no streaming client in this crate, so market data, fills and order
+// updates are polled over REST every poll_interval.
+pub struct StrategyRuntime<S: Strategy, B: Backend = KalshiClient> {
+    backend: B,
+    strategy: S,
+    config: RuntimeConfig,
+    order_manager: OrderManager,
+    risk_checker: RiskChecker,
+    exchange_status: Option<ExchangeStatus>,
+    balance: Cents,
+    positions: HashMap<String, i64>,
+    started_time: DateTime<Utc>,
+    fills_since: DateTime<Utc>,
+    // Trade ids delivered since fills_since with their created_time, so fills returned
+    // again by the overlapping window aren't delivered twice.
+    seen_trade_ids: HashMap<String, DateTime<Utc>>,
+    next_client_order_id: u64,
+}
+
+impl<S: Strategy, B: Backend> StrategyRuntime<S, B> {
+    pub fn new(backend: B, strategy: S, config: RuntimeConfig) -> Self {
+        let now = Utc::now();
+        StrategyRuntime {
+            backend,
+            strategy,
+            risk_checker: RiskChecker::new(config.risk_limits.clone()),
+            config,
+            order_manager: OrderManager::new(),
+            exchange_status: None,
+            balance: Cents::ZERO,
+            positions: HashMap::new(),
+            started_time: now,
+            fills_since: now,
+            seen_trade_ids: HashMap::new(),
+            next_client_order_id: 1,
+        }
+    }
+
+    pub fn order_manager(&self) -> &OrderManager {
+        &self.order_manager
+    }
+
+    // Runs the event loop until shutdown completes, e.g. with tokio::signal::ctrl_c(). The
+    // strategy's resting orders are then cancelled and the strategy is handed back.
+    pub async fn run<F: Future>(
+        mut self,
+        shutdown: F,
+    ) -> Result<S, Box<dyn std::error::Error + Send + Sync>> {
+        info!(
+            "Starting strategy runtime for tickers {:?}",
+            self.config.tickers
+        );
+        let mut poll = tokio::time::interval(self.config.poll_interval);
+        let mut timer = tokio::time::interval(self.config.timer_interval);
+        let mut status = tokio::time::interval(self.config.exchange_status_interval);
+        for interval in [&mut poll, &mut timer, &mut status] {
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        }
+        // The first timer tick completes immediately; strategies expect a full interval.
+        timer.tick().await;
+
+        tokio::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = status.tick() => {
+                    if let Err(e) = self.poll_exchange_status_async().await {
+                        eprintln!("Error polling exchange status - {}", e);
+                    }
+                }
+                _ = poll.tick() => {
+                    if let Err(e) = self.poll_async().await {
+                        eprintln!("Error polling market data - {}", e);
+                    }
+                }
+                _ = timer.tick() => {
+                    self.dispatch_async(|strategy, context| strategy.on_timer(context))
+                        .await;
+                }
+            }
+        }
+
+        info!("Shutting down strategy runtime");
+        self.cancel_open_orders_async().await?;
+        Ok(self.strategy)
+    }
+
+    async fn poll_exchange_status_async(
+        &mut self,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let status = self.backend.get_exchange_status_async().await?;
+        // While trading is halted the cancel is retried on every tick, so orders that failed
+        // to cancel when the halt was first seen are not left resting.
+        let cancel_result = if !status.trading_active && self.config.cancel_on_trading_halt {
+            self.cancel_open_orders_async().await
+        } else {
+            Ok(())
+        };
+        if self.exchange_status.as_ref() != Some(&status) {
+            let change = ExchangeStatusChange {
+                previous: self.exchange_status.replace(status.clone()),
+                current: status,
+                time: Utc::now(),
+            };
+            info!("Exchange status changed: {:?}", change);
+            self.dispatch_async(|strategy, context| {
+                strategy.on_exchange_status_change(context, &change.current)
+            })
+            .await;
+        }
+        cancel_result
+    }
+
+    async fn poll_async(&mut self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.refresh_account_async().await?;
+        self.order_manager.poll_async(&self.backend).await?;
+
+        let mut fills = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = self
+                .backend
+                .get_fills_async(
+                    None,
+                    None,
+                    Some(self.fills_since),
+                    None,
+                    Some(FILLS_PAGE_SIZE),
+                    cursor.as_deref(),
+                )
+                .await?;
+            fills.extend(page.fills);
+            if page.cursor.is_empty() {
+                break;
+            }
+            cursor = Some(page.cursor);
+        }
+        fills.sort_by_key(|fill| fill.created_time);
+        for fill in &fills {
+            // Fills for orders placed outside the runtime aren't the strategy's business.
+            if self.order_manager.get_by_order_id(&fill.order_id).is_none()
+                || self
+                    .seen_trade_ids
+                    .insert(fill.trade_id.clone(), fill.created_time)
+                    .is_some()
+            {
+                continue;
+            }
+            self.order_manager.on_fill(fill);
+            self.dispatch_async(|strategy, context| strategy.on_fill(context, fill))
+                .await;
+        }
+        if let Some(newest) = fills.last() {
+            // min_ts is sent in whole seconds, so the window really starts on the second.
+            let fills_since = (newest.created_time - chrono::Duration::seconds(FILLS_OVERLAP_SECS))
+                .trunc_subsecs(0);
+            if fills_since > self.fills_since {
+                self.fills_since = fills_since;
+                // Older fills are outside the window and can't be returned again.
+                self.seen_trade_ids
+                    .retain(|_, created_time| *created_time >= fills_since);
+            }
+        }
+
+        for ticker in self.config.tickers.clone() {
+            let market = self.backend.get_market_async(&ticker).await?.market;
+            self.dispatch_async(|strategy, context| strategy.on_market(context, &market))
+                .await;
+            let orderbook = self
+                .backend
+                .get_market_orderbook_async(&ticker, self.config.orderbook_depth)
+                .await?
+                .orderbook;
+            self.dispatch_async(|strategy, context| {
+                strategy.on_book_update(context, &ticker, &orderbook)
+            })
+            .await;
+        }
+        Ok(())
+    }
+
+    async fn refresh_account_async(
+        &mut self,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.balance = self.backend.get_balance_async().await?.balance;
+        let positions = self
+            .backend
+            .get_positions_async(None, None, None, None, None)
+            .await?;
+        self.positions = positions
+            .market_positions
+            .into_iter()
+            .map(|position| (position.ticker, position.position))
+            .collect();
+        Ok(())
+    }
+
+    // Runs a strategy handler and then sends the orders it queued.
+    async fn dispatch_async(&mut self, handler: impl FnOnce(&mut S, &mut StrategyContext)) {
+        let open_orders = self
+            .order_manager
+            .open_orders()
+            .filter_map(|order| order.last_exchange_order.clone())
+            .collect();
+        let mut context = StrategyContext::new(
+            Utc::now(),
+            self.balance,
+            self.positions.clone(),
+            open_orders,
+        );
+        handler(&mut self.strategy, &mut context);
+        for intent in context.take_intents() {
+            let result = match intent {
+                OrderIntent::Create(request) => self.create_order_async(request).await,
+                OrderIntent::Cancel(order_id) => self.cancel_order_async(&order_id).await,
+            };
+            if let Err(e) = result {
+                eprintln!("Strategy order failed - {}", e);
+            }
+        }
+    }
+
+    async fn create_order_async(
+        &mut self,
+        mut request: CreateOrderRequest,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(status) = &self.exchange_status {
+            if !status.trading_active {
+                return Err(Box::from("Trading is halted, not sending order"));
+            }
+        }
+        if request.client_order_id.is_empty() {
+            request.client_order_id = format!(
+                "runtime-{}-{}",
+                self.started_time.timestamp(),
+                self.next_client_order_id
+            );
+            self.next_client_order_id += 1;
+        }
+        let context = RiskContext::fetch_async(&self.backend, &request.ticker).await?;
+        if let Err(violation) = self.risk_checker.check(&request, &context) {
+            eprintln!("Order rejected by risk checks - {}", violation);
+            return Err(Box::new(violation));
+        }
+        self.order_manager
+            .create_order_async(&self.backend, &request)
+            .await?;
+        Ok(())
+    }
+
+    async fn cancel_order_async(
+        &mut self,
+        order_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let response = self.backend.cancel_order_async(order_id).await?;
+        self.order_manager.on_order_update(&response.order);
+        Ok(())
+    }
+
+    // Cancels every order the runtime placed that is still resting.
+    async fn cancel_open_orders_async(
+        &mut self,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let order_ids: Vec<String> = self
+            .order_manager
+            .open_orders()
+            .filter_map(|order| order.order_id.clone())
+            .collect();
+        if order_ids.is_empty() {
+            return Ok(());
+        }
+        info!("Cancelling {} resting orders", order_ids.len());
+        let mut failed = Vec::new();
+        match self
+            .backend
+            .batch_cancel_orders_async(&order_ids, SHUTDOWN_CANCEL_CONCURRENCY)
+            .await
+        {
+            Ok(response) => {
+                for result in response.orders {
+                    match result.order {
+                        Some(order) => self.order_manager.on_order_update(&order),
+                        None => failed.push(result.order_id),
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Batch cancel failed, cancelling individually - {}", e);
+                failed = order_ids;
+            }
+        }
+
+        // Anything the batch didn't cancel is retried one order at a time.
+        let mut errors = 0;
+        for order_id in failed {
+            if let Err(e) = self.cancel_order_async(&order_id).await {
+                eprintln!("Unable to cancel order '{}' - {}", order_id, e);
+                errors += 1;
+            }
+        }
+        if errors > 0 {
+            return Err(format!("Unable to cancel {} orders", errors).into());
+        }
+        Ok(())
+    }
+}
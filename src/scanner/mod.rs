@@ -0,0 +1,327 @@
+use crate::client::MarketData;
+use crate::market::Market;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+const DEFAULT_PAGE_SIZE: i64 = 200;
+
+// A numeric property of a market that filters and rankings can refer to by name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarketField {
+    // yes_ask - yes_bid in cents.
+    Spread,
+    YesBid,
+    YesAsk,
+    LastPrice,
+    Volume,
+    Volume24h,
+    Liquidity,
+    OpenInterest,
+    // Hours from now until close_time. Negative once the market has closed.
+    HoursToClose,
+}
+
+impl MarketField {
+    pub const ALL: [MarketField; 9] = [
+        MarketField::Spread,
+        MarketField::YesBid,
+        MarketField::YesAsk,
+        MarketField::LastPrice,
+        MarketField::Volume,
+        MarketField::Volume24h,
+        MarketField::Liquidity,
+        MarketField::OpenInterest,
+        MarketField::HoursToClose,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MarketField::Spread => "spread",
+            MarketField::YesBid => "yes_bid",
+            MarketField::YesAsk => "yes_ask",
+            MarketField::LastPrice => "last_price",
+            MarketField::Volume => "volume",
+            MarketField::Volume24h => "volume_24h",
+            MarketField::Liquidity => "liquidity",
+            MarketField::OpenInterest => "open_interest",
+            MarketField::HoursToClose => "hours_to_close",
+        }
+    }
+
+    pub fn value(self, market: &Market, now: DateTime<Utc>) -> f64 {
+        match self {
+            MarketField::Spread => (market.yes_ask - market.yes_bid).value() as f64,
+            MarketField::YesBid => market.yes_bid.value() as f64,
+            MarketField::YesAsk => market.yes_ask.value() as f64,
+            MarketField::LastPrice => market.last_price.value() as f64,
+            MarketField::Volume => market.volume as f64,
+            MarketField::Volume24h => market.volume_24h as f64,
+            MarketField::Liquidity => market.liquidity.value() as f64,
+            MarketField::OpenInterest => market.open_interest as f64,
+            MarketField::HoursToClose => (market.close_time - now).num_seconds() as f64 / 3600.0,
+        }
+    }
+}
+
+impl FromStr for MarketField {
+    type Err = InvalidScanExpression;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        MarketField::ALL
+            .into_iter()
+            .find(|field| field.name() == name.trim())
+            .ok_or_else(|| InvalidScanExpression(format!("Unknown market field '{}'", name)))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    // Longest operators first so "<=" isn't read as "<".
+    const OPERATORS: [(&'static str, Comparison); 6] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("!=", Comparison::NotEqual),
+        ("<", Comparison::LessThan),
+        (">", Comparison::GreaterThan),
+        ("=", Comparison::Equal),
+    ];
+
+    fn compare(self, left: f64, right: f64) -> bool {
+        match self {
+            Comparison::LessThan => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::GreaterThan => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        }
+    }
+}
+
+// A condition a market has to meet to be included in a scan. Parsed from expressions such
+// as "spread<=3", "volume_24h>=1000", "hours_to_close<48" or "category=Economics".
+#[derive(Clone, Debug, PartialEq)]
+pub enum MarketFilter {
+    Compare {
+        field: MarketField,
+        comparison: Comparison,
+        value: f64,
+    },
+    // Case insensitive match on the market's category.
+    Category {
+        category: String,
+        equal: bool,
+    },
+}
+
+impl MarketFilter {
+    pub fn matches(&self, market: &Market, now: DateTime<Utc>) -> bool {
+        match self {
+            MarketFilter::Compare {
+                field,
+                comparison,
+                value,
+            } => comparison.compare(field.value(market, now), *value),
+            MarketFilter::Category { category, equal } => {
+                market.category.eq_ignore_ascii_case(category) == *equal
+            }
+        }
+    }
+}
+
+impl FromStr for MarketFilter {
+    type Err = InvalidScanExpression;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let (name, comparison, value) = Comparison::OPERATORS
+            .iter()
+            .find_map(|(operator, comparison)| {
+                expression
+                    .split_once(operator)
+                    .map(|(name, value)| (name.trim(), *comparison, value.trim()))
+            })
+            .ok_or_else(|| {
+                InvalidScanExpression(format!("No comparison in filter '{}'", expression))
+            })?;
+
+        if name == "category" {
+            return match comparison {
+                Comparison::Equal | Comparison::NotEqual => Ok(MarketFilter::Category {
+                    category: value.to_string(),
+                    equal: comparison == Comparison::Equal,
+                }),
+                _ => Err(InvalidScanExpression(format!(
+                    "Category can only be compared with = or !=, got '{}'",
+                    expression
+                ))),
+            };
+        }
+        let field = name.parse()?;
+        let value = value.parse().map_err(|_| {
+            InvalidScanExpression(format!("Invalid number in filter '{}'", expression))
+        })?;
+        Ok(MarketFilter::Compare {
+            field,
+            comparison,
+            value,
+        })
+    }
+}
+
+// The order scan results are returned in. Parsed from "field", "field:desc" or
+// "field:asc"; descending is the default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ranking {
+    pub field: MarketField,
+    pub descending: bool,
+}
+
+impl FromStr for Ranking {
+    type Err = InvalidScanExpression;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let (name, direction) = expression.split_once(':').unwrap_or((expression, "desc"));
+        let descending = match direction.trim() {
+            "desc" => true,
+            "asc" => false,
+            _ => {
+                return Err(InvalidScanExpression(format!(
+                    "Ranking direction must be asc or desc, got '{}'",
+                    expression
+                )))
+            }
+        };
+        Ok(Ranking {
+            field: name.parse()?,
+            descending,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidScanExpression(pub String);
+
+impl fmt::Display for InvalidScanExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidScanExpression {}
+
+#[derive(Clone, Debug)]
+pub struct ScanConfig {
+    pub filters: Vec<MarketFilter>,
+    pub ranking: Ranking,
+    pub top_n: usize,
+    // Market status to page through: open, closed or settled.
+    pub status: Option<String>,
+    pub page_size: i64,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        ScanConfig {
+            filters: Vec::new(),
+            ranking: Ranking {
+                field: MarketField::Volume24h,
+                descending: true,
+            },
+            top_n: 20,
+            status: Some("open".to_string()),
+            page_size: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ScanResult {
+    // Value of the ranking field for this market.
+    pub score: f64,
+    pub market: Market,
+}
+
+// Keeps the markets that pass every filter and returns the top_n best by the ranking.
+// Ties are broken by ticker so results are stable.
+pub fn scan(
+    markets: impl IntoIterator<Item = Market>,
+    config: &ScanConfig,
+    now: DateTime<Utc>,
+) -> Vec<ScanResult> {
+    let mut results: Vec<ScanResult> = markets
+        .into_iter()
+        .filter(|market| {
+            config
+                .filters
+                .iter()
+                .all(|filter| filter.matches(market, now))
+        })
+        .map(|market| ScanResult {
+            score: config.ranking.field.value(&market, now),
+            market,
+        })
+        .collect();
+    results.sort_by(|a, b| {
+        let ordering = a.score.total_cmp(&b.score);
+        let ordering = if config.ranking.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        };
+        ordering.then_with(|| a.market.ticker.cmp(&b.market.ticker))
+    });
+    results.truncate(config.top_n);
+    results
+}
+
+// Pages through every market with the given status.
+pub async fn get_all_markets_async<B: MarketData + ?Sized>(
+    backend: &B,
+    status: Option<&str>,
+    page_size: i64,
+) -> Result<Vec<Market>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut markets = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let page = backend
+            .get_markets_async(
+                Some(page_size),
+                cursor.as_deref(),
+                None,
+                None,
+                None,
+                None,
+                status,
+                None,
+            )
+            .await?;
+        markets.extend(page.markets);
+        if page.cursor.is_empty() {
+            break;
+        }
+        cursor = Some(page.cursor);
+    }
+    Ok(markets)
+}
+
+// Fetches every market with the configured status and scans them.
+pub async fn scan_markets_async<B: MarketData + ?Sized>(
+    backend: &B,
+    config: &ScanConfig,
+) -> Result<Vec<ScanResult>, Box<dyn std::error::Error + Send + Sync>> {
+    let markets =
+        get_all_markets_async(backend, config.status.as_deref(), config.page_size).await?;
+    info!("Scanning {} markets", markets.len());
+    Ok(scan(markets, config, Utc::now()))
+}
@@ -0,0 +1,111 @@
+use crate::exchange::ExchangeStatus;
+use crate::market::{Candlestick, Market, Orderbook, Trade};
+use crate::money::Cents;
+use crate::portfolio::{CreateOrderRequest, Fill, Order};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+pub enum OrderIntent {
+    Create(CreateOrderRequest),
+    // Cancels the resting order with this order id.
+    Cancel(String),
+}
+
+// What a strategy can see while handling an update, and where it queues its orders. Orders
+// are only sent once the handler returns, which lets the backtester and live trading drive
+// the same strategy code.
+#[derive(Clone, Debug)]
+pub struct StrategyContext {
+    now: DateTime<Utc>,
+    balance: Cents,
+    positions: HashMap<String, i64>,
+    open_orders: Vec<Order>,
+    intents: Vec<OrderIntent>,
+}
+
+impl StrategyContext {
+    pub fn new(
+        now: DateTime<Utc>,
+        balance: Cents,
+        positions: HashMap<String, i64>,
+        open_orders: Vec<Order>,
+    ) -> Self {
+        StrategyContext {
+            now,
+            balance,
+            positions,
+            open_orders,
+            intents: Vec::new(),
+        }
+    }
+
+    // Exchange time of the update being handled. In a backtest this is the recorded time.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now
+    }
+
+    pub fn balance(&self) -> Cents {
+        self.balance
+    }
+
+    // Positive for yes contracts, negative for no contracts.
+    pub fn position(&self, ticker: &str) -> i64 {
+        self.positions.get(ticker).copied().unwrap_or(0)
+    }
+
+    pub fn open_orders(&self) -> &[Order] {
+        &self.open_orders
+    }
+
+    pub fn create_order(&mut self, request: CreateOrderRequest) {
+        self.intents.push(OrderIntent::Create(request));
+    }
+
+    pub fn cancel_order(&mut self, order_id: &str) {
+        self.intents.push(OrderIntent::Cancel(order_id.to_string()));
+    }
+
+    pub fn take_intents(&mut self) -> Vec<OrderIntent> {
+        std::mem::take(&mut self.intents)
+    }
+}
+
+// A trading strategy. Every handler is optional; implement the ones for the data the
+// strategy cares about and queue orders on the context.
+pub trait Strategy {
+    fn on_market(&mut self, _context: &mut StrategyContext, _market: &Market) {}
+
+    fn on_book_update(
+        &mut self,
+        _context: &mut StrategyContext,
+        _ticker: &str,
+        _orderbook: &Orderbook,
+    ) {
+    }
+
+    fn on_trade(&mut self, _context: &mut StrategyContext, _trade: &Trade) {}
+
+    fn on_candlestick(
+        &mut self,
+        _context: &mut StrategyContext,
+        _ticker: &str,
+        _candlestick: &Candlestick,
+    ) {
+    }
+
+    // Called for each of the strategy's own fills.
+    fn on_fill(&mut self, _context: &mut StrategyContext, _fill: &Fill) {}
+
+    // Called at a fixed interval by the live runtime. The backtester doesn't have a clock of
+    // its own, so it never calls this.
+    fn on_timer(&mut self, _context: &mut StrategyContext) {}
+
+    // Called by the live runtime with the first exchange status it sees and on every change.
+    fn on_exchange_status_change(
+        &mut self,
+        _context: &mut StrategyContext,
+        _status: &ExchangeStatus,
+    ) {
+    }
+}
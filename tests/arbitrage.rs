@@ -0,0 +1,150 @@
+use kalshi_api::arbitrage::{self, ArbitrageConfig, ArbitrageSide, BestPriceSums};
+use kalshi_api::client::KalshiClient;
+use kalshi_api::fees::FeeSchedule;
+use kalshi_api::market::api_structs::Event;
+use kalshi_api::market::{Market, Orderbook};
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use std::collections::HashMap;
+
+const EVENT_TICKER: &str = "FED-23MAY";
+const TICKERS: [&str; 3] = ["FED-23MAY-HOLD", "FED-23MAY-HIKE25", "FED-23MAY-HIKE50"];
+
+fn event() -> Event {
+    let mut event = fixtures::event(EVENT_TICKER, "FED");
+    event.mutually_exclusive = true;
+    event
+}
+
+fn markets() -> Vec<Market> {
+    TICKERS
+        .iter()
+        .map(|ticker| fixtures::market(ticker, EVENT_TICKER, 20, 30))
+        .collect()
+}
+
+// Every leg has yes bids at 20 and yes asks at 30 (no bids at 70). The last leg is thinner
+// and its next ask is at 40, which makes a set cost 100.
+fn orderbooks() -> HashMap<String, Orderbook> {
+    let mut orderbooks = HashMap::new();
+    orderbooks.insert(
+        TICKERS[0].to_string(),
+        fixtures::orderbook(&[(20, 10)], &[(70, 5), (68, 10)]),
+    );
+    orderbooks.insert(
+        TICKERS[1].to_string(),
+        fixtures::orderbook(&[(20, 10)], &[(70, 10)]),
+    );
+    orderbooks.insert(
+        TICKERS[2].to_string(),
+        fixtures::orderbook(&[(20, 10)], &[(70, 3), (60, 20)]),
+    );
+    orderbooks
+}
+
+#[test]
+fn finds_yes_sets_priced_under_100_after_fees() {
+    assert_eq!(
+        arbitrage::best_price_sums(&markets()),
+        BestPriceSums {
+            yes_asks: Cents(90),
+            yes_bids: Cents(60),
+        }
+    );
+
+    let opportunities = arbitrage::find_arbitrage(
+        &event(),
+        &markets(),
+        &orderbooks(),
+        &ArbitrageConfig::default(),
+    );
+    // Selling yes at 20 on every leg is nowhere near 100, so only the yes side shows up.
+    assert_eq!(opportunities.len(), 1);
+    let opportunity = &opportunities[0];
+    assert_eq!(opportunity.side, ArbitrageSide::BuyYes);
+    // Only 3 contracts are offered at 30 on the last leg.
+    assert_eq!(opportunity.sets, 3);
+    assert_eq!(opportunity.cost, Cents(270));
+    // ceil(0.07 * 3 * 0.30 * 0.70 dollars) = 5 cents per leg.
+    assert_eq!(opportunity.fees, Cents(15));
+    assert_eq!(opportunity.payout, Cents(300));
+    assert_eq!(opportunity.profit, Cents(15));
+    assert!(opportunity
+        .legs
+        .iter()
+        .all(|leg| leg.side == "yes" && leg.limit_price == price(30)));
+}
+
+#[test]
+fn fees_and_exclusivity_can_rule_out_arbitrage() {
+    // A set at 97 cents is eaten up by fees.
+    let mut orderbooks = orderbooks();
+    orderbooks.insert(
+        TICKERS[2].to_string(),
+        fixtures::orderbook(&[(20, 10)], &[(63, 10)]),
+    );
+    let config = ArbitrageConfig::default();
+    assert!(arbitrage::find_arbitrage(&event(), &markets(), &orderbooks, &config).is_empty());
+
+    let free = ArbitrageConfig {
+        fees: FeeSchedule::free(),
+        max_sets: Some(2),
+        ..ArbitrageConfig::default()
+    };
+    let opportunities = arbitrage::find_arbitrage(&event(), &markets(), &orderbooks, &free);
+    assert_eq!(opportunities[0].sets, 2);
+    assert_eq!(opportunities[0].profit, Cents(6));
+
+    let mut event = event();
+    event.mutually_exclusive = false;
+    assert!(arbitrage::find_arbitrage(&event, &markets(), &orderbooks, &free).is_empty());
+}
+
+#[test]
+fn finds_no_sets_when_yes_bids_sum_over_100() {
+    let mut orderbooks = HashMap::new();
+    for ticker in TICKERS {
+        orderbooks.insert(
+            ticker.to_string(),
+            fixtures::orderbook(&[(40, 4)], &[(50, 10)]),
+        );
+    }
+    let opportunities = arbitrage::find_arbitrage(
+        &event(),
+        &markets(),
+        &orderbooks,
+        &ArbitrageConfig::default(),
+    );
+    let opportunity = &opportunities[0];
+    assert_eq!(opportunity.side, ArbitrageSide::BuyNo);
+    // Each set is three no contracts at 60 and pays 200, since two legs settle no.
+    assert_eq!(opportunity.sets, 4);
+    assert_eq!(opportunity.cost, Cents(720));
+    assert_eq!(opportunity.payout, Cents(800));
+    // ceil(0.07 * 4 * 0.60 * 0.40 dollars) = 7 cents per leg.
+    assert_eq!(opportunity.profit, Cents(800 - 720 - 21));
+}
+
+#[tokio::test]
+async fn fetches_event_orderbooks_from_the_api() {
+    let mut state = MockState::default();
+    state.events.push(event());
+    state.markets = markets();
+    state.orderbooks = orderbooks();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let backend = KalshiClient::new(reqwest::Client::new(), token.clone());
+
+    let opportunities = server
+        .run(arbitrage::find_event_arbitrage_async(
+            &backend,
+            EVENT_TICKER,
+            10,
+            &ArbitrageConfig::default(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(opportunities[0].profit, Cents(15));
+    server.stop().await.unwrap();
+}
@@ -0,0 +1,173 @@
+use chrono::{DateTime, Duration, Utc};
+use kalshi_api::backtest::{BacktestConfig, Backtester, MarketDataEvent};
+use kalshi_api::market::{Market, Orderbook};
+use kalshi_api::mock::fixtures;
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::money::Cents;
+use kalshi_api::portfolio::{CreateOrderRequest, Fill};
+use kalshi_api::strategy::{Strategy, StrategyContext};
+
+const TICKER: &str = "INXD-23APR03-B4100";
+
+fn at(minutes: i64) -> DateTime<Utc> {
+    fixtures::fixture_time() + Duration::minutes(minutes)
+}
+
+fn orderbook_event(minutes: i64, yes: &[(i32, i32)], no: &[(i32, i32)]) -> MarketDataEvent {
+    MarketDataEvent::Orderbook {
+        time: at(minutes),
+        ticker: TICKER.to_string(),
+        orderbook: fixtures::orderbook(yes, no),
+    }
+}
+
+fn trade_event(minutes: i64, taker_side: &str, yes_price: i64, count: i32) -> MarketDataEvent {
+    let mut trade = fixtures::trade("t", TICKER, taker_side, price(yes_price), count);
+    trade.trade_id = format!("trade-{}", minutes);
+    trade.created_time = at(minutes);
+    MarketDataEvent::Trade(trade)
+}
+
+fn settled_market_event(minutes: i64, result: &str) -> MarketDataEvent {
+    let mut market = fixtures::market(TICKER, "INXD-23APR03", 40, 42);
+    market.result = result.to_string();
+    MarketDataEvent::Market {
+        time: at(minutes),
+        market,
+    }
+}
+
+// Buys yes once, on the first orderbook it sees.
+struct BuyOnce {
+    yes_price: i64,
+    count: i32,
+    placed: bool,
+    fills: Vec<Fill>,
+}
+
+impl BuyOnce {
+    fn new(yes_price: i64, count: i32) -> Self {
+        BuyOnce {
+            yes_price,
+            count,
+            placed: false,
+            fills: Vec::new(),
+        }
+    }
+}
+
+impl Strategy for BuyOnce {
+    fn on_book_update(&mut self, context: &mut StrategyContext, ticker: &str, _: &Orderbook) {
+        if self.placed {
+            return;
+        }
+        self.placed = true;
+        context.create_order(CreateOrderRequest {
+            action: "buy".to_string(),
+            client_order_id: "buy-once".to_string(),
+            count: self.count,
+            side: "yes".to_string(),
+            ticker: ticker.to_string(),
+            type_: "limit".to_string(),
+            yes_price: Some(price(self.yes_price)),
+            ..CreateOrderRequest::default()
+        });
+    }
+
+    fn on_market(&mut self, context: &mut StrategyContext, market: &Market) {
+        assert_eq!(context.position(&market.ticker), 0);
+    }
+
+    fn on_fill(&mut self, _: &mut StrategyContext, fill: &Fill) {
+        self.fills.push(fill.clone());
+    }
+}
+
+// Submits a single order on the first orderbook it sees.
+struct SubmitOnce {
+    request: Option<CreateOrderRequest>,
+}
+
+impl Strategy for SubmitOnce {
+    fn on_book_update(&mut self, context: &mut StrategyContext, _: &str, _: &Orderbook) {
+        if let Some(request) = self.request.take() {
+            context.create_order(request);
+        }
+    }
+}
+
+#[test]
+fn resting_orders_fill_after_the_queue_ahead_trades() {
+    let events = vec![
+        orderbook_event(0, &[(40, 10)], &[(55, 5)]),
+        trade_event(1, "no", 40, 8),
+        trade_event(2, "no", 40, 4),
+        settled_market_event(3, "yes"),
+    ];
+    let backtester = Backtester::new(BuyOnce::new(40, 5), BacktestConfig::default());
+    let (strategy, report) = backtester.run(events);
+
+    // 10 contracts were ahead of us, so only 2 of the 12 traded reach the order.
+    assert_eq!(strategy.fills.len(), 1);
+    assert_eq!(report.fills[0].count, 2);
+    assert!(!report.fills[0].is_taker);
+    assert_eq!(report.orders[0].status, "canceled");
+    assert_eq!(report.settlements[0].revenue, Cents(200));
+    assert_eq!(report.final_balance, Cents(100_000 - 80 + 200));
+    assert_eq!(report.total_pnl(), Cents(120));
+}
+
+#[test]
+fn marketable_orders_take_liquidity_and_pay_taker_fees() {
+    let events = vec![
+        orderbook_event(0, &[(40, 10)], &[(55, 3)]),
+        settled_market_event(1, "no"),
+    ];
+    let backtester = Backtester::new(BuyOnce::new(50, 5), BacktestConfig::default());
+    let (_, report) = backtester.run(events);
+
+    // 3 contracts at the 45 cent ask; the other 2 rest at 50 until settlement.
+    assert_eq!(report.fills.len(), 1);
+    assert_eq!(report.fills[0].yes_price, price(45));
+    assert!(report.fills[0].is_taker);
+    // ceil(0.07 * 3 * 0.45 * 0.55 dollars) = 6 cents.
+    assert_eq!(report.ledger.total_fees(), Cents(6));
+    assert_eq!(report.final_balance, Cents(100_000 - 135 - 6));
+    assert_eq!(report.pnl_curve.len(), 2);
+    assert_eq!(report.total_pnl(), Cents(-141));
+}
+
+#[test]
+fn market_orders_take_the_opposite_side_for_every_action_and_side() {
+    // Yes bids at 40 and no bids at 55.
+    let cases = [
+        ("buy", "yes", price(45), 3),
+        ("sell", "no", price(45), 3),
+        ("sell", "yes", price(40), 5),
+        ("buy", "no", price(40), 5),
+    ];
+    for (action, side, yes_price, count) in cases {
+        let events = vec![orderbook_event(0, &[(40, 10)], &[(55, 3)])];
+        let strategy = SubmitOnce {
+            request: Some(CreateOrderRequest {
+                action: action.to_string(),
+                client_order_id: "market".to_string(),
+                count: 5,
+                side: side.to_string(),
+                ticker: TICKER.to_string(),
+                type_: "market".to_string(),
+                ..CreateOrderRequest::default()
+            }),
+        };
+        let backtester = Backtester::new(strategy, BacktestConfig::default());
+        let (_, report) = backtester.run(events);
+
+        let case = format!("{} {}", action, side);
+        assert_eq!(report.fills.len(), 1, "{}", case);
+        assert_eq!(report.fills[0].yes_price, yes_price, "{}", case);
+        assert_eq!(report.fills[0].count, count, "{}", case);
+        assert!(report.fills[0].is_taker, "{}", case);
+        // Whatever the book can't fill is canceled rather than left resting.
+        assert_eq!(report.orders[0].remaining_count, 0, "{}", case);
+    }
+}
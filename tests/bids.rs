@@ -0,0 +1,125 @@
+use kalshi_api::bids::{self, Bids, Spread};
+use kalshi_api::market::Orderbook;
+use kalshi_api::money::{Cents, Price};
+use proptest::prelude::*;
+
+const FEE_RATE: f64 = 0.07;
+
+fn price() -> impl Strategy<Value = Price> {
+    (1i64..=99).prop_map(|cents| Price::try_from(cents).unwrap())
+}
+
+// A yes bid and ask, with the no side as their complement the way the exchange quotes it.
+fn bids() -> impl Strategy<Value = Bids> {
+    (1i64..=98)
+        .prop_flat_map(|bid| (Just(bid), bid + 1..=99))
+        .prop_map(|(bid, ask)| Bids {
+            yes: Spread {
+                bid: Cents(bid),
+                ask: Cents(ask),
+            },
+            no: Spread {
+                bid: Cents(100 - ask),
+                ask: Cents(100 - bid),
+            },
+        })
+}
+
+#[test]
+fn prices_a_simple_book() {
+    let bids = Bids {
+        yes: Spread {
+            bid: Cents(40),
+            ask: Cents(44),
+        },
+        no: Spread {
+            bid: Cents(56),
+            ask: Cents(60),
+        },
+    };
+    assert_eq!(bids.yes.implied_probability(), 0.42);
+    assert!((bids.overround() - 0.04).abs() < 1e-9);
+
+    let orderbook = Orderbook {
+        yes: vec![vec![38, 50], vec![40, 10]],
+        no: vec![vec![56, 30]],
+    };
+    // 3 times the size on the ask pulls the microprice towards the bid.
+    assert_eq!(bids::orderbook_microprice(&orderbook), Some(0.41));
+    let one_sided = Orderbook {
+        yes: vec![vec![40, 10]],
+        no: Vec::new(),
+    };
+    assert_eq!(bids::orderbook_microprice(&one_sided), None);
+
+    let price = Price::try_from(50).unwrap();
+    // 1.75 cents of fees on a 50 cent contract.
+    assert!((bids::break_even_probability(price, FEE_RATE) - 0.5175).abs() < 1e-9);
+    assert!((bids::expected_value(0.6, price, FEE_RATE) - 8.25).abs() < 1e-9);
+    assert!((bids::kelly_fraction(0.6, price, 0.0) - 0.2).abs() < 1e-9);
+}
+
+proptest! {
+    #[test]
+    fn implied_probability_is_between_bid_and_ask(bids in bids()) {
+        for spread in [&bids.yes, &bids.no] {
+            let probability = spread.implied_probability();
+            prop_assert!(probability >= spread.bid.value() as f64 / 100.0);
+            prop_assert!(probability <= spread.ask.value() as f64 / 100.0);
+        }
+        let total = bids.yes.implied_probability() + bids.no.implied_probability();
+        prop_assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn overround_is_the_spread(bids in bids()) {
+        let width = bids.yes.width().value() as f64 / 100.0;
+        prop_assert!((bids.overround() - width).abs() < 1e-9);
+        prop_assert!(bids.overround() > 0.0);
+    }
+
+    #[test]
+    fn microprice_is_between_bid_and_ask(
+        bids in bids(),
+        bid_size in 0i64..10_000,
+        ask_size in 0i64..10_000,
+    ) {
+        let microprice = bids::microprice(bids.yes.bid, bid_size, bids.yes.ask, ask_size);
+        prop_assert!(microprice >= bids.yes.bid.value() as f64 / 100.0 - 1e-9);
+        prop_assert!(microprice <= bids.yes.ask.value() as f64 / 100.0 + 1e-9);
+        if bid_size == ask_size {
+            prop_assert!((microprice - bids.yes.implied_probability()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn expected_value_is_zero_at_break_even(price in price(), fee_rate in 0.0..0.2f64) {
+        let break_even = bids::break_even_probability(price, fee_rate);
+        prop_assert!(break_even >= price.value() as f64 / 100.0);
+        prop_assert!(bids::expected_value(break_even, price, fee_rate).abs() < 1e-9);
+        prop_assert_eq!(bids::kelly_fraction(break_even, price, fee_rate), 0.0);
+    }
+
+    #[test]
+    fn expected_value_and_kelly_grow_with_probability(
+        price in price(),
+        fee_rate in 0.0..0.2f64,
+        low in 0.0..1.0f64,
+        high in 0.0..1.0f64,
+    ) {
+        let (low, high) = if low <= high { (low, high) } else { (high, low) };
+        prop_assert!(
+            bids::expected_value(low, price, fee_rate)
+                <= bids::expected_value(high, price, fee_rate)
+        );
+        let kelly_low = bids::kelly_fraction(low, price, fee_rate);
+        let kelly_high = bids::kelly_fraction(high, price, fee_rate);
+        prop_assert!(kelly_low <= kelly_high);
+        prop_assert!((0.0..=1.0).contains(&kelly_high));
+        // Kelly only stakes anything when the expected value is positive.
+        prop_assert_eq!(
+            kelly_high > 0.0,
+            bids::expected_value(high, price, fee_rate) > 0.0
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use kalshi_api::cassette::{CassetteMode, CassetteServer};
+use kalshi_api::money::Cents;
+use kalshi_api::{auth, exchange, market};
+
+const TSA_CASSETTE: &str = "tests/cassettes/tsa_event.json";
+const TSA_EVENT_TICKER: &str = "TSAW-23APR02";
+const TSA_MARKET_TICKER: &str = "TSAW-23APR02-A2.5";
+
+async fn exercise_client(client: &reqwest::Client) {
+    std::env::set_var("EMAIL", "user@example.com");
+    std::env::set_var("PASS", "hunter2");
+    let token = auth::login(client).await.unwrap();
+
+    let status = exchange::get_exchange_status_async(client).await.unwrap();
+    assert!(status.trading_active);
+
+    let event = market::get_event_async(client, TSA_EVENT_TICKER, &token)
+        .await
+        .unwrap();
+    assert_eq!(event.event.series_ticker, "TSAW");
+    assert_eq!(event.markets.len(), 1);
+
+    let market = market::get_market_async(client, TSA_MARKET_TICKER, &token)
+        .await
+        .unwrap()
+        .market;
+    assert_eq!(market.yes_ask, Cents(42));
+    assert_eq!(market.close_time.to_rfc3339(), "2023-04-02T23:59:00+00:00");
+
+    let orderbook = market::get_market_orderbook_async(client, TSA_MARKET_TICKER, 2, &token)
+        .await
+        .unwrap()
+        .orderbook;
+    assert_eq!(orderbook.yes[1], vec![40, 35]);
+
+    auth::logout(client.clone(), &token).await.unwrap();
+}
+
+#[tokio::test]
+async fn replays_recorded_interactions_offline() {
+    let server = CassetteServer::start(TSA_CASSETTE, CassetteMode::Replay)
+        .await
+        .unwrap();
+    let client = reqwest::Client::new();
+    server.run(exercise_client(&client)).await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn records_interactions_with_secrets_scrubbed() {
+    // Record against a replay server standing in for the real API.
+    let upstream = CassetteServer::start(TSA_CASSETTE, CassetteMode::Replay)
+        .await
+        .unwrap();
+    let path = std::env::temp_dir().join(format!("kalshi-cassette-{}.json", std::process::id()));
+    let recorder = CassetteServer::start(
+        &path,
+        CassetteMode::Record {
+            upstream_base_url: upstream.base_url(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let client = reqwest::Client::new();
+    recorder.run(exercise_client(&client)).await;
+    let cassette = recorder.stop().await.unwrap();
+    upstream.stop().await.unwrap();
+
+    assert_eq!(cassette.interactions.len(), 6);
+    let login = &cassette.interactions[0];
+    assert_eq!(login.request_body.as_ref().unwrap()["password"], "REDACTED");
+    assert_eq!(login.response_body["token"], "REDACTED");
+    let recorded = std::fs::read_to_string(&path).unwrap();
+    assert!(!recorded.contains("hunter2"));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,193 @@
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use tokio::process::Command;
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+const MARKET_TICKER: &str = "INXD-23APR03-B4100";
+
+async fn kalshi(server: &MockServer, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_kalshi"))
+        .args(["--base-url", &server.base_url()])
+        .args(args)
+        .env("EMAIL", "user@example.com")
+        .env("PASS", "hunter2")
+        .output()
+        .await
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+async fn start_server() -> MockServer {
+    let mut state = MockState::default();
+    state.balance = Cents(12_345);
+    state.events.push(fixtures::event(EVENT_TICKER, "INXD"));
+    state
+        .markets
+        .push(fixtures::market(MARKET_TICKER, EVENT_TICKER, 40, 42));
+    state.orderbooks.insert(
+        MARKET_TICKER.to_string(),
+        fixtures::orderbook(&[(40, 10)], &[(58, 5)]),
+    );
+    MockServer::start(state).await.unwrap()
+}
+
+#[tokio::test]
+async fn prints_json_and_csv_without_log_lines() {
+    let server = start_server().await;
+
+    let status: serde_json::Value =
+        serde_json::from_str(&kalshi(&server, &["status", "-o", "json"]).await).unwrap();
+    assert_eq!(status["trading_active"], true);
+
+    let balance: serde_json::Value =
+        serde_json::from_str(&kalshi(&server, &["balance", "--output", "json"]).await).unwrap();
+    assert_eq!(balance["balance"], 12_345);
+
+    let book = kalshi(&server, &["markets", "book", MARKET_TICKER, "-o", "csv"]).await;
+    assert_eq!(book, "side,price,count\nyes,40,10\nno,58,5\n");
+
+    // A single CSV table: the event's markets.
+    let event = kalshi(&server, &["events", "get", EVENT_TICKER, "-o", "csv"]).await;
+    let lines: Vec<&str> = event.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", event);
+    assert!(lines[0].starts_with("ticker,yes_bid,yes_ask"));
+    assert!(lines[1].starts_with(MARKET_TICKER));
+
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn creates_lists_and_cancels_orders() {
+    let server = start_server().await;
+
+    let created: serde_json::Value = serde_json::from_str(
+        &kalshi(
+            &server,
+            &[
+                "orders",
+                "create",
+                "--ticker",
+                MARKET_TICKER,
+                "--action",
+                "buy",
+                "--side",
+                "yes",
+                "--count",
+                "3",
+                "--yes-price",
+                "35",
+                "-o",
+                "json",
+            ],
+        )
+        .await,
+    )
+    .unwrap();
+    let order_id = created["order_id"].as_str().unwrap().to_string();
+
+    let table = kalshi(&server, &["orders", "list"]).await;
+    let mut lines = table.lines();
+    assert!(lines.next().unwrap().starts_with("order_id"));
+    assert!(lines.next().unwrap().contains("resting"));
+
+    kalshi(&server, &["orders", "cancel", &order_id]).await;
+    let state = server.stop().await.unwrap();
+    assert_eq!(state.orders[0].status, "canceled");
+    // Every command logs out again.
+    assert_eq!(
+        state
+            .requests()
+            .iter()
+            .filter(|request| request.path.ends_with("logout"))
+            .count(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn logs_in_with_a_credentials_file_and_only_when_needed() {
+    let server = start_server().await;
+    {
+        let mut state = server.state();
+        state.email = Some("file@example.com".to_string());
+        state.password = Some("correct horse".to_string());
+    }
+    let dir = std::env::temp_dir().join(format!("kalshi-cli-credentials-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("credentials.json");
+    std::fs::write(
+        &path,
+        r#"{"email": "file@example.com", "password": "correct horse"}"#,
+    )
+    .unwrap();
+    let run = |args: Vec<&str>| {
+        Command::new(env!("CARGO_BIN_EXE_kalshi"))
+            .args(["--base-url", &server.base_url()])
+            .args(args)
+            .env_remove("EMAIL")
+            .env_remove("PASS")
+            .output()
+    };
+
+    let output = run(vec![
+        "balance",
+        "-o",
+        "json",
+        "--credentials",
+        path.to_str().unwrap(),
+    ])
+    .await
+    .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let balance: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(balance["balance"], 12_345);
+
+    // Without credentials, commands that need an account fail and the rest still work.
+    let output = run(vec!["balance"]).await.unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'EMAIL' must be set"));
+    let output = run(vec!["status"]).await.unwrap();
+    assert!(output.status.success());
+    let output = run(vec![
+        "orders",
+        "create",
+        "--ticker",
+        MARKET_TICKER,
+        "--action",
+        "buy",
+        "--side",
+        "yes",
+        "--count",
+        "10",
+        "--yes-price",
+        "40",
+        "--dry-run",
+    ])
+    .await
+    .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let logins = server
+        .stop()
+        .await
+        .unwrap()
+        .requests()
+        .iter()
+        .filter(|request| request.path.ends_with("login"))
+        .count();
+    assert_eq!(logins, 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
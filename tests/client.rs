@@ -0,0 +1,340 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use kalshi_api::client::{MarketData, Portfolio, Trading};
+use kalshi_api::exchange::{ExchangeSchedule, ExchangeStatus};
+use kalshi_api::market::api_structs::{GetEventsResponse, GetMarketsResponse};
+use kalshi_api::market::{
+    GetEventResponse, GetMarketOrderbookResponse, GetMarketResponse, GetTradesResponse, Market,
+    Orderbook,
+};
+use kalshi_api::mock::fixtures::{self, price};
+use kalshi_api::money::{Cents, Price};
+use kalshi_api::order_manager::{OrderManager, OrderState};
+use kalshi_api::portfolio::{
+    AmendOrderResponse, Balance, CancelOrderResponse, CreateOrderRequest, CreateOrderResponse,
+    DecreaseOrderResponse, GetFillsResponse, GetOrderResponse, GetOrdersResponse,
+    GetPositionsResponse, GetSettlementsResponse, Order,
+};
+use kalshi_api::runtime::{RuntimeConfig, StrategyRuntime};
+use kalshi_api::strategy::{Strategy, StrategyContext};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+const MARKET_TICKER: &str = "INXD-23APR03-B4100";
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+// An exchange that lives entirely in memory: one market, and orders that rest until they
+// are cancelled. Nothing here goes over HTTP. The orders are shared so a test can still see
+// them after handing the exchange to a runtime.
+struct FakeExchange {
+    market: Market,
+    orderbook: Orderbook,
+    orders: Arc<Mutex<Vec<Order>>>,
+}
+
+impl FakeExchange {
+    fn new() -> Self {
+        FakeExchange {
+            market: fixtures::market(MARKET_TICKER, EVENT_TICKER, 40, 42),
+            orderbook: fixtures::orderbook(&[(40, 10)], &[(58, 5)]),
+            orders: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn orders(&self) -> Vec<Order> {
+        self.orders.lock().unwrap().clone()
+    }
+
+    fn find_order(&self, order_id: &str) -> Result<Order, Error> {
+        self.orders()
+            .into_iter()
+            .find(|order| order.order_id == order_id)
+            .ok_or_else(|| format!("Order '{}' not found", order_id).into())
+    }
+}
+
+fn unsupported(name: &str) -> Error {
+    format!("{} isn't supported by the fake exchange", name).into()
+}
+
+#[async_trait]
+impl MarketData for FakeExchange {
+    async fn get_exchange_status_async(&self) -> Result<ExchangeStatus, Error> {
+        Ok(ExchangeStatus {
+            exchange_active: true,
+            trading_active: true,
+        })
+    }
+
+    async fn get_exchange_schedule_async(&self) -> Result<ExchangeSchedule, Error> {
+        Err(unsupported("get_exchange_schedule_async"))
+    }
+
+    async fn get_event_async(&self, _: &str) -> Result<GetEventResponse, Error> {
+        Err(unsupported("get_event_async"))
+    }
+
+    async fn get_events_async(
+        &self,
+        _: Option<i64>,
+        _: Option<&str>,
+        _: Option<&str>,
+        _: Option<&str>,
+    ) -> Result<GetEventsResponse, Error> {
+        Err(unsupported("get_events_async"))
+    }
+
+    async fn get_market_async(&self, market_ticker: &str) -> Result<GetMarketResponse, Error> {
+        if market_ticker != self.market.ticker {
+            return Err(format!("Market '{}' not found", market_ticker).into());
+        }
+        Ok(GetMarketResponse {
+            market: self.market.clone(),
+        })
+    }
+
+    async fn get_markets_async(
+        &self,
+        _: Option<i64>,
+        _: Option<&str>,
+        _: Option<&str>,
+        _: Option<&str>,
+        _: Option<DateTime<Utc>>,
+        _: Option<DateTime<Utc>>,
+        _: Option<&str>,
+        _: Option<&str>,
+    ) -> Result<GetMarketsResponse, Error> {
+        Err(unsupported("get_markets_async"))
+    }
+
+    async fn get_market_orderbook_async(
+        &self,
+        _: &str,
+        _: i32,
+    ) -> Result<GetMarketOrderbookResponse, Error> {
+        Ok(GetMarketOrderbookResponse {
+            orderbook: self.orderbook.clone(),
+        })
+    }
+
+    async fn get_trades_async(
+        &self,
+        _: Option<&str>,
+        _: Option<DateTime<Utc>>,
+        _: Option<DateTime<Utc>>,
+        _: Option<i32>,
+        _: Option<&str>,
+    ) -> Result<GetTradesResponse, Error> {
+        Err(unsupported("get_trades_async"))
+    }
+}
+
+#[async_trait]
+impl Trading for FakeExchange {
+    async fn create_order_async(
+        &self,
+        request: &CreateOrderRequest,
+    ) -> Result<CreateOrderResponse, Error> {
+        let mut orders = self.orders.lock().unwrap();
+        let yes_price = request.yes_price.ok_or("The fake only takes yes prices")?;
+        let mut order = fixtures::order(
+            &format!("fake-order-{}", orders.len() + 1),
+            &request.ticker,
+            &request.side,
+            yes_price,
+            request.count,
+        );
+        order.action = request.action.clone();
+        order.client_order_id = request.client_order_id.clone();
+        orders.push(order.clone());
+        Ok(CreateOrderResponse { order })
+    }
+
+    async fn get_order_async(&self, order_id: &str) -> Result<GetOrderResponse, Error> {
+        Ok(GetOrderResponse {
+            order: self.find_order(order_id)?,
+        })
+    }
+
+    async fn cancel_order_async(&self, order_id: &str) -> Result<CancelOrderResponse, Error> {
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders
+            .iter_mut()
+            .find(|order| order.order_id == order_id && order.status == "resting")
+            .ok_or_else(|| format!("Order '{}' is not resting", order_id))?;
+        let reduced_by = order.remaining_count as i64;
+        order.remaining_count = 0;
+        order.status = "canceled".to_string();
+        Ok(CancelOrderResponse {
+            order: order.clone(),
+            reduced_by,
+        })
+    }
+
+    async fn amend_order_async(
+        &self,
+        _: &str,
+        _: &str,
+        _: &str,
+        _: i32,
+        _: Option<Price>,
+        _: &str,
+        _: &str,
+        _: &str,
+        _: Option<Price>,
+    ) -> Result<AmendOrderResponse, Error> {
+        Err(unsupported("amend_order_async"))
+    }
+
+    async fn decrease_order_async(
+        &self,
+        _: &str,
+        _: Option<i32>,
+        _: Option<i32>,
+    ) -> Result<DecreaseOrderResponse, Error> {
+        Err(unsupported("decrease_order_async"))
+    }
+}
+
+#[async_trait]
+impl Portfolio for FakeExchange {
+    async fn get_balance_async(&self) -> Result<Balance, Error> {
+        Ok(Balance {
+            balance: Cents(10_000),
+        })
+    }
+
+    async fn get_fills_async(
+        &self,
+        _: Option<&str>,
+        _: Option<&str>,
+        _: Option<DateTime<Utc>>,
+        _: Option<DateTime<Utc>>,
+        _: Option<i32>,
+        _: Option<&str>,
+    ) -> Result<GetFillsResponse, Error> {
+        Ok(GetFillsResponse {
+            cursor: String::new(),
+            fills: Vec::new(),
+        })
+    }
+
+    async fn get_orders_async(
+        &self,
+        _: Option<&str>,
+        _: Option<&str>,
+        _: Option<DateTime<Utc>>,
+        _: Option<DateTime<Utc>>,
+        _: Option<&str>,
+        _: Option<&str>,
+        _: Option<i32>,
+    ) -> Result<GetOrdersResponse, Error> {
+        Err(unsupported("get_orders_async"))
+    }
+
+    async fn get_positions_async(
+        &self,
+        _: Option<&str>,
+        _: Option<i32>,
+        _: Option<&str>,
+        _: Option<&str>,
+        _: Option<&str>,
+    ) -> Result<GetPositionsResponse, Error> {
+        Ok(GetPositionsResponse {
+            cursor: String::new(),
+            event_positions: Vec::new(),
+            market_positions: Vec::new(),
+        })
+    }
+
+    async fn get_settlements_async(
+        &self,
+        _: Option<i64>,
+        _: Option<String>,
+    ) -> Result<GetSettlementsResponse, Error> {
+        Err(unsupported("get_settlements_async"))
+    }
+}
+
+#[derive(Default)]
+struct JoinBid {
+    book_updates: usize,
+}
+
+impl Strategy for JoinBid {
+    fn on_book_update(&mut self, context: &mut StrategyContext, ticker: &str, _: &Orderbook) {
+        self.book_updates += 1;
+        if self.book_updates == 1 {
+            context.create_order(CreateOrderRequest {
+                action: "buy".to_string(),
+                count: 5,
+                side: "yes".to_string(),
+                ticker: ticker.to_string(),
+                type_: "limit".to_string(),
+                yes_price: Some(price(40)),
+                ..CreateOrderRequest::default()
+            });
+        }
+    }
+}
+
+#[tokio::test]
+async fn runtime_runs_against_an_in_memory_backend() {
+    let config = RuntimeConfig {
+        tickers: vec![MARKET_TICKER.to_string()],
+        poll_interval: Duration::from_millis(20),
+        timer_interval: Duration::from_millis(20),
+        exchange_status_interval: Duration::from_millis(20),
+        ..RuntimeConfig::default()
+    };
+    let exchange = FakeExchange::new();
+    let orders = exchange.orders.clone();
+    let runtime = StrategyRuntime::new(exchange, JoinBid::default(), config);
+    let strategy = runtime
+        .run(tokio::time::sleep(Duration::from_millis(150)))
+        .await
+        .unwrap();
+    assert!(strategy.book_updates > 1);
+
+    // The order went through the risk checks and was cancelled on shutdown.
+    let orders = orders.lock().unwrap();
+    assert_eq!(orders.len(), 1);
+    assert!(orders[0].client_order_id.starts_with("runtime-"));
+    assert_eq!(orders[0].status, "canceled");
+}
+
+#[tokio::test]
+async fn order_manager_tracks_orders_on_an_in_memory_backend() {
+    let exchange = FakeExchange::new();
+    let mut manager = OrderManager::new();
+    let request = CreateOrderRequest {
+        action: "buy".to_string(),
+        client_order_id: "client-order-1".to_string(),
+        count: 5,
+        side: "yes".to_string(),
+        ticker: MARKET_TICKER.to_string(),
+        type_: "limit".to_string(),
+        yes_price: Some(price(40)),
+        ..CreateOrderRequest::default()
+    };
+    let order = manager
+        .create_order_async(&exchange, &request)
+        .await
+        .unwrap();
+    assert_eq!(
+        manager.get("client-order-1").unwrap().state,
+        OrderState::Resting
+    );
+
+    exchange.cancel_order_async(&order.order_id).await.unwrap();
+    let drift = manager.poll_async(&exchange).await.unwrap();
+    assert_eq!(drift.len(), 1);
+    assert_eq!(drift[0].exchange_state, OrderState::Canceled);
+    assert_eq!(
+        manager.get("client-order-1").unwrap().state,
+        OrderState::Canceled
+    );
+    assert_eq!(exchange.orders()[0].status, "canceled");
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use kalshi_api::exchange::{
+    self, Announcement, ExchangeSchedule, ExchangeStatusWatcher, GetExchangeScheduleResponse,
+};
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use std::time::Duration;
+
+// Sessions run 08:00 to 03:00 the next morning Eastern time, Monday to Saturday, with a
+// maintenance window on Wednesday morning.
+const SCHEDULE_JSON: &str = r#"{
+    "schedule": {
+        "standard_hours": {
+            "monday": { "open_time": "08:00", "close_time": "03:00" },
+            "tuesday": { "open_time": "08:00", "close_time": "03:00" },
+            "wednesday": { "open_time": "08:00", "close_time": "03:00" },
+            "thursday": { "open_time": "08:00", "close_time": "03:00" },
+            "friday": { "open_time": "08:00", "close_time": "03:00" },
+            "saturday": { "open_time": "08:00", "close_time": "03:00" }
+        },
+        "maintenance_windows": [
+            {
+                "start_datetime": "2023-04-05T13:00:00Z",
+                "end_datetime": "2023-04-05T15:00:00Z"
+            }
+        ]
+    }
+}"#;
+
+fn schedule() -> ExchangeSchedule {
+    serde_json::from_str::<GetExchangeScheduleResponse>(SCHEDULE_JSON)
+        .unwrap()
+        .schedule
+}
+
+fn utc(text: &str) -> DateTime<Utc> {
+    text.parse().unwrap()
+}
+
+#[test]
+fn trading_hours_follow_eastern_time_and_maintenance() {
+    let schedule = schedule();
+    // Monday noon Eastern.
+    assert!(schedule.is_trading_open_at(utc("2023-04-03T16:00:00Z")));
+    // Monday's session runs until 03:00 on Tuesday.
+    assert!(schedule.is_trading_open_at(utc("2023-04-04T06:00:00Z")));
+    assert!(!schedule.is_trading_open_at(utc("2023-04-04T09:00:00Z")));
+    // Inside the Wednesday maintenance window.
+    assert!(!schedule.is_trading_open_at(utc("2023-04-05T13:30:00Z")));
+    // Saturday's session carries into Sunday morning, but Sunday has no session of its own.
+    assert!(schedule.is_trading_open_at(utc("2023-04-09T06:00:00Z")));
+    assert!(!schedule.is_trading_open_at(utc("2023-04-09T14:00:00Z")));
+}
+
+#[test]
+fn next_open_skips_closed_hours_and_maintenance() {
+    let schedule = schedule();
+    let open = utc("2023-04-03T16:00:00Z");
+    assert_eq!(schedule.next_open_after(open), Some(open));
+    assert_eq!(
+        schedule.next_open_after(utc("2023-04-04T09:00:00Z")),
+        Some(utc("2023-04-04T12:00:00Z"))
+    );
+    assert_eq!(
+        schedule.next_open_after(utc("2023-04-05T13:30:00Z")),
+        Some(utc("2023-04-05T15:00:00Z"))
+    );
+    assert_eq!(
+        schedule.next_open_after(utc("2023-04-09T14:00:00Z")),
+        Some(utc("2023-04-10T12:00:00Z"))
+    );
+
+    let never_open = ExchangeSchedule {
+        standard_hours: Default::default(),
+        maintenance_windows: Vec::new(),
+    };
+    assert_eq!(never_open.next_open_after(open), None);
+}
+
+#[tokio::test]
+async fn fetches_schedule_and_does_not_sleep_while_open() {
+    let server = MockServer::start(MockState::default()).await.unwrap();
+    let client = reqwest::Client::new();
+    server
+        .run(async {
+            let schedule = exchange::get_exchange_schedule_async(&client)
+                .await
+                .unwrap();
+            assert!(schedule.is_trading_open_at(Utc::now()));
+            exchange::sleep_until_open_async(&client).await.unwrap();
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn fetches_announcements() {
+    let mut state = MockState::default();
+    state.announcements.push(Announcement {
+        delivery_time: fixtures::fixture_time(),
+        message: "Scheduled maintenance tonight".to_string(),
+        status: "active".to_string(),
+        type_: "warning".to_string(),
+    });
+    let server = MockServer::start(state).await.unwrap();
+    let client = reqwest::Client::new();
+    let announcements = server
+        .run(exchange::get_exchange_announcements_async(&client))
+        .await
+        .unwrap()
+        .announcements;
+    assert_eq!(announcements[0].type_, "warning");
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn watcher_reports_trading_halts() {
+    let server = MockServer::start(MockState::default()).await.unwrap();
+    let mut watcher = server
+        .run(async {
+            ExchangeStatusWatcher::spawn(reqwest::Client::new(), Duration::from_millis(20))
+        })
+        .await;
+
+    let first = watcher.next_change().await.unwrap();
+    assert!(first.previous.is_none());
+    assert!(first.trading_resumed());
+
+    server.state().exchange_status.trading_active = false;
+    let halt = watcher.next_change().await.unwrap();
+    assert!(halt.trading_halted());
+    assert!(halt.current.exchange_active);
+
+    watcher.stop();
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn dropping_the_status_watcher_stops_polling() {
+    let server = MockServer::start(MockState::default()).await.unwrap();
+    let mut watcher = server
+        .run(async {
+            ExchangeStatusWatcher::spawn(reqwest::Client::new(), Duration::from_millis(20))
+        })
+        .await;
+    watcher.next_change().await.unwrap();
+    drop(watcher);
+
+    // Let an in-flight poll finish before counting.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let polls = server.state().requests().len();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(server.state().requests().len(), polls);
+    server.stop().await.unwrap();
+}
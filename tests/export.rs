@@ -0,0 +1,121 @@
+use chrono::Duration;
+use kalshi_api::client::KalshiClient;
+use kalshi_api::export::{tax_lots, AccountHistory, ExportFormat};
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::portfolio::Fill;
+
+const TICKER: &str = "INXD-23APR03-B4100";
+
+fn fill(trade_id: &str, hours: i64, action: &str, side: &str, yes_price: i64, count: i32) -> Fill {
+    let mut fill = fixtures::fill(trade_id, "order-1", TICKER, side, price(yes_price), count);
+    fill.action = action.to_string();
+    fill.created_time = fixtures::fixture_time() + Duration::hours(hours);
+    fill
+}
+
+// Buys yes twice, sells most of it, flips to no and holds the no contracts to settlement.
+fn history() -> AccountHistory {
+    let mut order = fixtures::order("order-1", TICKER, "yes", price(40), 10);
+    order.taker_fees = Cents(12);
+    order.maker_fill_count = 4;
+    order.maker_fill_cost = Cents(200);
+    order.maker_fees = Cents(3);
+    AccountHistory {
+        fills: vec![
+            fill("t1", 0, "buy", "yes", 40, 10),
+            fill("t2", 1, "buy", "yes", 50, 4),
+            fill("t3", 2, "sell", "yes", 60, 12),
+            fill("t4", 3, "buy", "no", 70, 5),
+        ],
+        orders: vec![order],
+        settlements: vec![fixtures::settlement(TICKER, "no", 0, Cents(300))],
+    }
+}
+
+#[test]
+fn matches_lots_first_in_first_out() {
+    let lots = history().tax_lots();
+    let closed: Vec<(&str, i64, Cents, Cents, Cents)> = lots
+        .closed
+        .iter()
+        .map(|lot| (lot.side, lot.count, lot.cost_basis, lot.proceeds, lot.gain))
+        .collect();
+    assert_eq!(
+        closed,
+        vec![
+            ("yes", 10, Cents(400), Cents(600), Cents(200)),
+            ("yes", 2, Cents(100), Cents(120), Cents(20)),
+            // Buying no at 30 sells the remaining yes contracts at 70.
+            ("yes", 2, Cents(100), Cents(140), Cents(40)),
+            ("no", 3, Cents(90), Cents(300), Cents(210)),
+        ]
+    );
+    assert!(lots.open.is_empty());
+
+    let summary = history().lot_summary();
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].closed_count, 17);
+    assert_eq!(summary[0].realized_gain, Cents(470));
+    assert_eq!(summary[0].fees, Cents(15));
+    assert_eq!(summary[0].open_count, 0);
+
+    // Without the settlement the no contracts are still open.
+    let unsettled = tax_lots::tax_lots(&history().fills, &[]);
+    assert_eq!(unsettled.open.len(), 1);
+    assert_eq!(unsettled.open[0].side, "no");
+    assert_eq!(unsettled.open[0].cost_basis(), Cents(90));
+}
+
+#[tokio::test]
+async fn exports_history_in_range_to_files() {
+    let mut state = MockState::default();
+    let history = history();
+    state.fills = history.fills.clone();
+    // Outside the range.
+    state.fills.push(fill("t0", -48, "buy", "yes", 20, 1));
+    state.orders = history.orders.clone();
+    state.settlements = history.settlements.clone();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let backend = KalshiClient::new(reqwest::Client::new(), token);
+
+    let from = fixtures::fixture_time() - Duration::hours(1);
+    let fetched = server
+        .run(AccountHistory::fetch_async(&backend, Some(from), None))
+        .await
+        .unwrap();
+    server.stop().await.unwrap();
+    assert_eq!(fetched.fills.len(), 4);
+    assert_eq!(fetched.orders.len(), 1);
+    assert_eq!(fetched.settlements.len(), 1);
+
+    let dir = std::env::temp_dir().join(format!("kalshi-export-{}", std::process::id()));
+    let paths = fetched.write_dir(&dir, ExportFormat::Csv).unwrap();
+    assert_eq!(paths.len(), 5);
+    let fills = std::fs::read_to_string(dir.join("fills.csv")).unwrap();
+    let mut lines = fills.lines();
+    assert_eq!(
+        lines.next(),
+        Some("time,trade_id,order_id,ticker,action,side,count,price,notional,is_taker")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("2023-04-01T12:00:00.000Z,t1,order-1,INXD-23APR03-B4100,buy,yes,10,0.40,4.00,true")
+    );
+    let orders = std::fs::read_to_string(dir.join("orders.csv")).unwrap();
+    let mut lines = orders.lines();
+    assert!(lines
+        .next()
+        .unwrap()
+        .ends_with(",taker_fill_cost,taker_fees,maker_fill_cost,maker_fees"));
+    assert!(lines.next().unwrap().ends_with(",0.00,0.12,2.00,0.03"));
+
+    fetched.write_dir(&dir, ExportFormat::JsonLines).unwrap();
+    let summary = std::fs::read_to_string(dir.join("lot_summary.jsonl")).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(summary.trim()).unwrap();
+    assert_eq!(summary["realized_gain"], "4.70");
+    assert_eq!(summary["fees"], "0.15");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,193 @@
+use kalshi_api::fees::{self, FeeSchedule, OrderCostEstimate};
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::paper::PaperTrading;
+use kalshi_api::portfolio::CreateOrderRequest;
+
+const MARKET_TICKER: &str = "INXD-23APR03-B4100";
+
+#[test]
+fn rounds_fees_up_to_the_next_cent() {
+    // 0.07 * 100 * 0.50 * 0.50 dollars is exactly $1.75.
+    assert_eq!(fees::fee(0.07, 100, price(50)), Cents(175));
+    // 0.07 * 1 * 0.50 * 0.50 dollars is 1.75 cents.
+    assert_eq!(fees::fee(0.07, 1, price(50)), Cents(2));
+    // The same fee applies to either side of the trade.
+    assert_eq!(fees::fee(0.07, 10, price(1)), Cents(1));
+    assert_eq!(fees::fee(0.07, 10, price(99)), Cents(1));
+    assert_eq!(fees::fee(0.0, 10, price(50)), Cents::ZERO);
+}
+
+#[test]
+fn applies_maker_rates_and_series_multipliers() {
+    let schedule = FeeSchedule {
+        maker_rate: 0.0175,
+        ..FeeSchedule::default()
+    }
+    .with_series_multiplier("INXD", 0.5);
+
+    assert_eq!(fees::series_ticker(MARKET_TICKER), "INXD");
+    assert_eq!(schedule.rate(MARKET_TICKER, true), 0.035);
+    assert_eq!(schedule.taker_fee(MARKET_TICKER, 100, price(50)), Cents(88));
+    assert_eq!(schedule.maker_fee(MARKET_TICKER, 100, price(50)), Cents(22));
+    assert_eq!(
+        schedule.taker_fee("FED-23MAY-T5", 100, price(50)),
+        Cents(175)
+    );
+    assert_eq!(
+        FeeSchedule::free().taker_fee(MARKET_TICKER, 100, price(50)),
+        Cents::ZERO
+    );
+}
+
+#[test]
+fn estimates_all_in_cost_of_orders() {
+    let mut request = CreateOrderRequest {
+        action: "buy".to_string(),
+        count: 10,
+        side: "no".to_string(),
+        ticker: MARKET_TICKER.to_string(),
+        type_: "limit".to_string(),
+        yes_price: Some(price(45)),
+        ..CreateOrderRequest::default()
+    };
+    let schedule = FeeSchedule::default();
+    assert_eq!(
+        schedule.estimate_order(&request),
+        Some(OrderCostEstimate {
+            price: price(55),
+            count: 10,
+            notional: Cents(550),
+            fees: Cents(18),
+            all_in: Cents(568),
+        })
+    );
+
+    request.action = "sell".to_string();
+    assert_eq!(
+        schedule.estimate_order(&request).unwrap().all_in,
+        Cents(532)
+    );
+
+    request.yes_price = None;
+    assert_eq!(schedule.estimate_order(&request), None);
+}
+
+#[tokio::test]
+async fn paper_trading_charges_taker_fees() {
+    let mut state = MockState::default();
+    state.events.push(fixtures::event("INXD-23APR03", "INXD"));
+    state
+        .markets
+        .push(fixtures::market(MARKET_TICKER, "INXD-23APR03", 40, 45));
+    state.orderbooks.insert(
+        MARKET_TICKER.to_string(),
+        fixtures::orderbook(&[(40, 10)], &[(55, 20)]),
+    );
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let client = reqwest::Client::new();
+    let paper = PaperTrading::new(Cents(10_000));
+
+    server
+        .run(async {
+            let order = paper
+                .create_order_async(
+                    &client,
+                    &token,
+                    "buy",
+                    None,
+                    "paper-1",
+                    10,
+                    None,
+                    None,
+                    None,
+                    "yes",
+                    MARKET_TICKER,
+                    "limit",
+                    Some(price(45)),
+                )
+                .await
+                .unwrap()
+                .order;
+            assert_eq!(order.status, "executed");
+            assert_eq!(order.taker_fees, Cents(18));
+
+            let balance = paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(10_000 - 450 - 18));
+            let positions = paper
+                .get_positions_async(&client, &token, None, None, None, None, None)
+                .await
+                .unwrap();
+            assert_eq!(positions.market_positions[0].fees_paid, Cents(18));
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn paper_trading_charges_maker_fees() {
+    let mut state = MockState::default();
+    state.events.push(fixtures::event("INXD-23APR03", "INXD"));
+    state
+        .markets
+        .push(fixtures::market(MARKET_TICKER, "INXD-23APR03", 40, 45));
+    state.orderbooks.insert(
+        MARKET_TICKER.to_string(),
+        fixtures::orderbook(&[(40, 10)], &[(55, 20)]),
+    );
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let client = reqwest::Client::new();
+    let paper = PaperTrading::with_fee_schedule(
+        Cents(10_000),
+        FeeSchedule {
+            maker_rate: 0.0175,
+            ..FeeSchedule::default()
+        },
+    );
+
+    server
+        .run(async {
+            let order = paper
+                .create_order_async(
+                    &client,
+                    &token,
+                    "buy",
+                    None,
+                    "paper-1",
+                    100,
+                    None,
+                    None,
+                    None,
+                    "yes",
+                    MARKET_TICKER,
+                    "limit",
+                    Some(price(42)),
+                )
+                .await
+                .unwrap()
+                .order;
+            assert_eq!(order.status, "resting");
+
+            // Someone offers at 42 and the order fills as a maker.
+            server.state().orderbooks.insert(
+                MARKET_TICKER.to_string(),
+                fixtures::orderbook(&[(40, 10)], &[(58, 100)]),
+            );
+            let balance = paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(10_000 - 4_200 - 43));
+            let order = paper
+                .get_order(&client, &token, &order.order_id)
+                .await
+                .unwrap()
+                .order;
+            assert_eq!(order.maker_fill_count, 100);
+            // 0.0175 * 100 * 0.42 * 0.58 dollars rounds up to 43 cents.
+            assert_eq!(order.maker_fees, Cents(43));
+            assert_eq!(order.taker_fees, Cents::ZERO);
+        })
+        .await;
+    server.stop().await.unwrap();
+}
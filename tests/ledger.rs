@@ -0,0 +1,184 @@
+use chrono::Duration;
+use kalshi_api::client::KalshiClient;
+use kalshi_api::ledger::Ledger;
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::portfolio::Fill;
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+const TICKER: &str = "INXD-23APR03-B4100";
+const OTHER_TICKER: &str = "INXD-23APR03-B4125";
+const FED_TICKER: &str = "FED-23MAY-T5";
+
+fn fill(
+    trade_id: &str,
+    ticker: &str,
+    action: &str,
+    side: &str,
+    yes_price: i64,
+    count: i32,
+    minutes: i64,
+) -> Fill {
+    let mut fill = fixtures::fill(trade_id, "order-1", ticker, side, price(yes_price), count);
+    fill.action = action.to_string();
+    fill.created_time = fixtures::fixture_time() + Duration::minutes(minutes);
+    fill
+}
+
+#[test]
+fn replays_fills_and_settlements_in_order() {
+    let fills = vec![
+        // Out of order on purpose; replay sorts by time.
+        fill("trade-2", TICKER, "sell", "yes", 50, 4, 2),
+        fill("trade-1", TICKER, "buy", "yes", 40, 10, 1),
+        // Buying no at 55 closes yes contracts at 45.
+        fill("trade-3", TICKER, "buy", "no", 45, 3, 3),
+        fill("trade-3", TICKER, "buy", "no", 45, 3, 3),
+    ];
+    let settlements = vec![fixtures::settlement(TICKER, "yes", 3, Cents(300))];
+
+    let ledger = Ledger::replay(&fills[..3], &[]);
+    let market = ledger.market(TICKER).unwrap();
+    assert_eq!(market.position, 3);
+    assert_eq!(market.realized_pnl, Cents(4 * 10 + 3 * 5));
+    assert_eq!(market.open_cost, Cents(120));
+    assert_eq!(market.average_cost(), Some(40.0));
+
+    let ledger = Ledger::replay(&fills, &settlements);
+    let market = ledger.market(TICKER).unwrap();
+    assert_eq!(market.position, 0);
+    assert!(market.settled);
+    assert_eq!(market.realized_pnl, Cents(55 + 300 - 120));
+    assert_eq!(ledger.total_realized_pnl(), Cents(235));
+    assert_eq!(market.average_cost(), None);
+}
+
+#[test]
+fn tracks_no_positions_and_marks() {
+    let mut ledger = Ledger::new();
+    ledger.apply_fill(&fill("trade-1", TICKER, "buy", "no", 30, 5, 0));
+    let market = ledger.market(TICKER).unwrap();
+    assert_eq!(market.position, -5);
+    assert_eq!(market.average_cost(), Some(70.0));
+    assert_eq!(market.total_cost(), Cents(350));
+    assert_eq!(ledger.unrealized_pnl(TICKER), None);
+
+    ledger.mark(&fixtures::market(TICKER, EVENT_TICKER, 20, 25));
+    // Buying the yes contracts back at 25 closes the short for 5 cents each.
+    assert_eq!(ledger.unrealized_pnl(TICKER), Some(Cents(25)));
+    assert_eq!(ledger.total_unrealized_pnl(), Cents(25));
+
+    // A no settlement pays 100 cents a contract on the 350 paid.
+    ledger.apply_settlement(&fixtures::settlement(TICKER, "no", 0, Cents(500)));
+    assert_eq!(ledger.market(TICKER).unwrap().realized_pnl, Cents(150));
+    assert_eq!(ledger.unrealized_pnl(TICKER), Some(Cents::ZERO));
+}
+
+#[test]
+fn groups_unmarked_markets_into_events() {
+    let mut ledger = Ledger::new();
+    ledger.apply_fill(&fill("trade-1", TICKER, "buy", "yes", 40, 2, 0));
+    ledger.apply_fill(&fill("trade-2", OTHER_TICKER, "buy", "yes", 10, 5, 0));
+    ledger.apply_fill(&fill("trade-3", FED_TICKER, "buy", "no", 80, 1, 0));
+    ledger.apply_fee(TICKER, Cents(2));
+    ledger.apply_fee(OTHER_TICKER, Cents(1));
+
+    let events = ledger.events();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_ticker, "FED-23MAY");
+    assert_eq!(events[0].position, -1);
+    assert_eq!(events[1].event_ticker, EVENT_TICKER);
+    assert_eq!(events[1].tickers, [TICKER, OTHER_TICKER]);
+    assert_eq!(events[1].position, 7);
+    assert_eq!(events[1].total_cost, Cents(130));
+    assert_eq!(events[1].fees, Cents(3));
+    assert_eq!(ledger.total_fees(), Cents(3));
+
+    // Marking a market uses the event it reports.
+    ledger.mark(&fixtures::market(FED_TICKER, "FED-23MAY-X", 10, 15));
+    let tickers: Vec<String> = ledger
+        .events()
+        .into_iter()
+        .map(|event| event.event_ticker)
+        .collect();
+    assert_eq!(tickers, ["FED-23MAY-X", EVENT_TICKER]);
+}
+
+#[test]
+fn reconciles_positions_against_the_exchange() {
+    let mut ledger = Ledger::new();
+    ledger.apply_fill(&fill("trade-1", TICKER, "buy", "yes", 40, 2, 0));
+    ledger.apply_fill(&fill("trade-2", OTHER_TICKER, "buy", "yes", 10, 5, 0));
+
+    let positions = vec![
+        fixtures::market_position(TICKER, 2, Cents(80)),
+        fixtures::market_position(OTHER_TICKER, 5, Cents(50)),
+    ];
+    assert!(ledger.reconcile(&positions).is_empty());
+
+    let positions = vec![
+        fixtures::market_position(TICKER, 3, Cents(120)),
+        fixtures::market_position(FED_TICKER, -1, Cents(80)),
+    ];
+    let mut mismatches = ledger.reconcile(&positions);
+    mismatches.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+    let summary: Vec<(&str, i64, i64)> = mismatches
+        .iter()
+        .map(|m| (m.ticker.as_str(), m.local_position, m.exchange_position))
+        .collect();
+    assert_eq!(
+        summary,
+        [(FED_TICKER, 0, -1), (TICKER, 2, 3), (OTHER_TICKER, 5, 0)]
+    );
+}
+
+#[tokio::test]
+async fn loads_history_and_fees_from_the_exchange() {
+    let mut state = MockState::default();
+    for i in 0..3 {
+        state.fills.push(fill(
+            &format!("trade-{}", i),
+            TICKER,
+            "buy",
+            "yes",
+            40,
+            2,
+            i,
+        ));
+    }
+    state
+        .fills
+        .push(fill("trade-3", OTHER_TICKER, "buy", "yes", 10, 5, 3));
+    state
+        .settlements
+        .push(fixtures::settlement(OTHER_TICKER, "no", 0, Cents::ZERO));
+    let mut order = fixtures::order("order-1", TICKER, "yes", price(40), 6);
+    order.taker_fees = Cents(9);
+    state.orders.push(order);
+    let mut order = fixtures::order("order-2", OTHER_TICKER, "yes", price(10), 5);
+    order.maker_fees = Cents(2);
+    state.orders.push(order);
+    state
+        .market_positions
+        .push(fixtures::market_position(TICKER, 6, Cents(240)));
+
+    let server = MockServer::start(state).await.unwrap();
+    let token = server.state().token.clone();
+    let backend = KalshiClient::new(reqwest::Client::new(), token.clone());
+    server
+        .run(async {
+            let ledger = Ledger::load_async(&backend).await.unwrap();
+            let market = ledger.market(TICKER).unwrap();
+            assert_eq!(market.position, 6);
+            assert_eq!(market.fees, Cents(9));
+            let other = ledger.market(OTHER_TICKER).unwrap();
+            assert!(other.settled);
+            assert_eq!(other.fees, Cents(2));
+            assert_eq!(other.realized_pnl, Cents(-50));
+            assert_eq!(ledger.total_fees(), Cents(11));
+            assert!(ledger.reconcile_async(&backend).await.unwrap().is_empty());
+        })
+        .await;
+    server.stop().await.unwrap();
+}
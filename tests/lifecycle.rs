@@ -0,0 +1,254 @@
+use chrono::{Duration, Utc};
+use kalshi_api::client::KalshiClient;
+use kalshi_api::lifecycle::{LifecycleConfig, LifecycleEvent, LifecycleTracker, LifecycleWatcher};
+use kalshi_api::mock::{fixtures, MockError, MockServer, MockState};
+use kalshi_api::money::Cents;
+use std::time::Duration as StdDuration;
+
+const TICKER: &str = "INXD-23APR03-B4100";
+
+fn kinds(events: &[LifecycleEvent]) -> Vec<&'static str> {
+    events
+        .iter()
+        .map(|event| match event {
+            LifecycleEvent::Opened { .. } => "opened",
+            LifecycleEvent::Closed { .. } => "closed",
+            LifecycleEvent::Determined { .. } => "determined",
+            LifecycleEvent::Settled(_) => "settled",
+        })
+        .collect()
+}
+
+#[test]
+fn tracks_transitions_and_polls_faster_near_them() {
+    let config = LifecycleConfig::default();
+    let now = Utc::now();
+    let mut market = fixtures::market(TICKER, "INXD-23APR03", 40, 45);
+    market.open_time = now + Duration::hours(1);
+    market.close_time = now + Duration::hours(2);
+    let mut tracker = LifecycleTracker::new();
+
+    assert!(tracker.observe_market(&market, now).is_empty());
+    assert_eq!(
+        tracker.next_poll_delay(now, &config),
+        StdDuration::from_secs(60)
+    );
+    // 30 seconds before the window ahead of the open starts.
+    let soon = market.open_time - Duration::seconds(330);
+    assert_eq!(
+        tracker.next_poll_delay(soon, &config),
+        StdDuration::from_secs(30)
+    );
+    let sooner = market.open_time - Duration::seconds(120);
+    assert_eq!(
+        tracker.next_poll_delay(sooner, &config),
+        StdDuration::from_secs(5)
+    );
+
+    let opened = market.open_time + Duration::seconds(1);
+    assert_eq!(kinds(&tracker.observe_market(&market, opened)), ["opened"]);
+    assert!(tracker.observe_market(&market, opened).is_empty());
+
+    // The market closes early.
+    market.close_time = opened + Duration::minutes(10);
+    let closed = market.close_time + Duration::seconds(1);
+    assert_eq!(kinds(&tracker.observe_market(&market, closed)), ["closed"]);
+    assert_eq!(
+        tracker.next_poll_delay(closed, &config),
+        StdDuration::from_secs(5)
+    );
+    let later = closed + Duration::hours(1);
+    assert_eq!(
+        tracker.next_poll_delay(later, &config),
+        StdDuration::from_secs(60)
+    );
+
+    market.result = "yes".to_string();
+    assert!(!tracker.awaiting_settlement());
+    assert_eq!(
+        kinds(&tracker.observe_market(&market, later)),
+        ["determined"]
+    );
+    assert!(tracker.awaiting_settlement());
+    assert_eq!(tracker.unchecked_positions(), [TICKER]);
+    // With a position, settlements are polled however long the payout takes.
+    tracker.observe_position(TICKER, 10);
+    assert!(tracker.unchecked_positions().is_empty());
+    assert!(tracker.awaiting_settlement());
+    assert_eq!(
+        tracker.next_poll_delay(later + Duration::days(1), &config),
+        StdDuration::from_secs(60)
+    );
+
+    let settlement = fixtures::settlement(TICKER, "yes", 10, Cents(1_000));
+    assert!(tracker
+        .observe_settlement(&fixtures::settlement("FED-23MAY-T5", "no", 1, Cents::ZERO))
+        .is_none());
+    assert_eq!(
+        tracker.observe_settlement(&settlement).unwrap().ticker(),
+        TICKER
+    );
+    assert!(tracker.observe_settlement(&settlement).is_none());
+    assert!(!tracker.awaiting_settlement());
+}
+
+#[test]
+fn stops_awaiting_settlement_without_a_position() {
+    let mut market = fixtures::market(TICKER, "INXD-23APR03", 40, 45);
+    market.result = "no".to_string();
+    let mut tracker = LifecycleTracker::new();
+    tracker.observe_market(&market, Utc::now());
+    assert!(tracker.awaiting_settlement());
+
+    tracker.observe_position(TICKER, 0);
+    assert!(!tracker.awaiting_settlement());
+}
+
+#[tokio::test]
+async fn watcher_reports_a_settled_market_in_order() {
+    let mut state = MockState::default();
+    let mut market = fixtures::market(TICKER, "INXD-23APR03", 40, 45);
+    market.result = "yes".to_string();
+    state.markets.push(market);
+    state
+        .market_positions
+        .push(fixtures::market_position(TICKER, 10, Cents(400)));
+    state
+        .settlements
+        .push(fixtures::settlement(TICKER, "yes", 10, Cents(1_000)));
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let config = LifecycleConfig {
+        tickers: vec![TICKER.to_string()],
+        poll_interval: StdDuration::from_millis(20),
+        fast_poll_interval: StdDuration::from_millis(20),
+        ..LifecycleConfig::default()
+    };
+    let mut watcher = server
+        .run(async {
+            LifecycleWatcher::spawn(KalshiClient::new(reqwest::Client::new(), token), config)
+        })
+        .await;
+
+    let mut events = Vec::new();
+    for _ in 0..4 {
+        events.push(watcher.next_event().await.unwrap());
+    }
+    assert_eq!(
+        kinds(&events),
+        ["opened", "closed", "determined", "settled"]
+    );
+    match &events[3] {
+        LifecycleEvent::Settled(settlement) => assert_eq!(settlement.revenue, Cents(1_000)),
+        event => panic!("Expected a settlement, got {:?}", event),
+    }
+
+    watcher.stop();
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn watcher_keeps_events_when_other_requests_fail() {
+    let mut state = MockState::default();
+    let mut market = fixtures::market(TICKER, "INXD-23APR03", 40, 45);
+    market.result = "yes".to_string();
+    state.markets.push(market);
+    state
+        .market_positions
+        .push(fixtures::market_position(TICKER, 10, Cents(400)));
+    state
+        .settlements
+        .push(fixtures::settlement(TICKER, "yes", 10, Cents(1_000)));
+    state.fail_next("portfolio/settlements", MockError::InternalServerError);
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let config = LifecycleConfig {
+        // The first market doesn't exist, so every poll of it fails.
+        tickers: vec!["FED-23MAY-T5".to_string(), TICKER.to_string()],
+        poll_interval: StdDuration::from_millis(20),
+        fast_poll_interval: StdDuration::from_millis(20),
+        ..LifecycleConfig::default()
+    };
+    let mut watcher = server
+        .run(async {
+            LifecycleWatcher::spawn(KalshiClient::new(reqwest::Client::new(), token), config)
+        })
+        .await;
+
+    let mut events = Vec::new();
+    for _ in 0..4 {
+        events.push(watcher.next_event().await.unwrap());
+    }
+    assert_eq!(
+        kinds(&events),
+        ["opened", "closed", "determined", "settled"]
+    );
+
+    drop(watcher);
+    let state = server.stop().await.unwrap();
+    let settlement_polls = state
+        .requests()
+        .iter()
+        .filter(|request| request.path.contains("portfolio/settlements"))
+        .count();
+    assert_eq!(settlement_polls, 2);
+}
+
+#[tokio::test]
+async fn watcher_polls_settlements_until_a_held_market_settles() {
+    let mut state = MockState::default();
+    let mut market = fixtures::market(TICKER, "INXD-23APR03", 40, 45);
+    market.result = "yes".to_string();
+    state.markets.push(market);
+    state
+        .market_positions
+        .push(fixtures::market_position(TICKER, 10, Cents(400)));
+    // More than a page of older settlements, newest first.
+    for i in 0..150 {
+        let mut settlement = fixtures::settlement("FED-23MAY-T5", "no", 1, Cents::ZERO);
+        settlement.settled_time = fixtures::fixture_time() - Duration::minutes(i);
+        state.settlements.push(settlement);
+    }
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let config = LifecycleConfig {
+        tickers: vec![TICKER.to_string()],
+        poll_interval: StdDuration::from_millis(20),
+        fast_poll_interval: StdDuration::from_millis(20),
+        ..LifecycleConfig::default()
+    };
+    let mut watcher = server
+        .run(async {
+            LifecycleWatcher::spawn(KalshiClient::new(reqwest::Client::new(), token), config)
+        })
+        .await;
+
+    let mut events = Vec::new();
+    for _ in 0..3 {
+        events.push(watcher.next_event().await.unwrap());
+    }
+    assert_eq!(kinds(&events), ["opened", "closed", "determined"]);
+    // The payout arrives well after the result.
+    tokio::time::sleep(StdDuration::from_millis(200)).await;
+    server
+        .state()
+        .settlements
+        .insert(0, fixtures::settlement(TICKER, "yes", 10, Cents(1_000)));
+    let event = watcher.next_event().await.unwrap();
+    assert_eq!(kinds(&[event]), ["settled"]);
+
+    drop(watcher);
+    let state = server.stop().await.unwrap();
+    let settlement_requests: Vec<_> = state
+        .requests()
+        .iter()
+        .filter(|request| request.path.contains("portfolio/settlements"))
+        .collect();
+    assert!(settlement_requests.len() > 3);
+    // Only the first poll pages past settlements it has already seen.
+    let later_pages = settlement_requests
+        .iter()
+        .filter(|request| request.query.contains_key("cursor"))
+        .count();
+    assert_eq!(later_pages, 1);
+}
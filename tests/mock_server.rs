@@ -0,0 +1,567 @@
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockError, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::portfolio::{CancelScope, CreateOrderRequest};
+use kalshi_api::{auth, environment, exchange, market, portfolio};
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+const SERIES_TICKER: &str = "INXD";
+const MARKET_TICKER: &str = "INXD-23APR03-B4100";
+const OTHER_MARKET_TICKER: &str = "INXD-23APR03-B4125";
+
+fn scripted_state() -> MockState {
+    let mut state = MockState::default();
+    state.email = Some("user@example.com".to_string());
+    state.password = Some("hunter2".to_string());
+    state.balance = Cents(10_000);
+    state
+        .events
+        .push(fixtures::event(EVENT_TICKER, SERIES_TICKER));
+    state
+        .markets
+        .push(fixtures::market(MARKET_TICKER, EVENT_TICKER, 40, 42));
+    state
+        .markets
+        .push(fixtures::market(OTHER_MARKET_TICKER, EVENT_TICKER, 10, 13));
+    state.orderbooks.insert(
+        MARKET_TICKER.to_string(),
+        fixtures::orderbook(&[(40, 10), (39, 25)], &[(58, 5)]),
+    );
+    state.trades.push(fixtures::trade(
+        "trade-0",
+        MARKET_TICKER,
+        "yes",
+        price(42),
+        7,
+    ));
+    state.candlesticks.insert(
+        MARKET_TICKER.to_string(),
+        vec![fixtures::candlestick(fixtures::fixture_time(), 41, 120)],
+    );
+    state.fills.push(fixtures::fill(
+        "trade-1",
+        "order-1",
+        MARKET_TICKER,
+        "yes",
+        price(41),
+        3,
+    ));
+    state
+        .market_positions
+        .push(fixtures::market_position(MARKET_TICKER, 3, Cents(123)));
+    state.settlements.push(fixtures::settlement(
+        OTHER_MARKET_TICKER,
+        "no",
+        0,
+        Cents::ZERO,
+    ));
+    state
+}
+
+fn set_credentials() {
+    std::env::set_var("EMAIL", "user@example.com");
+    std::env::set_var("PASS", "hunter2");
+}
+
+async fn login(client: &reqwest::Client) -> String {
+    set_credentials();
+    auth::login(client).await.unwrap()
+}
+
+fn limit_order(client_order_id: &str, yes_price: i64) -> CreateOrderRequest {
+    CreateOrderRequest {
+        action: "buy".to_string(),
+        client_order_id: client_order_id.to_string(),
+        count: 5,
+        side: "yes".to_string(),
+        ticker: MARKET_TICKER.to_string(),
+        type_: "limit".to_string(),
+        yes_price: Some(price(yes_price)),
+        ..CreateOrderRequest::default()
+    }
+}
+
+#[tokio::test]
+async fn auth_and_market_data() {
+    let server = MockServer::start(scripted_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    server
+        .run(async {
+            let token = login(&client).await;
+            assert_eq!(token, "mock-token");
+
+            let status = exchange::get_exchange_status_async(&client).await.unwrap();
+            assert!(status.exchange_active);
+
+            let event = market::get_event_async(&client, EVENT_TICKER, &token)
+                .await
+                .unwrap();
+            assert_eq!(event.markets.len(), 2);
+
+            let events =
+                market::get_events_async(&client, &token, None, None, None, Some(SERIES_TICKER))
+                    .await
+                    .unwrap();
+            assert_eq!(events.events[0].event_ticker, EVENT_TICKER);
+
+            let market = market::get_market_async(&client, MARKET_TICKER, &token)
+                .await
+                .unwrap()
+                .market;
+            assert_eq!(market.yes_ask, Cents(42));
+
+            let page = market::get_markets_async(
+                &client,
+                &token,
+                Some(1),
+                None,
+                Some(EVENT_TICKER),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(page.markets.len(), 1);
+            let next_page = market::get_markets_async(
+                &client,
+                &token,
+                Some(1),
+                Some(&page.cursor),
+                Some(EVENT_TICKER),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(next_page.markets[0].ticker, OTHER_MARKET_TICKER);
+            assert!(next_page.cursor.is_empty());
+
+            let orderbook = market::get_market_orderbook_async(&client, MARKET_TICKER, 2, &token)
+                .await
+                .unwrap()
+                .orderbook;
+            assert_eq!(orderbook.yes[0], vec![40, 10]);
+
+            let trades = market::get_trades_async(
+                &client,
+                &token,
+                Some(MARKET_TICKER),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(trades.trades[0].count, 7);
+
+            let candlesticks = market::get_market_candlesticks_async(
+                &client,
+                &token,
+                SERIES_TICKER,
+                MARKET_TICKER,
+                fixtures::fixture_time() - chrono::Duration::hours(1),
+                fixtures::fixture_time(),
+                60,
+            )
+            .await
+            .unwrap();
+            assert_eq!(candlesticks.candlesticks[0].volume, 120);
+
+            auth::logout(client.clone(), &token).await.unwrap();
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn portfolio_reads() {
+    let server = MockServer::start(scripted_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    server
+        .run(async {
+            let token = login(&client).await;
+
+            let balance = portfolio::get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(10_000));
+
+            let fills = portfolio::get_fills_async(
+                &client,
+                &token,
+                Some(MARKET_TICKER),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(fills.fills[0].yes_price, price(41));
+
+            let positions = portfolio::get_positions_async(
+                &client,
+                &token,
+                None,
+                None,
+                None,
+                None,
+                Some(EVENT_TICKER),
+            )
+            .await
+            .unwrap();
+            assert_eq!(positions.market_positions[0].position, 3);
+
+            let settlements = portfolio::get_settlements_async(&client, &token, None, None)
+                .await
+                .unwrap();
+            assert_eq!(settlements.settlements[0].market_result, "no");
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn order_lifecycle() {
+    let server = MockServer::start(scripted_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    server
+        .run(async {
+            let token = login(&client).await;
+
+            let order = portfolio::create_order_async(
+                &client,
+                &token,
+                "buy",
+                None,
+                "client-1",
+                10,
+                None,
+                None,
+                None,
+                "yes",
+                MARKET_TICKER,
+                "limit",
+                Some(price(35)),
+            )
+            .await
+            .unwrap()
+            .order;
+            assert_eq!(order.status, "resting");
+            assert_eq!(order.no_price, price(65));
+
+            // Counts and prices sent as strings are rejected, like on the exchange.
+            let response = client
+                .post(environment::api_url("portfolio/orders").unwrap())
+                .bearer_auth(&token)
+                .json(&serde_json::json!({
+                    "action": "buy",
+                    "client_order_id": "client-strings",
+                    "count": "10",
+                    "side": "yes",
+                    "ticker": MARKET_TICKER,
+                    "type": "limit",
+                    "yes_price": "35",
+                }))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 400);
+
+            let fetched = portfolio::get_order(&client, &token, &order.order_id)
+                .await
+                .unwrap()
+                .order;
+            assert_eq!(fetched.client_order_id, "client-1");
+
+            let amended = portfolio::amend_order_async(
+                &client,
+                &token,
+                &order.order_id,
+                "buy",
+                "client-1",
+                8,
+                None,
+                "yes",
+                MARKET_TICKER,
+                "client-2",
+                Some(price(36)),
+            )
+            .await
+            .unwrap();
+            assert_eq!(amended.old_order.yes_price, price(35));
+            assert_eq!(amended.order.remaining_count, 8);
+
+            let decreased =
+                portfolio::decrease_order_async(&client, &token, &order.order_id, None, Some(6))
+                    .await
+                    .unwrap()
+                    .order;
+            assert_eq!(decreased.decrease_count, 2);
+
+            let resting = portfolio::get_orders_async(
+                &client,
+                &token,
+                None,
+                Some(EVENT_TICKER),
+                None,
+                None,
+                Some("resting"),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(resting.orders.len(), 1);
+
+            let cancelled = portfolio::cancel_order_async(&client, &token, &order.order_id)
+                .await
+                .unwrap();
+            assert_eq!(cancelled.reduced_by, 6);
+            assert_eq!(cancelled.order.status, "canceled");
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn batch_orders_and_cancel_all() {
+    let server = MockServer::start(scripted_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    server
+        .run(async {
+            let token = login(&client).await;
+
+            let created = portfolio::batch_create_orders_async(
+                &client,
+                &token,
+                &[limit_order("a", 30), limit_order("b", 31)],
+                2,
+            )
+            .await
+            .unwrap();
+            let order_ids: Vec<String> = created
+                .orders
+                .iter()
+                .map(|result| result.order.as_ref().unwrap().order_id.clone())
+                .collect();
+            assert_eq!(order_ids.len(), 2);
+
+            let cancelled =
+                portfolio::batch_cancel_orders_async(&client, &token, &order_ids[..1], 2)
+                    .await
+                    .unwrap();
+            assert_eq!(cancelled.orders[0].reduced_by, 5);
+
+            let report = portfolio::cancel_all_async(
+                &client,
+                &token,
+                CancelScope::Ticker(MARKET_TICKER.to_string()),
+                2,
+                10,
+            )
+            .await
+            .unwrap();
+            assert_eq!(report.cancelled.len(), 1);
+            assert!(report.failed.is_empty());
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn batch_orders_fall_back_to_single_requests() {
+    let mut state = scripted_state();
+    state.batch_orders_enabled = false;
+    let server = MockServer::start(state).await.unwrap();
+    let client = reqwest::Client::new();
+    server
+        .run(async {
+            let token = login(&client).await;
+            let created =
+                portfolio::batch_create_orders_async(&client, &token, &[limit_order("a", 30)], 1)
+                    .await
+                    .unwrap();
+            assert!(created.orders[0].order.is_some());
+        })
+        .await;
+    let state = server.stop().await.unwrap();
+    let paths: Vec<&str> = state
+        .requests()
+        .iter()
+        .map(|request| request.path.as_str())
+        .collect();
+    assert!(paths.contains(&"portfolio/orders/batched"));
+    assert!(paths.contains(&"portfolio/orders/"));
+}
+
+#[tokio::test]
+async fn failed_batch_chunks_keep_earlier_results() {
+    let server = MockServer::start(scripted_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    server.state().fail_after(
+        "portfolio/orders/batched",
+        1,
+        MockError::InternalServerError,
+    );
+    server
+        .run(async {
+            let token = login(&client).await;
+
+            // 25 orders go out as a batch of 20 and a batch of 5; the second batch fails.
+            let orders: Vec<CreateOrderRequest> = (0..25)
+                .map(|i| limit_order(&format!("order-{}", i), 30))
+                .collect();
+            let created = portfolio::batch_create_orders_async(&client, &token, &orders, 2)
+                .await
+                .unwrap();
+            assert_eq!(created.orders.len(), 25);
+            assert!(created.orders[..20]
+                .iter()
+                .all(|result| result.order.is_some() && result.error.is_none()));
+            assert!(created.orders[20..]
+                .iter()
+                .all(|result| result.order.is_none()
+                    && result.error.as_ref().unwrap().message.contains("500")));
+            assert_eq!(
+                created.orders[24].client_order_id.as_deref(),
+                Some("order-24")
+            );
+
+            let order_ids: Vec<String> = created.orders[..20]
+                .iter()
+                .map(|result| result.order.as_ref().unwrap().order_id.clone())
+                .chain((0..5).map(|i| format!("missing-{}", i)))
+                .collect();
+            server.state().fail_after(
+                "portfolio/orders/batched",
+                1,
+                MockError::InternalServerError,
+            );
+            let cancelled = portfolio::batch_cancel_orders_async(&client, &token, &order_ids, 2)
+                .await
+                .unwrap();
+            assert_eq!(cancelled.orders.len(), 25);
+            assert!(cancelled.orders[..20]
+                .iter()
+                .all(|result| result.reduced_by == 5));
+            assert!(cancelled.orders[20..]
+                .iter()
+                .all(|result| result.error.is_some()));
+            assert_eq!(cancelled.orders[24].order_id, "missing-4");
+        })
+        .await;
+    let state = server.stop().await.unwrap();
+    assert_eq!(state.orders.len(), 20);
+}
+
+#[tokio::test]
+async fn cancel_all_uses_batches_and_falls_back_to_single_cancels() {
+    for batch_orders_enabled in [true, false] {
+        let mut state = scripted_state();
+        state.batch_orders_enabled = batch_orders_enabled;
+        let server = MockServer::start(state).await.unwrap();
+        let client = reqwest::Client::new();
+        server
+            .run(async {
+                let token = login(&client).await;
+                portfolio::batch_create_orders_async(
+                    &client,
+                    &token,
+                    &[
+                        limit_order("a", 30),
+                        limit_order("b", 31),
+                        limit_order("c", 32),
+                    ],
+                    1,
+                )
+                .await
+                .unwrap();
+                let report = portfolio::cancel_all_async(&client, &token, CancelScope::All, 2, 100)
+                    .await
+                    .unwrap();
+                assert_eq!(report.cancelled.len(), 3);
+                assert!(report.failed.is_empty());
+            })
+            .await;
+        let state = server.stop().await.unwrap();
+        let cancels: Vec<&str> = state
+            .requests()
+            .iter()
+            .filter(|request| request.method == "DELETE")
+            .map(|request| request.path.as_str())
+            .collect();
+        if batch_orders_enabled {
+            assert_eq!(cancels, ["portfolio/orders/batched"]);
+        } else {
+            // One rejected batch, then a cancel per order.
+            assert_eq!(cancels.len(), 4);
+            assert_eq!(cancels[0], "portfolio/orders/batched");
+        }
+    }
+}
+
+#[tokio::test]
+async fn injected_errors_surface_as_errors() {
+    let server = MockServer::start(scripted_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    {
+        let mut state = server.state();
+        state.fail_next("exchange/status", MockError::InternalServerError);
+        state.fail_next("portfolio/balance", MockError::RateLimited);
+        state.fail_next("markets", MockError::MalformedJson);
+        state.fail_next("portfolio/fills", MockError::Unauthorized);
+    }
+    server
+        .run(async {
+            let token = login(&client).await;
+
+            let error = exchange::get_exchange_status_async(&client)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("500"));
+            assert!(exchange::get_exchange_status_async(&client).await.is_ok());
+
+            let error = portfolio::get_balance_async(&client, &token)
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("429"));
+
+            assert!(market::get_market_async(&client, MARKET_TICKER, &token)
+                .await
+                .is_err());
+
+            let error =
+                portfolio::get_fills_async(&client, &token, None, None, None, None, None, None)
+                    .await
+                    .unwrap_err();
+            assert!(error.to_string().contains("401"));
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn rejects_bad_credentials_and_tokens() {
+    let server = MockServer::start(scripted_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    server
+        .run(async {
+            assert!(portfolio::get_balance_async(&client, "wrong-token")
+                .await
+                .is_err());
+
+            server.state().password = Some("something-else".to_string());
+            set_credentials();
+            let error = auth::login(&client).await.unwrap_err();
+            assert!(error.to_string().contains("401"));
+        })
+        .await;
+    server.stop().await.unwrap();
+}
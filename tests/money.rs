@@ -0,0 +1,80 @@
+use kalshi_api::money::{Cents, InvalidPrice, Price};
+
+#[test]
+fn cents_arithmetic_is_checked() {
+    assert_eq!(Cents(150) + Cents(25) - Cents(75), Cents(100));
+    assert_eq!(Cents(45) * 10, Cents(450));
+    assert_eq!(-Cents(5), Cents(-5));
+    assert_eq!(Cents(-5).abs(), Cents(5));
+    assert_eq!(
+        [Cents(1), Cents(2), Cents(3)].into_iter().sum::<Cents>(),
+        Cents(6)
+    );
+
+    assert_eq!(Cents(i64::MAX).checked_add(Cents(1)), None);
+    assert_eq!(Cents(i64::MIN).checked_sub(Cents(1)), None);
+    assert_eq!(Cents(i64::MAX).checked_mul(2), None);
+    assert_eq!(Cents(i64::MIN).checked_neg(), None);
+    assert_eq!(Cents(10).checked_sub(Cents(15)), Some(Cents(-5)));
+}
+
+#[test]
+#[should_panic(expected = "overflow when adding Cents")]
+fn cents_addition_panics_on_overflow() {
+    let _ = Cents(i64::MAX) + Cents(1);
+}
+
+#[test]
+fn cents_display_as_dollars() {
+    assert_eq!(Cents(1234).to_string(), "$12.34");
+    assert_eq!(Cents(0).to_string(), "$0.00");
+    assert_eq!(Cents(-5).to_string(), "-$0.05");
+    assert_eq!(Cents(-1234).to_string(), "-$12.34");
+    assert_eq!(Cents(i64::MIN).to_string(), "-$92233720368547758.08");
+    assert_eq!(Cents(-5).to_decimal_string(), "-0.05");
+    assert_eq!(Cents(450).as_dollars(), 4.5);
+}
+
+#[test]
+fn prices_are_between_1_and_99() {
+    for cents in [i64::MIN, -1, 0, 100, 256, i64::MAX] {
+        assert_eq!(Price::try_from(cents), Err(InvalidPrice(cents)));
+    }
+    assert_eq!(Price::try_from(1).unwrap(), Price::MIN);
+    assert_eq!(Price::try_from(99).unwrap(), Price::MAX);
+    assert_eq!(Price::try_from(Cents(45)).unwrap().value(), 45);
+    assert_eq!(Price::try_from(45i32).unwrap().cents(), Cents(45));
+    assert_eq!(Price::new(0), None);
+    assert_eq!(Price::new(100), None);
+    assert_eq!(
+        InvalidPrice(100).to_string(),
+        "Price must be between 1 and 99 cents, got 100"
+    );
+}
+
+#[test]
+fn price_arithmetic_stays_in_bounds() {
+    let price = Price::try_from(30).unwrap();
+    assert_eq!(price.complement().value(), 70);
+    assert_eq!(price.complement().complement(), price);
+    assert_eq!(Price::MIN.complement(), Price::MAX);
+    assert_eq!(price.checked_add(69), Some(Price::MAX));
+    assert_eq!(price.checked_add(70), None);
+    assert_eq!(price.checked_sub(29), Some(Price::MIN));
+    assert_eq!(price.checked_sub(30), None);
+    assert_eq!(price.checked_mul(10), Some(Cents(300)));
+    assert_eq!(price.to_string(), "30¢");
+}
+
+#[test]
+fn money_round_trips_through_serde() {
+    let price: Price = serde_json::from_str("45").unwrap();
+    assert_eq!(price.value(), 45);
+    assert_eq!(serde_json::to_string(&price).unwrap(), "45");
+    assert!(serde_json::from_str::<Price>("0").is_err());
+    assert!(serde_json::from_str::<Price>("100").is_err());
+
+    let cents: Cents = serde_json::from_str("-1250").unwrap();
+    assert_eq!(cents, Cents(-1250));
+    assert_eq!(serde_json::to_string(&cents).unwrap(), "-1250");
+}
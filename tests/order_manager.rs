@@ -0,0 +1,218 @@
+use kalshi_api::client::KalshiClient;
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::order_manager::{OrderManager, OrderState, TrackedOrder};
+use kalshi_api::portfolio::CreateOrderRequest;
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+const TICKER: &str = "INXD-23APR03-B4100";
+
+fn request(client_order_id: &str, count: i32) -> CreateOrderRequest {
+    CreateOrderRequest {
+        action: "buy".to_string(),
+        client_order_id: client_order_id.to_string(),
+        count,
+        side: "yes".to_string(),
+        ticker: TICKER.to_string(),
+        type_: "limit".to_string(),
+        yes_price: Some(price(40)),
+        ..CreateOrderRequest::default()
+    }
+}
+
+fn assert_counts_add_up(order: &TrackedOrder) {
+    assert_eq!(
+        order.filled_count + order.remaining_count + order.decreased_count,
+        order.count,
+        "{:?}",
+        order
+    );
+}
+
+#[test]
+fn walks_the_state_machine_and_ignores_duplicate_fills() {
+    let mut manager = OrderManager::new();
+    manager.track_pending(&request("client-order-1", 10));
+    assert_eq!(
+        manager.get("client-order-1").unwrap().state,
+        OrderState::Pending
+    );
+
+    let order = fixtures::order("order-1", TICKER, "yes", price(40), 10);
+    manager.on_order_update(&order);
+    let tracked = manager.get_by_order_id("order-1").unwrap();
+    assert_eq!(tracked.state, OrderState::Resting);
+    assert_eq!(tracked.average_fill_price(), None);
+    assert_counts_add_up(tracked);
+
+    // Fills for orders we don't track are ignored.
+    manager.on_fill(&fixtures::fill(
+        "trade-0",
+        "order-2",
+        TICKER,
+        "yes",
+        price(40),
+        1,
+    ));
+
+    let first = fixtures::fill("trade-1", "order-1", TICKER, "yes", price(40), 4);
+    manager.on_fill(&first);
+    manager.on_fill(&first);
+    let tracked = manager.get("client-order-1").unwrap();
+    assert_eq!(tracked.state, OrderState::PartiallyFilled);
+    assert_eq!(tracked.filled_count, 4);
+    assert_eq!(tracked.remaining_count, 6);
+    assert_eq!(tracked.fills.len(), 1);
+    assert_counts_add_up(tracked);
+
+    manager.on_fill(&fixtures::fill(
+        "trade-2",
+        "order-1",
+        TICKER,
+        "yes",
+        price(35),
+        6,
+    ));
+    let tracked = manager.get("client-order-1").unwrap();
+    assert_eq!(tracked.state, OrderState::Executed);
+    assert_eq!(tracked.fill_cost, Cents(4 * 40 + 6 * 35));
+    assert_eq!(tracked.average_fill_price(), Some(37.0));
+    assert_counts_add_up(tracked);
+    assert_eq!(manager.open_orders().count(), 0);
+}
+
+#[test]
+fn prices_no_side_fills_on_the_no_side() {
+    let mut manager = OrderManager::new();
+    let order = fixtures::order("order-1", TICKER, "no", price(40), 5);
+    manager.on_order_update(&order);
+    manager.on_fill(&fixtures::fill(
+        "trade-1",
+        "order-1",
+        TICKER,
+        "no",
+        price(40),
+        5,
+    ));
+    let tracked = manager.get_by_order_id("order-1").unwrap();
+    assert_eq!(tracked.average_fill_price(), Some(60.0));
+    assert_eq!(tracked.state, OrderState::Executed);
+}
+
+#[test]
+fn adopts_exchange_fills_and_cancels() {
+    let mut manager = OrderManager::new();
+    manager.track_pending(&request("client-order-1", 10));
+    let mut order = fixtures::order("order-1", TICKER, "yes", price(40), 10);
+    order.client_order_id = "client-order-1".to_string();
+    manager.on_order_update(&order);
+
+    // The exchange reports fills we haven't seen yet.
+    order.taker_fill_count = 2;
+    order.taker_fill_cost = Cents(80);
+    order.maker_fill_count = 3;
+    order.maker_fill_cost = Cents(120);
+    order.taker_fees = Cents(1);
+    order.maker_fees = Cents(2);
+    order.remaining_count = 5;
+    let drift = manager.detect_drift(&order).unwrap();
+    assert_eq!(drift.local_filled_count, 0);
+    assert_eq!(drift.exchange_filled_count, 5);
+    manager.on_order_update(&order);
+    assert!(manager.detect_drift(&order).is_none());
+    let tracked = manager.get("client-order-1").unwrap();
+    assert_eq!(tracked.state, OrderState::PartiallyFilled);
+    assert_eq!(tracked.filled_count, 5);
+    assert_eq!(tracked.average_fill_price(), Some(40.0));
+    assert_eq!(tracked.fees, Cents(3));
+    assert_counts_add_up(tracked);
+
+    // The fills arrive later and don't count twice.
+    manager.on_fill(&fixtures::fill(
+        "trade-1",
+        "order-1",
+        TICKER,
+        "yes",
+        price(40),
+        5,
+    ));
+    let tracked = manager.get("client-order-1").unwrap();
+    assert_eq!(tracked.filled_count, 5);
+    assert_eq!(tracked.remaining_count, 5);
+
+    // A fill seen before the exchange's order catches up isn't undone by a stale update.
+    manager.on_fill(&fixtures::fill(
+        "trade-2",
+        "order-1",
+        TICKER,
+        "yes",
+        price(40),
+        1,
+    ));
+    manager.on_order_update(&order);
+    let tracked = manager.get("client-order-1").unwrap();
+    assert_eq!(tracked.filled_count, 6);
+    assert_eq!(tracked.remaining_count, 4);
+    assert_counts_add_up(tracked);
+
+    order.taker_fill_count = 3;
+    order.taker_fill_cost = Cents(120);
+    order.decrease_count = 4;
+    order.remaining_count = 0;
+    order.status = "canceled".to_string();
+    manager.on_order_update(&order);
+    let tracked = manager.get("client-order-1").unwrap();
+    assert_eq!(tracked.state, OrderState::Canceled);
+    assert_eq!(tracked.decreased_count, 4);
+    assert_counts_add_up(tracked);
+    assert_eq!(manager.open_orders().count(), 0);
+}
+
+#[tokio::test]
+async fn poll_reports_drift_once() {
+    let mut state = MockState::default();
+    state.events.push(fixtures::event(EVENT_TICKER, "INXD"));
+    state
+        .markets
+        .push(fixtures::market(TICKER, EVENT_TICKER, 40, 42));
+    let server = MockServer::start(state).await.unwrap();
+    let token = server.state().token.clone();
+    let backend = KalshiClient::new(reqwest::Client::new(), token.clone());
+    let mut manager = OrderManager::new();
+    server
+        .run(async {
+            let order = manager
+                .create_order_async(&backend, &request("client-order-1", 10))
+                .await
+                .unwrap();
+            assert_eq!(
+                manager.get("client-order-1").unwrap().state,
+                OrderState::Resting
+            );
+            assert!(manager.poll_async(&backend).await.unwrap().is_empty());
+
+            {
+                let mut state = server.state();
+                let exchange_order = state
+                    .orders
+                    .iter_mut()
+                    .find(|o| o.order_id == order.order_id)
+                    .unwrap();
+                exchange_order.taker_fill_count = 4;
+                exchange_order.taker_fill_cost = Cents(160);
+                exchange_order.remaining_count = 6;
+            }
+            let drift = manager.poll_async(&backend).await.unwrap();
+            assert_eq!(drift.len(), 1);
+            assert_eq!(drift[0].exchange_filled_count, 4);
+            assert!(manager.poll_async(&backend).await.unwrap().is_empty());
+
+            let tracked = manager.get("client-order-1").unwrap();
+            assert_eq!(tracked.state, OrderState::PartiallyFilled);
+            assert_eq!(tracked.average_fill_price(), Some(40.0));
+            assert_counts_add_up(tracked);
+        })
+        .await;
+    server.stop().await.unwrap();
+}
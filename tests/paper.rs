@@ -0,0 +1,464 @@
+use kalshi_api::fees::FeeSchedule;
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::paper::{PaperTrading, Trader};
+use kalshi_api::portfolio::Order;
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+const TICKER: &str = "INXD-23APR03-B4100";
+
+// Yes bids at 40 and no bids at 55 and 50, i.e. yes asks at 45 and 50.
+fn mock_state() -> MockState {
+    let mut state = MockState::default();
+    state.balance = Cents(1_234);
+    state.events.push(fixtures::event(EVENT_TICKER, "INXD"));
+    state
+        .markets
+        .push(fixtures::market(TICKER, EVENT_TICKER, 40, 45));
+    state.orderbooks.insert(
+        TICKER.to_string(),
+        fixtures::orderbook(&[(40, 10)], &[(55, 4), (50, 6)]),
+    );
+    state
+}
+
+fn set_orderbook(server: &MockServer, yes: &[(i32, i32)], no: &[(i32, i32)]) {
+    server
+        .state()
+        .orderbooks
+        .insert(TICKER.to_string(), fixtures::orderbook(yes, no));
+}
+
+async fn limit_order(
+    paper: &PaperTrading,
+    client: &reqwest::Client,
+    token: &str,
+    action: &str,
+    count: i32,
+    yes_price: i64,
+) -> Result<Order, Box<dyn std::error::Error + Send + Sync>> {
+    paper
+        .create_order_async(
+            client,
+            token,
+            action,
+            None,
+            &format!("client-{}-{}", action, yes_price),
+            count,
+            None,
+            None,
+            None,
+            "yes",
+            TICKER,
+            "limit",
+            Some(price(yes_price)),
+        )
+        .await
+        .map(|response| response.order)
+}
+
+#[tokio::test]
+async fn crosses_rests_and_fills_against_new_orderbooks() {
+    let server = MockServer::start(mock_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    let token = server.state().token.clone();
+    let paper = PaperTrading::with_fee_schedule(Cents(10_000), FeeSchedule::free());
+    server
+        .run(async {
+            // Takes the 4 offered at 45 and rests the rest at 47.
+            let order = limit_order(&paper, &client, &token, "buy", 8, 47)
+                .await
+                .unwrap();
+            assert_eq!(order.status, "resting");
+            assert_eq!(order.taker_fill_count, 4);
+            assert_eq!(order.taker_fill_cost, Cents(180));
+            assert_eq!(order.remaining_count, 4);
+
+            // The snapshot still shows the 4 at 45 that were already taken, so nothing more
+            // fills on refresh.
+            let balance = paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(10_000 - 180));
+
+            // Someone offers at 47 and the resting order fills as a maker.
+            set_orderbook(&server, &[(40, 10)], &[(55, 4), (53, 10)]);
+            let balance = paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(10_000 - 180 - 188));
+            let order = paper
+                .get_order(&client, &token, &order.order_id)
+                .await
+                .unwrap()
+                .order;
+            assert_eq!(order.status, "executed");
+            assert_eq!(order.maker_fill_count, 4);
+            assert_eq!(order.maker_fill_cost, Cents(188));
+
+            // Selling 3 into the bid at 40 realizes (40 - 46) * 3 against the average cost.
+            let order = limit_order(&paper, &client, &token, "sell", 3, 40)
+                .await
+                .unwrap();
+            assert_eq!(order.status, "executed");
+            let positions = paper
+                .get_positions_async(&client, &token, None, None, None, None, None)
+                .await
+                .unwrap();
+            let position = &positions.market_positions[0];
+            assert_eq!(position.position, 5);
+            assert_eq!(position.realized_pnl, Cents(-18));
+            assert_eq!(positions.event_positions[0].event_ticker, EVENT_TICKER);
+            let balance = paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(10_000 - 368 + 120));
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+async fn maker_fill_count(
+    paper: &PaperTrading,
+    client: &reqwest::Client,
+    token: &str,
+    order_id: &str,
+) -> i32 {
+    paper
+        .get_order(client, token, order_id)
+        .await
+        .unwrap()
+        .order
+        .maker_fill_count
+}
+
+#[tokio::test]
+async fn orders_compete_for_the_same_offered_size() {
+    let server = MockServer::start(mock_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    let token = server.state().token.clone();
+    let paper = PaperTrading::with_fee_schedule(Cents(10_000), FeeSchedule::free());
+    server
+        .run(async {
+            let first = limit_order(&paper, &client, &token, "buy", 10, 42)
+                .await
+                .unwrap();
+            let second = paper
+                .create_order_async(
+                    &client,
+                    &token,
+                    "buy",
+                    None,
+                    "client-2",
+                    10,
+                    None,
+                    None,
+                    None,
+                    "yes",
+                    TICKER,
+                    "limit",
+                    Some(price(42)),
+                )
+                .await
+                .unwrap()
+                .order;
+            assert_eq!(second.status, "resting");
+
+            // Ten are offered at 42, which only fills the older order, however often the
+            // same snapshot is seen.
+            set_orderbook(&server, &[(40, 10)], &[(58, 10)]);
+            for _ in 0..3 {
+                paper.get_balance_async(&client, &token).await.unwrap();
+            }
+            assert_eq!(
+                maker_fill_count(&paper, &client, &token, &first.order_id).await,
+                10
+            );
+            assert_eq!(
+                maker_fill_count(&paper, &client, &token, &second.order_id).await,
+                0
+            );
+
+            // A new order can't take that size either.
+            let third = paper
+                .create_order_async(
+                    &client,
+                    &token,
+                    "buy",
+                    None,
+                    "client-3",
+                    5,
+                    None,
+                    None,
+                    None,
+                    "yes",
+                    TICKER,
+                    "limit",
+                    Some(price(42)),
+                )
+                .await
+                .unwrap()
+                .order;
+            assert_eq!(third.taker_fill_count, 0);
+
+            // Five more join the level, and the second order gets them.
+            set_orderbook(&server, &[(40, 10)], &[(58, 15)]);
+            paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(
+                maker_fill_count(&paper, &client, &token, &second.order_id).await,
+                5
+            );
+            assert_eq!(
+                maker_fill_count(&paper, &client, &token, &third.order_id).await,
+                0
+            );
+
+            // Once the level is gone its size no longer counts against a new one.
+            set_orderbook(&server, &[(40, 10)], &[]);
+            paper.get_balance_async(&client, &token).await.unwrap();
+            set_orderbook(&server, &[(40, 10)], &[(58, 8)]);
+            paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(
+                maker_fill_count(&paper, &client, &token, &second.order_id).await,
+                10
+            );
+            assert_eq!(
+                maker_fill_count(&paper, &client, &token, &third.order_id).await,
+                3
+            );
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn rejects_sells_larger_than_the_position() {
+    let server = MockServer::start(mock_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    let token = server.state().token.clone();
+    let paper = PaperTrading::with_fee_schedule(Cents(10_000), FeeSchedule::free());
+    server
+        .run(async {
+            assert!(limit_order(&paper, &client, &token, "sell", 1, 40)
+                .await
+                .is_err());
+
+            limit_order(&paper, &client, &token, "buy", 3, 45)
+                .await
+                .unwrap();
+            assert!(limit_order(&paper, &client, &token, "sell", 4, 40)
+                .await
+                .is_err());
+            // A resting sell holds on to the contracts it covers.
+            let resting = limit_order(&paper, &client, &token, "sell", 2, 60)
+                .await
+                .unwrap();
+            assert_eq!(resting.status, "resting");
+            assert!(limit_order(&paper, &client, &token, "sell", 2, 40)
+                .await
+                .is_err());
+            let order = limit_order(&paper, &client, &token, "sell", 1, 40)
+                .await
+                .unwrap();
+            assert_eq!(order.status, "executed");
+
+            let positions = paper
+                .get_positions_async(&client, &token, None, None, None, None, None)
+                .await
+                .unwrap();
+            assert_eq!(positions.market_positions[0].position, 2);
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn cancels_and_rejects_orders() {
+    let server = MockServer::start(mock_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    let token = server.state().token.clone();
+    let paper = PaperTrading::with_fee_schedule(Cents(2_000), FeeSchedule::free());
+    server
+        .run(async {
+            assert!(limit_order(&paper, &client, &token, "buy", 60, 40)
+                .await
+                .is_err());
+
+            let order = limit_order(&paper, &client, &token, "buy", 5, 30)
+                .await
+                .unwrap();
+            assert_eq!(order.status, "resting");
+            let canceled = paper
+                .cancel_order_async(&client, &token, &order.order_id)
+                .await
+                .unwrap();
+            assert_eq!(canceled.reduced_by, 5);
+            assert_eq!(canceled.order.status, "canceled");
+            assert_eq!(canceled.order.decrease_count, 5);
+            assert!(paper
+                .cancel_order_async(&client, &token, &order.order_id)
+                .await
+                .is_err());
+            assert!(paper
+                .cancel_order_async(&client, &token, "paper-404")
+                .await
+                .is_err());
+
+            // A market order that can't fill completely cancels the rest.
+            let order = paper
+                .create_order_async(
+                    &client, &token, "buy", None, "market", 12, None, None, None, "yes", TICKER,
+                    "market", None,
+                )
+                .await
+                .unwrap()
+                .order;
+            assert_eq!(order.status, "executed");
+            assert_eq!(order.taker_fill_count, 10);
+            assert_eq!(order.decrease_count, 2);
+
+            let balance = paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(2_000 - 4 * 45 - 6 * 50));
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn pages_fills_and_orders_with_cursors() {
+    let server = MockServer::start(mock_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    let token = server.state().token.clone();
+    let paper = PaperTrading::with_fee_schedule(Cents(10_000), FeeSchedule::free());
+    server
+        .run(async {
+            // Buys fill at 45 and 50, then one contract is sold at 40.
+            limit_order(&paper, &client, &token, "buy", 6, 50)
+                .await
+                .unwrap();
+            limit_order(&paper, &client, &token, "sell", 1, 40)
+                .await
+                .unwrap();
+            limit_order(&paper, &client, &token, "buy", 1, 30)
+                .await
+                .unwrap();
+
+            let page = paper
+                .get_fills_async(&client, &token, None, None, None, None, Some(2), None)
+                .await
+                .unwrap();
+            assert_eq!(page.fills.len(), 2);
+            assert_eq!(page.fills[0].action, "sell");
+            assert_eq!(page.cursor, "2");
+            let page = paper
+                .get_fills_async(
+                    &client,
+                    &token,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(2),
+                    Some(&page.cursor),
+                )
+                .await
+                .unwrap();
+            assert_eq!(page.fills.len(), 1);
+            assert_eq!(page.fills[0].yes_price, price(45));
+            assert!(page.cursor.is_empty());
+
+            let mut order_ids = Vec::new();
+            let mut cursor: Option<String> = None;
+            loop {
+                let page = paper
+                    .get_orders_async(
+                        &client,
+                        &token,
+                        None,
+                        Some(EVENT_TICKER),
+                        None,
+                        None,
+                        None,
+                        cursor.as_deref(),
+                        Some(1),
+                    )
+                    .await
+                    .unwrap();
+                order_ids.extend(page.orders.into_iter().map(|order| order.order_id));
+                if page.cursor.is_empty() {
+                    break;
+                }
+                cursor = Some(page.cursor);
+            }
+            assert_eq!(order_ids.len(), 3);
+            assert_eq!(order_ids[2], "paper-1");
+
+            assert!(paper
+                .get_orders_async(
+                    &client,
+                    &token,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some("not-a-cursor"),
+                    None,
+                )
+                .await
+                .is_err());
+        })
+        .await;
+    server.stop().await.unwrap();
+}
+
+async fn create(
+    trader: &Trader,
+    client: &reqwest::Client,
+    token: &str,
+) -> Result<Order, Box<dyn std::error::Error + Send + Sync>> {
+    trader
+        .create_order_async(
+            client,
+            token,
+            "buy",
+            None,
+            "client-1",
+            1,
+            None,
+            None,
+            None,
+            "yes",
+            TICKER,
+            "limit",
+            Some(price(30)),
+        )
+        .await
+        .map(|response| response.order)
+}
+
+#[tokio::test]
+async fn trader_switches_between_live_and_paper() {
+    let server = MockServer::start(mock_state()).await.unwrap();
+    let client = reqwest::Client::new();
+    let token = server.state().token.clone();
+    server
+        .run(async {
+            let live = Trader::new(false, Cents(10_000));
+            assert!(matches!(live, Trader::Live));
+            let balance = live.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(1_234));
+            let order = create(&live, &client, &token).await.unwrap();
+            assert!(order.order_id.starts_with("mock-order-"));
+
+            let paper = Trader::new(true, Cents(10_000));
+            assert!(matches!(paper, Trader::Paper(_)));
+            let order = create(&paper, &client, &token).await.unwrap();
+            assert_eq!(order.order_id, "paper-1");
+            let balance = paper.get_balance_async(&client, &token).await.unwrap();
+            assert_eq!(balance.balance, Cents(10_000));
+            let orders = paper
+                .get_orders_async(&client, &token, None, None, None, None, None, None, None)
+                .await
+                .unwrap();
+            assert_eq!(orders.orders.len(), 1);
+        })
+        .await;
+    let state = server.stop().await.unwrap();
+    assert_eq!(state.orders.len(), 1);
+}
@@ -0,0 +1,205 @@
+use chrono::Duration;
+use kalshi_api::backtest::MarketDataEvent;
+use kalshi_api::client::KalshiClient;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::recorder::{CsvStore, Recorder, RecorderConfig, SnapshotStore};
+
+const SERIES_TICKER: &str = "INXD";
+const EVENT_TICKER: &str = "INXD-23APR03";
+
+fn state() -> MockState {
+    let mut state = MockState::default();
+    state
+        .events
+        .push(fixtures::event(EVENT_TICKER, SERIES_TICKER));
+    state.events.push(fixtures::event("FED-23MAY", "FED"));
+    for (ticker, event_ticker) in [
+        ("INXD-23APR03-B4100", EVENT_TICKER),
+        ("INXD-23APR03-B4200", EVENT_TICKER),
+        ("FED-23MAY-T5", "FED-23MAY"),
+    ] {
+        state
+            .markets
+            .push(fixtures::market(ticker, event_ticker, 40, 45));
+        state.orderbooks.insert(
+            ticker.to_string(),
+            fixtures::orderbook(&[(40, 10)], &[(55, 20)]),
+        );
+    }
+    let price = fixtures::price(42);
+    state.trades.push(fixtures::trade(
+        "trade-1",
+        "INXD-23APR03-B4100",
+        "yes",
+        price,
+        5,
+    ));
+    state
+        .trades
+        .push(fixtures::trade("trade-2", "FED-23MAY-T5", "no", price, 3));
+    state
+}
+
+fn count(events: &[MarketDataEvent]) -> (usize, usize, usize) {
+    let markets = events
+        .iter()
+        .filter(|event| matches!(event, MarketDataEvent::Market { .. }))
+        .count();
+    let orderbooks = events
+        .iter()
+        .filter(|event| matches!(event, MarketDataEvent::Orderbook { .. }))
+        .count();
+    let trades = events
+        .iter()
+        .filter(|event| matches!(event, MarketDataEvent::Trade(_)))
+        .count();
+    (markets, orderbooks, trades)
+}
+
+// Records the configured series twice. Trades are only written the first time.
+async fn record<S: SnapshotStore>(store: S) -> S {
+    let state = state();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let config = RecorderConfig {
+        series_tickers: vec![SERIES_TICKER.to_string()],
+        ..RecorderConfig::default()
+    };
+    let mut recorder = Recorder::new(
+        KalshiClient::new(reqwest::Client::new(), token),
+        config,
+        store,
+    );
+    server
+        .run(async {
+            assert_eq!(recorder.snapshot_async().await.unwrap(), 5);
+            assert_eq!(recorder.snapshot_async().await.unwrap(), 4);
+        })
+        .await;
+    server.stop().await.unwrap();
+    recorder.into_store()
+}
+
+fn assert_recorded(events: &[MarketDataEvent]) {
+    assert_eq!(count(events), (4, 4, 1));
+    // The fixture trade predates the snapshots, so it loads first.
+    match &events[0] {
+        MarketDataEvent::Trade(trade) => {
+            assert_eq!(trade.trade_id, "trade-1");
+            assert_eq!(trade.count, 5);
+            assert_eq!(trade.yes_price.value(), 42);
+        }
+        event => panic!("Expected a trade, got {:?}", event),
+    }
+    for event in events {
+        match event {
+            MarketDataEvent::Market { market, .. } => {
+                assert!(market.ticker.starts_with(EVENT_TICKER));
+                assert_eq!(market.yes_bid, Cents(40));
+                assert_eq!(market.close_time, fixtures::market("", "", 0, 0).close_time);
+            }
+            MarketDataEvent::Orderbook { orderbook, .. } => {
+                assert_eq!(orderbook.yes, vec![vec![40, 10]]);
+                assert_eq!(orderbook.no, vec![vec![55, 20]]);
+            }
+            _ => {}
+        }
+    }
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0].time() <= pair[1].time()));
+}
+
+#[tokio::test]
+async fn records_series_to_csv_and_loads_it_back() {
+    let dir = std::env::temp_dir().join(format!("kalshi-recorder-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let store = record(CsvStore::open(&dir).unwrap()).await;
+
+    assert_recorded(&store.load().unwrap());
+    // Reopening the directory appends to the same files, and a restarted recorder doesn't
+    // write the trades it already recorded again.
+    let reopened = CsvStore::open(&dir).unwrap();
+    assert_eq!(count(&reopened.load().unwrap()), (4, 4, 1));
+    let reopened = record(reopened).await;
+    assert_eq!(count(&reopened.load().unwrap()), (8, 8, 1));
+    let header = std::fs::read_to_string(dir.join("trades.csv")).unwrap();
+    assert!(
+        header.starts_with("trade_id,ticker,created_time,yes_price,no_price,count,taker_side\n")
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn csv_store_skips_trades_it_has_already_recorded() {
+    let dir = std::env::temp_dir().join(format!("kalshi-csv-trades-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let trade = |trade_id: &str, secs: i64| {
+        let mut trade = fixtures::trade(
+            trade_id,
+            "INXD-23APR03-B4100",
+            "yes",
+            fixtures::price(42),
+            1,
+        );
+        trade.created_time = fixtures::fixture_time() + Duration::seconds(secs);
+        MarketDataEvent::Trade(trade)
+    };
+    let trade_ids = |store: &CsvStore| -> Vec<String> {
+        store
+            .load()
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| match event {
+                MarketDataEvent::Trade(trade) => Some(trade.trade_id),
+                _ => None,
+            })
+            .collect()
+    };
+
+    let mut store = CsvStore::open(&dir).unwrap();
+    store
+        .write(&[trade("trade-1", 0), trade("trade-2", 600)])
+        .unwrap();
+    // trade-0 is older than the overlap window of the newest trade, so it is taken to be
+    // recorded already.
+    store
+        .write(&[
+            trade("trade-0", 300),
+            trade("trade-1", 0),
+            trade("trade-2", 600),
+            trade("trade-3", 630),
+        ])
+        .unwrap();
+    assert_eq!(trade_ids(&store), ["trade-1", "trade-2", "trade-3"]);
+
+    let mut reopened = CsvStore::open(&dir).unwrap();
+    reopened
+        .write(&[
+            trade("trade-1", 0),
+            trade("trade-2", 600),
+            trade("trade-3", 630),
+            trade("trade-4", 660),
+        ])
+        .unwrap();
+    assert_eq!(
+        trade_ids(&reopened),
+        ["trade-1", "trade-2", "trade-3", "trade-4"]
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn records_series_to_sqlite_and_loads_it_back() {
+    use kalshi_api::recorder::SqliteStore;
+
+    let mut store = record(SqliteStore::open_in_memory().unwrap()).await;
+    let events = store.load().unwrap();
+    assert_recorded(&events);
+
+    // Trades are keyed by id, so writing them again doesn't duplicate them.
+    store.write(&events[..1]).unwrap();
+    assert_eq!(count(&store.load().unwrap()), (4, 4, 1));
+}
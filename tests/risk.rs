@@ -0,0 +1,393 @@
+use kalshi_api::bids::{Bids, Spread};
+use kalshi_api::client::KalshiClient;
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::portfolio::{CreateOrderRequest, EventPosition};
+use kalshi_api::risk::{RiskChecker, RiskContext, RiskLimits, RiskViolation};
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+const TICKER: &str = "INXD-23APR03-B4100";
+
+// Buys 10 yes at 41 in a 40/42 market that last traded at 41.
+fn base() -> (RiskLimits, CreateOrderRequest, RiskContext) {
+    let order = CreateOrderRequest {
+        action: "buy".to_string(),
+        client_order_id: "client-order-1".to_string(),
+        count: 10,
+        side: "yes".to_string(),
+        ticker: TICKER.to_string(),
+        type_: "limit".to_string(),
+        yes_price: Some(price(41)),
+        ..CreateOrderRequest::default()
+    };
+    let context = RiskContext {
+        ticker: TICKER.to_string(),
+        event_ticker: EVENT_TICKER.to_string(),
+        bids: Bids {
+            no: Spread {
+                ask: Cents(60),
+                bid: Cents(58),
+            },
+            yes: Spread {
+                ask: Cents(42),
+                bid: Cents(40),
+            },
+        },
+        last_price: Cents(41),
+        balance: Cents(10_000),
+        market_position: 0,
+        event_position: 0,
+        exposure: Cents::ZERO,
+    };
+    (RiskLimits::default(), order, context)
+}
+
+type Setup = fn(&mut RiskLimits, &mut CreateOrderRequest, &mut RiskContext);
+
+#[test]
+fn checks_every_rule_at_its_boundary() {
+    let cases: Vec<(&str, Setup, Result<(), RiskViolation>)> = vec![
+        ("base order", |_, _, _| {}, Ok(())),
+        // Order size.
+        ("order at max size", |_, o, _| o.count = 100, Ok(())),
+        (
+            "order over max size",
+            |_, o, _| o.count = 101,
+            Err(RiskViolation::OrderSizeExceeded {
+                count: 101,
+                limit: 100,
+            }),
+        ),
+        // Market position.
+        (
+            "market position at limit",
+            |_, _, c| {
+                c.market_position = 490;
+                c.event_position = 490;
+            },
+            Ok(()),
+        ),
+        (
+            "market position over limit",
+            |_, o, c| {
+                o.count = 11;
+                c.market_position = 490;
+                c.event_position = 490;
+            },
+            Err(RiskViolation::MarketPositionExceeded {
+                ticker: TICKER.to_string(),
+                resulting_position: 501,
+                limit: 500,
+            }),
+        ),
+        (
+            "short market position over limit",
+            |_, o, c| {
+                o.side = "no".to_string();
+                o.yes_price = None;
+                o.no_price = Some(price(59));
+                o.count = 6;
+                c.market_position = -495;
+                c.event_position = 495;
+            },
+            Err(RiskViolation::MarketPositionExceeded {
+                ticker: TICKER.to_string(),
+                resulting_position: -501,
+                limit: 500,
+            }),
+        ),
+        (
+            "reducing a position over the limit",
+            |_, o, c| {
+                o.action = "sell".to_string();
+                c.market_position = 600;
+                c.event_position = 600;
+            },
+            Ok(()),
+        ),
+        // Event position.
+        (
+            "event position at limit",
+            |_, o, c| {
+                o.count = 5;
+                c.event_position = 995;
+            },
+            Ok(()),
+        ),
+        (
+            "event position over limit",
+            |_, o, c| {
+                o.count = 6;
+                c.event_position = 995;
+            },
+            Err(RiskViolation::EventPositionExceeded {
+                event_ticker: EVENT_TICKER.to_string(),
+                resulting_position: 1001,
+                limit: 1000,
+            }),
+        ),
+        // Exposure, as a fraction of the balance.
+        (
+            "exposure at limit",
+            |_, _, c| c.exposure = Cents(4_590),
+            Ok(()),
+        ),
+        (
+            "exposure over limit",
+            |_, _, c| c.exposure = Cents(4_591),
+            Err(RiskViolation::ExposureExceeded {
+                resulting_exposure: Cents(5_001),
+                limit: Cents(5_000),
+            }),
+        ),
+        (
+            "market order exposure at limit",
+            |_, o, c| {
+                o.type_ = "market".to_string();
+                o.yes_price = None;
+                c.exposure = Cents(4_010);
+            },
+            Ok(()),
+        ),
+        (
+            "market order exposure over limit",
+            |_, o, c| {
+                o.type_ = "market".to_string();
+                o.yes_price = None;
+                c.exposure = Cents(4_011);
+            },
+            Err(RiskViolation::ExposureExceeded {
+                resulting_exposure: Cents(5_001),
+                limit: Cents(5_000),
+            }),
+        ),
+        (
+            "sells add no exposure",
+            |_, o, c| {
+                o.action = "sell".to_string();
+                c.exposure = Cents(10_000);
+            },
+            Ok(()),
+        ),
+        // Notional against the balance, when the exposure fraction allows leverage.
+        (
+            "notional equal to balance",
+            |l, _, c| {
+                l.max_exposure_fraction = 2.0;
+                c.balance = Cents(410);
+            },
+            Ok(()),
+        ),
+        (
+            "notional over balance",
+            |l, _, c| {
+                l.max_exposure_fraction = 2.0;
+                c.balance = Cents(409);
+            },
+            Err(RiskViolation::InsufficientBalance {
+                notional: Cents(410),
+                balance: Cents(409),
+            }),
+        ),
+        // Price band around the bid and ask.
+        (
+            "price at top of band",
+            |_, o, _| o.yes_price = Some(price(47)),
+            Ok(()),
+        ),
+        (
+            "price above band",
+            |_, o, _| o.yes_price = Some(price(48)),
+            Err(RiskViolation::OutsidePriceBand {
+                price: price(48),
+                bid: Cents(40),
+                ask: Cents(42),
+                band: 5,
+            }),
+        ),
+        (
+            "price at bottom of band",
+            |_, o, _| o.yes_price = Some(price(35)),
+            Ok(()),
+        ),
+        (
+            "price below band",
+            |_, o, _| o.yes_price = Some(price(34)),
+            Err(RiskViolation::OutsidePriceBand {
+                price: price(34),
+                bid: Cents(40),
+                ask: Cents(42),
+                band: 5,
+            }),
+        ),
+        (
+            "no price checked against the no spread",
+            |_, o, _| {
+                o.side = "no".to_string();
+                o.yes_price = None;
+                o.no_price = Some(price(66));
+            },
+            Err(RiskViolation::OutsidePriceBand {
+                price: price(66),
+                bid: Cents(58),
+                ask: Cents(60),
+                band: 5,
+            }),
+        ),
+        (
+            "no order priced in yes terms",
+            |_, o, _| o.side = "no".to_string(),
+            Ok(()),
+        ),
+        // Fat finger, with the band opened up so it doesn't trip first.
+        (
+            "price at max deviation from last trade",
+            |l, o, _| {
+                l.price_band = 100;
+                o.yes_price = Some(price(61));
+            },
+            Ok(()),
+        ),
+        (
+            "price past max deviation from last trade",
+            |l, o, _| {
+                l.price_band = 100;
+                o.yes_price = Some(price(62));
+            },
+            Err(RiskViolation::FatFinger {
+                price: price(62),
+                last_price: Cents(41),
+                max_deviation: 20,
+            }),
+        ),
+        (
+            "no price past max deviation from last trade",
+            |l, o, _| {
+                l.price_band = 100;
+                o.side = "no".to_string();
+                o.yes_price = None;
+                o.no_price = Some(price(80));
+            },
+            Err(RiskViolation::FatFinger {
+                price: price(80),
+                last_price: Cents(59),
+                max_deviation: 20,
+            }),
+        ),
+        (
+            "market that never traded",
+            |l, o, c| {
+                l.price_band = 100;
+                o.yes_price = Some(price(90));
+                c.last_price = Cents::ZERO;
+            },
+            Ok(()),
+        ),
+    ];
+
+    for (name, setup, expected) in cases {
+        let (mut limits, mut order, mut context) = base();
+        setup(&mut limits, &mut order, &mut context);
+        let result = RiskChecker::new(limits).check(&order, &context);
+        assert_eq!(result, expected, "{}", name);
+    }
+}
+
+#[tokio::test]
+async fn rejected_orders_are_not_submitted() {
+    let mut state = MockState::default();
+    state.balance = Cents(10_000);
+    state.events.push(fixtures::event(EVENT_TICKER, "INXD"));
+    state
+        .markets
+        .push(fixtures::market(TICKER, EVENT_TICKER, 40, 42));
+    let server = MockServer::start(state).await.unwrap();
+    let token = server.state().token.clone();
+    let backend = KalshiClient::new(reqwest::Client::new(), token.clone());
+    let checker = RiskChecker::default();
+    let (_, mut order, _) = base();
+    server
+        .run(async {
+            order.count = 101;
+            let error = checker
+                .create_order_async(&backend, &order)
+                .await
+                .unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<RiskViolation>(),
+                Some(&RiskViolation::OrderSizeExceeded {
+                    count: 101,
+                    limit: 100,
+                })
+            );
+
+            order.count = 10;
+            checker.create_order_async(&backend, &order).await.unwrap();
+        })
+        .await;
+    let state = server.stop().await.unwrap();
+    let posts = state
+        .requests()
+        .iter()
+        .filter(|request| request.method == "POST")
+        .count();
+    assert_eq!(posts, 1);
+}
+
+fn event_position(event_ticker: &str, event_exposure: Cents) -> EventPosition {
+    EventPosition {
+        event_exposure,
+        event_ticker: event_ticker.to_string(),
+        fees_paid: Cents::ZERO,
+        realized_pnl: Cents::ZERO,
+        resting_order_count: 0,
+        total_cost: event_exposure,
+    }
+}
+
+#[tokio::test]
+async fn fetches_positions_by_event() {
+    let mut state = MockState::default();
+    state.balance = Cents(10_000);
+    state.events.push(fixtures::event(EVENT_TICKER, "INXD"));
+    state.events.push(fixtures::event("OTHER", "OTHER"));
+    state
+        .markets
+        .push(fixtures::market(TICKER, EVENT_TICKER, 40, 42));
+    // In the event without sharing its ticker prefix, and the other way around.
+    state
+        .markets
+        .push(fixtures::market("ODD-1", EVENT_TICKER, 10, 12));
+    state
+        .markets
+        .push(fixtures::market("INXD-23APR03-X", "OTHER", 10, 12));
+    state
+        .market_positions
+        .push(fixtures::market_position(TICKER, 10, Cents(400)));
+    state
+        .market_positions
+        .push(fixtures::market_position("ODD-1", -5, Cents(50)));
+    state
+        .market_positions
+        .push(fixtures::market_position("INXD-23APR03-X", 7, Cents(70)));
+    state
+        .event_positions
+        .push(event_position(EVENT_TICKER, Cents(450)));
+    state
+        .event_positions
+        .push(event_position("OTHER", Cents(70)));
+    let server = MockServer::start(state).await.unwrap();
+    let token = server.state().token.clone();
+    let backend = KalshiClient::new(reqwest::Client::new(), token.clone());
+    server
+        .run(async {
+            let context = RiskContext::fetch_async(&backend, TICKER).await.unwrap();
+            assert_eq!(context.market_position, 10);
+            assert_eq!(context.event_position, 15);
+            assert_eq!(context.exposure, Cents(520));
+            assert_eq!(context.balance, Cents(10_000));
+        })
+        .await;
+    server.stop().await.unwrap();
+}
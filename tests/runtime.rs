@@ -0,0 +1,246 @@
+use chrono::Utc;
+use kalshi_api::client::KalshiClient;
+use kalshi_api::exchange::ExchangeStatus;
+use kalshi_api::market::Orderbook;
+use kalshi_api::mock::fixtures::price;
+use kalshi_api::mock::{fixtures, MockError, MockServer, MockState};
+use kalshi_api::money::Cents;
+use kalshi_api::portfolio::{CreateOrderRequest, Fill};
+use kalshi_api::runtime::{RuntimeConfig, StrategyRuntime};
+use kalshi_api::strategy::{Strategy, StrategyContext};
+use std::time::Duration;
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+const MARKET_TICKER: &str = "INXD-23APR03-B4100";
+
+#[derive(Default)]
+struct JoinBid {
+    book_updates: usize,
+    timers: usize,
+    statuses: Vec<ExchangeStatus>,
+    fills: Vec<String>,
+}
+
+impl Strategy for JoinBid {
+    fn on_book_update(&mut self, context: &mut StrategyContext, ticker: &str, _: &Orderbook) {
+        self.book_updates += 1;
+        if self.book_updates == 1 {
+            context.create_order(CreateOrderRequest {
+                action: "buy".to_string(),
+                count: 5,
+                side: "yes".to_string(),
+                ticker: ticker.to_string(),
+                type_: "limit".to_string(),
+                yes_price: Some(price(40)),
+                ..CreateOrderRequest::default()
+            });
+        }
+    }
+
+    fn on_timer(&mut self, _: &mut StrategyContext) {
+        self.timers += 1;
+    }
+
+    fn on_exchange_status_change(&mut self, _: &mut StrategyContext, status: &ExchangeStatus) {
+        self.statuses.push(status.clone());
+    }
+
+    fn on_fill(&mut self, _: &mut StrategyContext, fill: &Fill) {
+        self.fills.push(fill.trade_id.clone());
+    }
+}
+
+fn scripted_state() -> MockState {
+    let mut state = MockState::default();
+    state.balance = Cents(10_000);
+    state.events.push(fixtures::event(EVENT_TICKER, "INXD"));
+    state
+        .markets
+        .push(fixtures::market(MARKET_TICKER, EVENT_TICKER, 40, 42));
+    state.orderbooks.insert(
+        MARKET_TICKER.to_string(),
+        fixtures::orderbook(&[(40, 10)], &[(58, 5)]),
+    );
+    state
+}
+
+fn backend(token: &str) -> KalshiClient {
+    KalshiClient::new(reqwest::Client::new(), token.to_string())
+}
+
+fn config() -> RuntimeConfig {
+    RuntimeConfig {
+        tickers: vec![MARKET_TICKER.to_string()],
+        poll_interval: Duration::from_millis(50),
+        timer_interval: Duration::from_millis(50),
+        exchange_status_interval: Duration::from_millis(50),
+        ..RuntimeConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn runs_strategy_and_cancels_resting_orders_on_shutdown() {
+    let state = scripted_state();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+
+    let runtime = StrategyRuntime::new(backend(&token), JoinBid::default(), config());
+    let strategy = server
+        .run(runtime.run(tokio::time::sleep(Duration::from_millis(400))))
+        .await
+        .unwrap();
+
+    assert!(strategy.book_updates > 1);
+    assert!(strategy.timers > 0);
+    // The status never changes, so it is only delivered once.
+    assert_eq!(strategy.statuses.len(), 1);
+
+    let state = server.stop().await.unwrap();
+    assert_eq!(state.orders.len(), 1);
+    assert_eq!(state.orders[0].status, "canceled");
+    assert_eq!(state.orders[0].remaining_count, 0);
+}
+
+#[tokio::test]
+async fn cancels_resting_orders_when_trading_halts() {
+    let state = scripted_state();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+
+    let runtime = StrategyRuntime::new(backend(&token), JoinBid::default(), config());
+    let halt = async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(server.state().orders[0].status, "resting");
+        server.state().exchange_status.trading_active = false;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // Cancelled by the halt, well before shutdown.
+        assert_eq!(server.state().orders[0].status, "canceled");
+    };
+    let (strategy, _) = tokio::join!(
+        server.run(runtime.run(tokio::time::sleep(Duration::from_millis(600)))),
+        halt
+    );
+
+    let strategy = strategy.unwrap();
+    assert_eq!(strategy.statuses.len(), 2);
+    assert!(!strategy.statuses[1].trading_active);
+    server.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn delivers_every_fill_once_across_pages_and_polls() {
+    let state = scripted_state();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+
+    let runtime = StrategyRuntime::new(backend(&token), JoinBid::default(), config());
+    let fill = async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let mut state = server.state();
+        let order_id = state.orders[0].order_id.clone();
+        // More than one page of fills, plus one for an order the runtime didn't place.
+        for i in 0..150 {
+            let mut fill = fixtures::fill(
+                &format!("trade-{}", i),
+                &order_id,
+                MARKET_TICKER,
+                "yes",
+                price(40),
+                1,
+            );
+            fill.created_time = Utc::now();
+            state.fills.push(fill);
+        }
+        let mut other = fixtures::fill(
+            "trade-other",
+            "order-other",
+            MARKET_TICKER,
+            "yes",
+            price(40),
+            1,
+        );
+        other.created_time = Utc::now();
+        state.fills.push(other);
+    };
+    let (strategy, _) = tokio::join!(
+        server.run(runtime.run(tokio::time::sleep(Duration::from_millis(600)))),
+        fill
+    );
+
+    let strategy = strategy.unwrap();
+    assert_eq!(strategy.fills.len(), 150);
+    assert_eq!(strategy.fills[0], "trade-0");
+    let state = server.stop().await.unwrap();
+    let fill_pages = state
+        .requests()
+        .iter()
+        .filter(|request| request.path.contains("portfolio/fills"))
+        .filter(|request| request.query.contains_key("cursor"))
+        .count();
+    assert!(fill_pages > 0);
+}
+
+#[tokio::test]
+async fn cancels_individually_when_the_batch_cancel_fails() {
+    let state = scripted_state();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    server
+        .state()
+        .fail_next("portfolio/orders/batched", MockError::InternalServerError);
+
+    let runtime = StrategyRuntime::new(backend(&token), JoinBid::default(), config());
+    server
+        .run(runtime.run(tokio::time::sleep(Duration::from_millis(300))))
+        .await
+        .unwrap();
+
+    let state = server.stop().await.unwrap();
+    assert_eq!(state.orders[0].status, "canceled");
+    let order_path = format!("portfolio/orders/{}", state.orders[0].order_id);
+    assert!(state
+        .requests()
+        .iter()
+        .any(|request| request.method == "DELETE" && request.path.ends_with(&order_path)));
+}
+
+#[tokio::test]
+async fn retries_failed_cancels_while_trading_is_halted() {
+    let state = scripted_state();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+
+    // Only the first poll runs, so nothing but the cancels touches the order.
+    let config = RuntimeConfig {
+        poll_interval: Duration::from_secs(60),
+        ..config()
+    };
+    let runtime = StrategyRuntime::new(backend(&token), JoinBid::default(), config);
+    let halt = async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        {
+            let mut state = server.state();
+            let order_path = format!("portfolio/orders/{}", state.orders[0].order_id);
+            state.fail_next("portfolio/orders/batched", MockError::InternalServerError);
+            state.fail_next(&order_path, MockError::InternalServerError);
+            state.exchange_status.trading_active = false;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(server.state().orders[0].status, "canceled");
+    };
+    let (strategy, _) = tokio::join!(
+        server.run(runtime.run(tokio::time::sleep(Duration::from_millis(600)))),
+        halt
+    );
+
+    // The halt is delivered even though the first cancel failed.
+    let strategy = strategy.unwrap();
+    assert_eq!(strategy.statuses.len(), 2);
+    let state = server.stop().await.unwrap();
+    let batch_cancels = state
+        .requests()
+        .iter()
+        .filter(|request| request.method == "DELETE" && request.path.contains("batched"))
+        .count();
+    assert_eq!(batch_cancels, 2);
+}
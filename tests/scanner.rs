@@ -0,0 +1,123 @@
+use chrono::Duration;
+use kalshi_api::client::KalshiClient;
+use kalshi_api::market::Market;
+use kalshi_api::mock::{fixtures, MockServer, MockState};
+use kalshi_api::scanner::{self, Comparison, MarketField, MarketFilter, Ranking, ScanConfig};
+
+const EVENT_TICKER: &str = "INXD-23APR03";
+
+fn market(ticker: &str, yes_bid: i64, yes_ask: i64, volume_24h: i32, category: &str) -> Market {
+    let mut market = fixtures::market(ticker, EVENT_TICKER, yes_bid, yes_ask);
+    market.volume_24h = volume_24h;
+    market.category = category.to_string();
+    market
+}
+
+fn markets() -> Vec<Market> {
+    vec![
+        market("TIGHT", 40, 42, 500, "Economics"),
+        market("WIDE", 30, 45, 2_000, "Economics"),
+        market("BUSY", 60, 61, 5_000, "Climate"),
+        market("QUIET", 10, 11, 10, "Economics"),
+    ]
+}
+
+fn tickers(results: &[scanner::ScanResult]) -> Vec<&str> {
+    results
+        .iter()
+        .map(|result| result.market.ticker.as_str())
+        .collect()
+}
+
+#[test]
+fn parses_filter_and_ranking_expressions() {
+    assert_eq!(
+        "spread <= 3".parse::<MarketFilter>().unwrap(),
+        MarketFilter::Compare {
+            field: MarketField::Spread,
+            comparison: Comparison::LessOrEqual,
+            value: 3.0,
+        }
+    );
+    assert_eq!(
+        "category!=Sports".parse::<MarketFilter>().unwrap(),
+        MarketFilter::Category {
+            category: "Sports".to_string(),
+            equal: false,
+        }
+    );
+    assert!("category>Sports".parse::<MarketFilter>().is_err());
+    assert!("spreadiness<3".parse::<MarketFilter>().is_err());
+    assert!("volume_24h".parse::<MarketFilter>().is_err());
+
+    assert_eq!(
+        "hours_to_close:asc".parse::<Ranking>().unwrap(),
+        Ranking {
+            field: MarketField::HoursToClose,
+            descending: false,
+        }
+    );
+    assert!("volume:up".parse::<Ranking>().is_err());
+}
+
+#[test]
+fn filters_and_ranks_markets() {
+    let config = ScanConfig {
+        filters: vec![
+            "spread<=2".parse().unwrap(),
+            "category=economics".parse().unwrap(),
+            "hours_to_close>12".parse().unwrap(),
+        ],
+        ranking: "volume_24h".parse().unwrap(),
+        ..ScanConfig::default()
+    };
+    let results = scanner::scan(markets(), &config, fixtures::fixture_time());
+    assert_eq!(tickers(&results), ["TIGHT", "QUIET"]);
+    assert_eq!(results[0].score, 500.0);
+
+    // Every market closes a day after the fixture time.
+    let config = ScanConfig {
+        filters: vec!["hours_to_close<12".parse().unwrap()],
+        ..ScanConfig::default()
+    };
+    let later = fixtures::fixture_time() + Duration::hours(13);
+    assert_eq!(scanner::scan(markets(), &config, later).len(), 4);
+
+    let config = ScanConfig {
+        ranking: "spread:asc".parse().unwrap(),
+        top_n: 3,
+        ..ScanConfig::default()
+    };
+    let results = scanner::scan(markets(), &config, fixtures::fixture_time());
+    // Ties on spread are broken by ticker.
+    assert_eq!(tickers(&results), ["BUSY", "QUIET", "TIGHT"]);
+}
+
+#[tokio::test]
+async fn pages_through_every_market() {
+    let mut state = MockState::default();
+    state.events.push(fixtures::event(EVENT_TICKER, "INXD"));
+    state.markets = markets();
+    let token = state.token.clone();
+    let server = MockServer::start(state).await.unwrap();
+    let backend = KalshiClient::new(reqwest::Client::new(), token.clone());
+
+    let config = ScanConfig {
+        page_size: 1,
+        top_n: 2,
+        ..ScanConfig::default()
+    };
+    let results = server
+        .run(scanner::scan_markets_async(&backend, &config))
+        .await
+        .unwrap();
+    assert_eq!(tickers(&results), ["BUSY", "WIDE"]);
+
+    let state = server.stop().await.unwrap();
+    let market_requests = state
+        .requests()
+        .iter()
+        .filter(|request| request.path.trim_end_matches('/').ends_with("markets"))
+        .count();
+    assert_eq!(market_requests, 4);
+}